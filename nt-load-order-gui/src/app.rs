@@ -1,27 +1,47 @@
 // Copyright 2025 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::{mem, ptr};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use std::{mem, ptr, slice};
 
 use lazy_static::lazy_static;
 use native_windows_derive as nwd;
 use native_windows_gui as nwg;
 
-use nt_load_order::NtLoadOrder;
+use nt_load_order::{
+    diff_load_orders, LoadOrderChange, LoadOrderNode, NtLoadOrder, NtLoadOrderEntry,
+    NtLoadOrderEntryKind,
+};
 use nwd::{NwgPartial, NwgUi};
 use nwg::stretch::geometry::{Rect, Size};
-use nwg::stretch::style::Dimension;
+use nwg::stretch::style::{Dimension, Style};
 use nwg::{CheckBox, CheckBoxState, EmbedResource, Icon, RadioButtonState};
 use raw_cpuid::CpuId;
 use winapi::shared::basetsd::UINT_PTR;
 use winapi::shared::minwindef::LOWORD;
+use winapi::shared::windef::COLORREF;
 use winapi::shared::windef::{POINT, RECT};
-use winapi::um::commctrl::LVSCW_AUTOSIZE;
+use winapi::um::commctrl::{
+    CDDS_ITEMPREPAINT, CDDS_PREPAINT, CDRF_DODEFAULT, CDRF_NOTIFYITEMDRAW, LVIF_TEXT,
+    LVM_SETEXTENDEDLISTVIEWSTYLE, LVN_GETDISPINFOW, LVN_GETINFOTIPW, LVSCW_AUTOSIZE,
+    LVS_EX_INFOTIP, NMLVCUSTOMDRAW, NMLVDISPINFOW, NMLVGETINFOTIPW, NM_CUSTOMDRAW,
+};
+use winapi::um::shellapi::ShellExecuteW;
+use winapi::um::wingdi::RGB;
 use winapi::um::winuser::{
-    GetParent, GetWindowRect, ScreenToClient, SetWindowPos, SWP_NOZORDER, WM_SIZE,
+    DispatchMessageW, GetAncestor, GetMessageW, GetParent, GetWindowRect, IsDialogMessageW,
+    ScreenToClient, SendMessageW, SetWindowPos, TranslateMessage, GA_ROOT, MSG, NMHDR,
+    SWP_NOZORDER, SW_SHOWNORMAL, VK_F5, WM_KEYDOWN, WM_NOTIFY, WM_SIZE,
 };
 
 use crate::linklabel::{build_link_label_font, hook_link_label_style};
+use crate::virtuallistview::VirtualListView;
 use crate::{dpi_adjust_size, FONT_SIZE};
 
 const APP_TITLE: &str = "nt-load-order-gui";
@@ -33,6 +53,7 @@ const WINDOW_TITLE: &str = concat!(
 
 const PT_0: Dimension = Dimension::Points(0.0);
 const PT_10: Dimension = Dimension::Points(10.0);
+const PT_22: Dimension = Dimension::Points(22.0);
 
 const MARGIN_0: Rect<Dimension> = Rect {
     start: PT_0,
@@ -48,6 +69,14 @@ const MARGIN_10: Rect<Dimension> = Rect {
     bottom: PT_10,
 };
 
+// Leaves room for `status_bar`, which positions itself at the bottom of the window.
+const PADDING_STATUS_BAR: Rect<Dimension> = Rect {
+    start: PT_0,
+    end: PT_0,
+    top: PT_0,
+    bottom: PT_22,
+};
+
 lazy_static! {
     static ref CPU_VENDOR: Option<String> = CpuId::new()
         .get_vendor_info()
@@ -60,12 +89,19 @@ pub struct App {
     #[nwg_events(OnMinMaxInfo: [App::on_min_max_info(SELF, EVT_DATA)], OnWindowClose: [App::on_close])]
     window: nwg::Window,
 
-    #[nwg_layout(parent: window, flex_direction: nwg::stretch::style::FlexDirection::Column)]
+    #[nwg_control(parent: window, text: "&View")]
+    view_menu: nwg::Menu,
+
+    #[nwg_control(parent: view_menu, text: "&Refresh\tF5")]
+    #[nwg_events(OnMenuItemSelected: [App::update_load_order])]
+    refresh_menu_item: nwg::MenuItem,
+
+    #[nwg_layout(parent: window, flex_direction: nwg::stretch::style::FlexDirection::Column, padding: PADDING_STATUS_BAR)]
     layout: nwg::FlexboxLayout,
 
     #[nwg_control(flags: "VISIBLE")]
     #[nwg_layout_item(layout: layout, margin: MARGIN_0,
-        size: Size { width: Dimension::Auto, height: Dimension::Points(180.0) }
+        size: Size { width: Dimension::Auto, height: Dimension::Points(210.0) }
     )]
     frame: nwg::Frame,
 
@@ -74,17 +110,40 @@ pub struct App {
         (source_ui.local_system_root_option, OnButtonClick): [App::on_local_system_root_option_click],
         (source_ui.custom_system_root_option, OnButtonClick): [App::on_custom_system_root_option_click],
         (source_ui.custom_system_root_path, OnMousePress): [App::on_custom_system_root_path_press(SELF, EVT)],
+        (source_ui.compare_with_local_system, OnButtonClick): [App::update_load_order],
         (steps_ui.sort_by_tag_and_group, OnButtonClick): [App::update_load_order],
         (steps_ui.sort_by_hardcoded_groups, OnButtonClick): [App::update_load_order],
         (steps_ui.sort_by_hardcoded_service_lists, OnButtonClick): [App::update_load_order],
         (steps_ui.add_kernel_binaries, OnButtonClick): [App::update_load_order],
         (steps_ui.add_imports, OnButtonClick): [App::update_load_order],
+        (steps_ui.show_import_tree, OnButtonClick): [App::on_show_import_tree_click],
     )]
     frames: FramesPartial,
 
     #[nwg_control(list_style: nwg::ListViewStyle::Detailed, ex_flags: nwg::ListViewExFlags::FULL_ROW_SELECT)]
     #[nwg_layout_item(layout: layout, margin: MARGIN_10, flex_grow: 1.0)]
-    list: nwg::ListView,
+    #[nwg_events(OnListViewRightClick: [App::on_list_right_click])]
+    list: VirtualListView,
+
+    // The entries displayed by `list`, whose cells are only formatted when the list view requests them.
+    list_entries: Rc<RefCell<Vec<NtLoadOrderEntry>>>,
+
+    // How every entry of `list_entries` differs from the local system, if "Compare with the Local System" is checked.
+    list_changes: Rc<RefCell<Vec<LoadOrderChange>>>,
+
+    // Only added to the layout (in place of `list`) when "Show Imports as Tree" is checked.
+    #[nwg_control(flags: "TAB_STOP")]
+    tree: nwg::TreeView,
+
+    #[nwg_control(parent: window)]
+    status_bar: nwg::StatusBar,
+
+    #[nwg_control(parent: window, popup: true)]
+    list_menu: nwg::Menu,
+
+    #[nwg_control(parent: list_menu, text: "Open containing folder")]
+    #[nwg_events(OnMenuItemSelected: [App::on_open_containing_folder_click])]
+    open_containing_folder_menu_item: nwg::MenuItem,
 
     #[nwg_resource(title: "Select Custom System Root", action: nwg::FileDialogAction::OpenDirectory)]
     select_custom_system_root_dialog: nwg::FileDialog,
@@ -113,7 +172,7 @@ pub struct FramesPartial {
 #[derive(Default, NwgPartial)]
 pub struct SourceFramePartial {
     // Add an extra row for the `custom_system_root_path` label, even though we later position it ourselves.
-    #[nwg_layout(max_row: Some(3))]
+    #[nwg_layout(max_row: Some(4))]
     grid: nwg::GridLayout,
 
     #[nwg_control(text: "Use the Local System Root", check_state: nwg::RadioButtonState::Checked)]
@@ -126,6 +185,10 @@ pub struct SourceFramePartial {
 
     #[nwg_control(flags: "NONE")]
     custom_system_root_path: nwg::Label,
+
+    #[nwg_control(text: "Compare with the Local System")]
+    #[nwg_layout_item(layout: grid, row: 3, col: 0)]
+    compare_with_local_system: nwg::CheckBox,
 }
 
 #[derive(Default, NwgPartial)]
@@ -152,6 +215,10 @@ pub struct StepsFramePartial {
     #[nwg_control(text: "Add Imports", check_state: nwg::CheckBoxState::Checked)]
     #[nwg_layout_item(layout: grid, row: 4, col: 0)]
     add_imports: nwg::CheckBox,
+
+    #[nwg_control(text: "Show Imports as Tree")]
+    #[nwg_layout_item(layout: grid, row: 5, col: 0)]
+    show_import_tree: nwg::CheckBox,
 }
 
 impl App {
@@ -169,6 +236,9 @@ impl App {
             .set_font(Some(&link_label_font));
         hook_link_label_style(&self.frames.source_ui.custom_system_root_path);
         self.hook_custom_system_root_path_link_label_position();
+        self.hook_list_display_info();
+        self.hook_list_info_tip();
+        self.hook_list_custom_draw();
 
         self.list.set_redraw(false);
 
@@ -179,18 +249,47 @@ impl App {
         self.list.insert_column("Service");
         self.list.insert_column("Image Path");
         self.list.insert_column("Reason");
+        self.list.insert_column("Critical");
 
         // Add initial data to the list.
         self.update_load_order_inner();
 
         // Auto-size the list columns based on the data.
-        for i in 0..5 {
+        for i in 0..6 {
             self.list.set_column_width(i, LVSCW_AUTOSIZE as isize);
         }
 
         self.list.set_redraw(true);
     }
 
+    /// Runs the message loop like `nwg::dispatch_thread_events`, but additionally refreshes the load order
+    /// when F5 is pressed anywhere in the main window.
+    ///
+    /// native-windows-gui does not support accelerator tables, and key presses only reach the focused control.
+    pub fn dispatch_thread_events(&self) {
+        let window_hwnd = self.window.handle.hwnd().unwrap();
+
+        unsafe {
+            let mut msg: MSG = mem::zeroed();
+            while GetMessageW(&mut msg, ptr::null_mut(), 0, 0) != 0 {
+                let root_hwnd = GetAncestor(msg.hwnd, GA_ROOT);
+
+                if msg.message == WM_KEYDOWN
+                    && msg.wParam == VK_F5 as usize
+                    && root_hwnd == window_hwnd
+                {
+                    self.update_load_order();
+                    continue;
+                }
+
+                if IsDialogMessageW(root_hwnd, &mut msg) == 0 {
+                    TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+            }
+        }
+    }
+
     /// Hooks the `WM_SIZE` message to position the `custom_system_root_path` link label below
     /// `custom_system_root_option`.
     fn hook_custom_system_root_path_link_label_position(&self) {
@@ -238,6 +337,118 @@ impl App {
         .unwrap();
     }
 
+    /// Hooks the `NM_CUSTOMDRAW` notification to color the `list` rows by their difference to the local system.
+    fn hook_list_custom_draw(&self) {
+        const HANDLER_ID: UINT_PTR = 0x20004;
+
+        let list_hwnd = self.list.handle.hwnd().unwrap();
+        let list_changes = Rc::clone(&self.list_changes);
+
+        nwg::bind_raw_event_handler(&self.window.handle, HANDLER_ID, move |_hwnd, msg, _w, l| {
+            if msg == WM_NOTIFY {
+                let nmhdr = unsafe { &*(l as *const NMHDR) };
+
+                if nmhdr.hwndFrom == list_hwnd && nmhdr.code == NM_CUSTOMDRAW {
+                    let custom_draw = unsafe { &mut *(l as *mut NMLVCUSTOMDRAW) };
+
+                    match custom_draw.nmcd.dwDrawStage {
+                        CDDS_PREPAINT => return Some(CDRF_NOTIFYITEMDRAW),
+                        CDDS_ITEMPREPAINT => {
+                            let list_changes = list_changes.borrow();
+                            let change = list_changes.get(custom_draw.nmcd.dwItemSpec);
+                            if let Some(color) = change.and_then(|change| change_color(*change)) {
+                                custom_draw.clrTextBk = color;
+                            }
+
+                            return Some(CDRF_DODEFAULT);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+
+            None
+        })
+        .unwrap();
+    }
+
+    /// Hooks the `LVN_GETDISPINFOW` notification to provide the cell texts of the virtual `list` on demand.
+    fn hook_list_display_info(&self) {
+        const HANDLER_ID: UINT_PTR = 0x20002;
+
+        let list_hwnd = self.list.handle.hwnd().unwrap();
+        let list_entries = Rc::clone(&self.list_entries);
+
+        nwg::bind_raw_event_handler(&self.window.handle, HANDLER_ID, move |_hwnd, msg, _w, l| {
+            if msg == WM_NOTIFY {
+                let nmhdr = unsafe { &*(l as *const NMHDR) };
+
+                if nmhdr.hwndFrom == list_hwnd && nmhdr.code == LVN_GETDISPINFOW {
+                    let item = unsafe { &mut (*(l as *mut NMLVDISPINFOW)).item };
+
+                    if item.mask & LVIF_TEXT != 0 && !item.pszText.is_null() && item.cchTextMax > 0
+                    {
+                        if let Some(entry) = list_entries.borrow().get(item.iItem as usize) {
+                            let text = list_cell_text(entry, item.iSubItem);
+                            let buffer = unsafe {
+                                slice::from_raw_parts_mut(item.pszText, item.cchTextMax as usize)
+                            };
+                            copy_to_text_buffer(&text, buffer);
+                        }
+                    }
+                }
+            }
+
+            None
+        })
+        .unwrap();
+    }
+
+    /// Hooks the `LVN_GETINFOTIPW` notification to show the full reason of a `list` row in a tooltip.
+    ///
+    /// Reasons often get too long for the column, especially after annotations for hardcoded groups and lists.
+    fn hook_list_info_tip(&self) {
+        const HANDLER_ID: UINT_PTR = 0x20003;
+
+        let list_hwnd = self.list.handle.hwnd().unwrap();
+        let list_entries = Rc::clone(&self.list_entries);
+
+        // native-windows-gui has no flag for this extended style.
+        unsafe {
+            SendMessageW(
+                list_hwnd,
+                LVM_SETEXTENDEDLISTVIEWSTYLE,
+                LVS_EX_INFOTIP as usize,
+                LVS_EX_INFOTIP as isize,
+            );
+        }
+
+        nwg::bind_raw_event_handler(&self.window.handle, HANDLER_ID, move |_hwnd, msg, _w, l| {
+            if msg == WM_NOTIFY {
+                let nmhdr = unsafe { &*(l as *const NMHDR) };
+
+                if nmhdr.hwndFrom == list_hwnd && nmhdr.code == LVN_GETINFOTIPW {
+                    let info_tip = unsafe { &mut *(l as *mut NMLVGETINFOTIPW) };
+
+                    if !info_tip.pszText.is_null() && info_tip.cchTextMax > 0 {
+                        if let Some(entry) = list_entries.borrow().get(info_tip.iItem as usize) {
+                            let buffer = unsafe {
+                                slice::from_raw_parts_mut(
+                                    info_tip.pszText,
+                                    info_tip.cchTextMax as usize,
+                                )
+                            };
+                            copy_to_text_buffer(&entry.reason, buffer);
+                        }
+                    }
+                }
+            }
+
+            None
+        })
+        .unwrap();
+    }
+
     fn on_close(&self) {
         nwg::stop_thread_dispatch();
     }
@@ -258,6 +469,36 @@ impl App {
         }
     }
 
+    fn on_show_import_tree_click(&self) {
+        let show_import_tree = is_checked(&self.frames.steps_ui.show_import_tree);
+        let (old, new): (&dyn LayoutControl, &dyn LayoutControl) = if show_import_tree {
+            (&*self.list, &self.tree)
+        } else {
+            (&self.tree, &*self.list)
+        };
+
+        // Exchange the controls in the layout, giving the new one the same space as the old one.
+        let style = Style {
+            margin: MARGIN_10,
+            flex_grow: 1.0,
+            ..Default::default()
+        };
+        self.layout.remove_child(old.handle());
+        old.set_visible(false);
+        self.layout.add_child(new.handle(), style).unwrap();
+        new.set_visible(true);
+        self.layout.fit().unwrap();
+
+        self.update_load_order();
+    }
+
+    fn on_list_right_click(&self) {
+        if self.list.selected_item().is_some() {
+            let (x, y) = nwg::GlobalCursor::position();
+            self.list_menu.popup(x, y);
+        }
+    }
+
     fn on_local_system_root_option_click(&self) {
         self.frames
             .source_ui
@@ -272,6 +513,51 @@ impl App {
         data.set_min_size(600, 400);
     }
 
+    /// Opens an Explorer window with the image file of the selected list entry selected.
+    fn on_open_containing_folder_click(&self) {
+        let Some(index) = self.list.selected_item() else {
+            return;
+        };
+        let Some(entry) = self.list_entries.borrow().get(index).cloned() else {
+            return;
+        };
+
+        // Absolute image paths can only be opened as they are on the local system.
+        let path = match self.system_root() {
+            Some(system_root) => entry.resolved_path(&system_root, Some(&HashMap::new())),
+            None => {
+                let system_root = env::var("SystemRoot").unwrap_or_default();
+                entry.resolved_path(&system_root, None)
+            }
+        };
+        let path = match path {
+            Some(path) if path.exists() => path,
+            _ => {
+                let message = format!("Cannot find the image file \"{}\"", entry.image_path);
+                nwg::modal_error_message(&self.window, APP_TITLE, &message);
+                return;
+            }
+        };
+
+        let operation = to_wide_string(OsStr::new("open"));
+        let file = to_wide_string(OsStr::new("explorer.exe"));
+        let mut parameters = OsStr::new("/select,\"").to_os_string();
+        parameters.push(&path);
+        parameters.push("\"");
+        let parameters = to_wide_string(&parameters);
+
+        unsafe {
+            ShellExecuteW(
+                self.window.handle.hwnd().unwrap(),
+                operation.as_ptr(),
+                file.as_ptr(),
+                parameters.as_ptr(),
+                ptr::null(),
+                SW_SHOWNORMAL,
+            );
+        }
+    }
+
     fn revert_to_local_system_root(&self) {
         self.frames
             .source_ui
@@ -316,8 +602,22 @@ impl App {
         self.list.set_redraw(true);
     }
 
-    fn update_load_order_inner(&self) {
-        let system_root = if let RadioButtonState::Checked = self
+    fn insert_tree_nodes(&self, nodes: &[LoadOrderNode], parent: Option<&nwg::TreeItem>) {
+        for node in nodes {
+            let item =
+                self.tree
+                    .insert_item(&tree_item_text(&node.entry), parent, nwg::TreeInsert::Last);
+
+            if !node.children.is_empty() {
+                self.insert_tree_nodes(&node.children, Some(&item));
+                self.tree.set_expand_state(&item, nwg::ExpandState::Expand);
+            }
+        }
+    }
+
+    /// Returns the custom system root if one is selected, or `None` for the local system root.
+    fn system_root(&self) -> Option<String> {
+        if let RadioButtonState::Checked = self
             .frames
             .source_ui
             .custom_system_root_option
@@ -326,11 +626,12 @@ impl App {
             Some(self.frames.source_ui.custom_system_root_path.text())
         } else {
             None
-        };
-
-        self.list.clear();
+        }
+    }
 
-        let load_order = NtLoadOrder::new()
+    /// Returns an `NtLoadOrder` for the given system root with the steps selected in the UI.
+    fn new_load_order(&self, system_root: Option<String>) -> NtLoadOrder {
+        NtLoadOrder::new()
             .system_root(system_root)
             .cpu_vendor(CPU_VENDOR.clone())
             .sort_by_tag_and_group(is_checked(&self.frames.steps_ui.sort_by_tag_and_group))
@@ -339,28 +640,131 @@ impl App {
                 &self.frames.steps_ui.sort_by_hardcoded_service_lists,
             ))
             .add_kernel_binaries(is_checked(&self.frames.steps_ui.add_kernel_binaries))
-            .add_imports(is_checked(&self.frames.steps_ui.add_imports));
+            .add_imports(is_checked(&self.frames.steps_ui.add_imports))
+    }
+
+    fn update_load_order_inner(&self) {
+        let system_root = self.system_root();
+        let compare =
+            system_root.is_some() && is_checked(&self.frames.source_ui.compare_with_local_system);
 
-        let entries = match load_order.get() {
-            Ok(entries) => entries,
+        self.list_entries.borrow_mut().clear();
+        self.list_changes.borrow_mut().clear();
+        self.list.clear();
+        self.tree.clear();
+
+        let load_order = self.new_load_order(system_root);
+
+        self.status_bar.set_text(0, "");
+        let start_time = Instant::now();
+
+        if is_checked(&self.frames.steps_ui.show_import_tree) {
+            match load_order.get_tree() {
+                Ok(nodes) => {
+                    self.update_status_bar(
+                        nodes
+                            .iter()
+                            .flat_map(LoadOrderNode::iter)
+                            .map(|node| &node.entry),
+                        start_time.elapsed(),
+                    );
+                    self.insert_tree_nodes(&nodes, None);
+                }
+                Err(e) => {
+                    nwg::modal_error_message(&self.window, APP_TITLE, &e.to_string());
+                }
+            }
+
+            return;
+        }
+
+        let result = if compare {
+            self.new_load_order(None).get().and_then(|local_entries| {
+                let entries = load_order.get()?;
+                Ok(diff_load_orders(&local_entries, &entries)
+                    .into_iter()
+                    .map(|diff_entry| (diff_entry.entry, diff_entry.change))
+                    .unzip())
+            })
+        } else {
+            load_order.get().map(|entries| (entries, Vec::new()))
+        };
+
+        let (entries, changes): (Vec<NtLoadOrderEntry>, Vec<LoadOrderChange>) = match result {
+            Ok(result) => result,
             Err(e) => {
                 nwg::modal_error_message(&self.window, APP_TITLE, &e.to_string());
                 return;
             }
         };
 
+        // Removed entries are only shown for comparison and not counted.
+        let counted_entries = entries
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| changes.get(*index) != Some(&LoadOrderChange::Removed))
+            .map(|(_, entry)| entry);
+        self.update_status_bar(counted_entries, start_time.elapsed());
+
+        let item_count = entries.len() as u32;
+        *self.list_entries.borrow_mut() = entries;
+        *self.list_changes.borrow_mut() = changes;
+        self.list.set_item_count(item_count);
+    }
+
+    /// Shows the number of entries of each kind and the time it took to determine them.
+    fn update_status_bar<'a, I>(&self, entries: I, duration: Duration)
+    where
+        I: Iterator<Item = &'a NtLoadOrderEntry>,
+    {
+        let mut services = 0;
+        let mut imports = 0;
+        let mut kernel_binaries = 0;
+
         for entry in entries {
-            self.list.insert_items_row(
-                None,
-                &[
-                    format_option(entry.group.map(|group| group.display_name)),
-                    format_option(entry.tag),
-                    entry.name,
-                    entry.image_path,
-                    entry.reason,
-                ],
-            );
+            match entry.kind {
+                NtLoadOrderEntryKind::Service | NtLoadOrderEntryKind::BootFileSystem => {
+                    services += 1
+                }
+                NtLoadOrderEntryKind::Import | NtLoadOrderEntryKind::UnresolvedImport => {
+                    imports += 1
+                }
+                NtLoadOrderEntryKind::KernelBinary => kernel_binaries += 1,
+                NtLoadOrderEntryKind::BootExecute => (),
+            }
         }
+
+        let text = format!(
+            "{services} services, {imports} imports, {kernel_binaries} kernel binaries - computed in {} ms",
+            duration.as_millis()
+        );
+        self.status_bar.set_text(0, &text);
+    }
+}
+
+/// The controls that can be exchanged in the main layout.
+trait LayoutControl {
+    fn handle(&self) -> nwg::ControlHandle;
+    fn set_visible(&self, visible: bool);
+}
+
+impl LayoutControl for nwg::ListView {
+    fn handle(&self) -> nwg::ControlHandle {
+        self.handle
+    }
+
+    fn set_visible(&self, visible: bool) {
+        nwg::ListView::set_visible(self, visible)
+    }
+}
+
+impl LayoutControl for nwg::TreeView {
+    fn handle(&self) -> nwg::ControlHandle {
+        self.handle
+    }
+
+    fn set_visible(&self, visible: bool) {
+        nwg::TreeView::set_visible(self, visible)
     }
 }
 
@@ -374,6 +778,55 @@ where
     }
 }
 
+/// Returns the background color of a `list` row with the given difference to the local system.
+fn change_color(change: LoadOrderChange) -> Option<COLORREF> {
+    match change {
+        LoadOrderChange::Added => Some(RGB(200, 255, 200)),
+        LoadOrderChange::Removed => Some(RGB(255, 200, 200)),
+        LoadOrderChange::Moved => Some(RGB(255, 255, 180)),
+        LoadOrderChange::Unchanged => None,
+    }
+}
+
+/// Returns the text of a cell in the given column of `list`.
+fn list_cell_text(entry: &NtLoadOrderEntry, column: i32) -> String {
+    match column {
+        0 => format_option(
+            entry
+                .group
+                .as_ref()
+                .map(|group| group.display_name.as_str()),
+        ),
+        1 => format_option(entry.tag),
+        2 => entry.name.clone(),
+        3 => entry.image_path.clone(),
+        4 => entry.reason.clone(),
+        5 => if entry.critical { "Yes" } else { "" }.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Copies `text` into a buffer provided by the list view, truncating it if necessary.
+fn copy_to_text_buffer(text: &str, buffer: &mut [u16]) {
+    let max_length = buffer.len() - 1;
+    let mut length = 0;
+
+    for (dest, src) in buffer.iter_mut().zip(text.encode_utf16().take(max_length)) {
+        *dest = src;
+        length += 1;
+    }
+
+    buffer[length] = 0;
+}
+
+fn to_wide_string(string: &OsStr) -> Vec<u16> {
+    string.encode_wide().chain(Some(0u16)).collect()
+}
+
+fn tree_item_text(entry: &NtLoadOrderEntry) -> String {
+    format!("{} ({}) - {}", entry.name, entry.image_path, entry.reason)
+}
+
 fn is_checked(checkbox: &CheckBox) -> bool {
     matches!(checkbox.check_state(), CheckBoxState::Checked)
 }
@@ -18,6 +18,7 @@
 
 mod app;
 mod linklabel;
+mod virtuallistview;
 
 use muldiv::MulDiv;
 use native_windows_gui as nwg;
@@ -43,7 +44,7 @@ fn main() {
     let app = App::build_ui(Default::default()).expect("Failed to build UI");
     app.init();
 
-    nwg::dispatch_thread_events();
+    app.dispatch_thread_events();
 }
 
 /// Calculate the absolute size in pixels for the given size in pixels
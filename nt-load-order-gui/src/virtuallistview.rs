@@ -0,0 +1,69 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use native_windows_gui as nwg;
+use winapi::um::commctrl::LVS_OWNERDATA;
+
+/// A list view in virtual (owner-data) mode, which doesn't store any items itself.
+///
+/// Instead, the number of items is set via `set_item_count` and the text of every visible cell is requested
+/// from the parent window through the `LVN_GETDISPINFOW` notification.
+#[derive(Default)]
+pub struct VirtualListView {
+    list: nwg::ListView,
+}
+
+nwg::subclass_control!(VirtualListView, ListView, list);
+
+impl VirtualListView {
+    pub fn builder() -> VirtualListViewBuilder {
+        VirtualListViewBuilder {
+            ex_flags: None,
+            list_style: nwg::ListViewStyle::Detailed,
+            parent: None,
+        }
+    }
+}
+
+pub struct VirtualListViewBuilder {
+    ex_flags: Option<nwg::ListViewExFlags>,
+    list_style: nwg::ListViewStyle,
+    parent: Option<nwg::ControlHandle>,
+}
+
+impl VirtualListViewBuilder {
+    pub fn ex_flags(mut self, ex_flags: nwg::ListViewExFlags) -> Self {
+        self.ex_flags = Some(ex_flags);
+        self
+    }
+
+    pub fn list_style(mut self, list_style: nwg::ListViewStyle) -> Self {
+        self.list_style = list_style;
+        self
+    }
+
+    pub fn parent<C: Into<nwg::ControlHandle>>(mut self, parent: C) -> Self {
+        self.parent = Some(parent.into());
+        self
+    }
+
+    pub fn build(self, out: &mut VirtualListView) -> Result<(), nwg::NwgError> {
+        // `LVS_OWNERDATA` cannot be changed after creation and native-windows-gui has no flag for it.
+        let flags = nwg::ListView::default().flags() | LVS_OWNERDATA;
+        let flags = unsafe { nwg::ListViewFlags::from_bits_unchecked(flags) };
+
+        let mut builder = nwg::ListView::builder()
+            .flags(flags)
+            .list_style(self.list_style);
+
+        if let Some(ex_flags) = self.ex_flags {
+            builder = builder.ex_flags(ex_flags);
+        }
+
+        if let Some(parent) = self.parent {
+            builder = builder.parent(parent);
+        }
+
+        builder.build(&mut out.list)
+    }
+}
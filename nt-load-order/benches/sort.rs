@@ -0,0 +1,174 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Benchmarks for the sorting steps, run on synthetic service lists of varying sizes.
+//!
+//! Run with `cargo bench --features bench`.
+
+use std::collections::HashMap;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use dlv_list::VecList;
+use indexmap::IndexSet;
+use nt_load_order::bench::{
+    new_entry, sort_by_hardcoded_groups, sort_by_hardcoded_service_lists, sort_by_tag_and_group,
+    RegistryInfo,
+};
+use nt_load_order::{NtLoadOrderEntry, NtLoadOrderEntryGroup, NtLoadOrderEntryKind};
+
+/// Numbers of services and groups to benchmark with.
+const SIZES: &[(usize, usize)] = &[(100, 10), (500, 25), (2000, 50)];
+
+/// Group names hardcoded into the bootloader, which are mixed into the synthetic groups.
+const HARDCODED_GROUPS: &[&str] = &["Early-Launch", "Core Platform Extensions"];
+
+/// Some image paths of hardcoded service lists, which are mixed into the synthetic services.
+const HARDCODED_IMAGE_PATHS: &[&str] = &[
+    "system32\\drivers\\wdf01000.sys",
+    "system32\\drivers\\cng.sys",
+    "system32\\drivers\\acpi.sys",
+];
+
+/// A simple linear congruential generator to get reproducible pseudo-random input.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % bound
+    }
+}
+
+fn group_name(index: usize) -> String {
+    match HARDCODED_GROUPS.get(index) {
+        Some(name) => name.to_string(),
+        None => format!("Group {index}"),
+    }
+}
+
+fn synthetic_registry_info(service_count: usize, group_count: usize) -> RegistryInfo {
+    let mut lcg = Lcg(service_count as u64);
+    let tags_per_group = service_count / group_count + 1;
+
+    // Give every other group a GroupOrderList entry with shuffled tags.
+    // The remaining groups use their tags as indexes, like the "Core" group.
+    let mut groups = HashMap::new();
+    for group_index in (0..group_count).step_by(2) {
+        let mut tags = (1..=tags_per_group as u32).collect::<Vec<_>>();
+        for i in (1..tags.len()).rev() {
+            tags.swap(i, lcg.next(i + 1));
+        }
+
+        groups.insert(
+            group_name(group_index).to_ascii_lowercase(),
+            tags.into_iter().collect::<IndexSet<u32>>(),
+        );
+    }
+
+    let service_group_order = (0..group_count).map(group_name).collect();
+
+    let entries = (0..service_count)
+        .map(|i| {
+            // Leave some services without a group or tag.
+            let group = (lcg.next(10) != 0).then(|| {
+                let display_name = group_name(lcg.next(group_count));
+                let search_key = display_name.to_ascii_lowercase();
+                NtLoadOrderEntryGroup {
+                    display_name,
+                    search_key,
+                }
+            });
+            let tag = (lcg.next(5) != 0).then(|| lcg.next(tags_per_group) as u32 + 1);
+            let image_path = match HARDCODED_IMAGE_PATHS.get(i % 100) {
+                Some(image_path) => image_path.to_string(),
+                None => format!("system32\\drivers\\service{i}.sys"),
+            };
+
+            NtLoadOrderEntry {
+                group,
+                tag,
+                ..new_entry(
+                    format!("Service{i}"),
+                    image_path,
+                    "Boot Service".to_string(),
+                    NtLoadOrderEntryKind::Service,
+                )
+            }
+        })
+        .collect();
+
+    RegistryInfo {
+        entries,
+        groups,
+        service_group_order,
+    }
+}
+
+fn bench_sort_by_tag_and_group(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort_by_tag_and_group");
+
+    for &(service_count, group_count) in SIZES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(service_count),
+            &(service_count, group_count),
+            |b, &(service_count, group_count)| {
+                b.iter_batched(
+                    || synthetic_registry_info(service_count, group_count),
+                    |registry_info| sort_by_tag_and_group(registry_info, true),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_hardcoded_sort<F>(c: &mut Criterion, name: &str, sort: F)
+where
+    F: Fn(&mut VecList<NtLoadOrderEntry>),
+{
+    let mut group = c.benchmark_group(name);
+
+    for &(service_count, group_count) in SIZES {
+        let entries =
+            sort_by_tag_and_group(synthetic_registry_info(service_count, group_count), true);
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(service_count),
+            &entries,
+            |b, entries| {
+                b.iter_batched_ref(
+                    || entries.clone(),
+                    |entries| sort(entries),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_sort_by_hardcoded_groups(c: &mut Criterion) {
+    bench_hardcoded_sort(c, "sort_by_hardcoded_groups", sort_by_hardcoded_groups);
+}
+
+fn bench_sort_by_hardcoded_service_lists(c: &mut Criterion) {
+    bench_hardcoded_sort(
+        c,
+        "sort_by_hardcoded_service_lists",
+        sort_by_hardcoded_service_lists,
+    );
+}
+
+criterion_group!(
+    benches,
+    bench_sort_by_tag_and_group,
+    bench_sort_by_hardcoded_groups,
+    bench_sort_by_hardcoded_service_lists
+);
+criterion_main!(benches);
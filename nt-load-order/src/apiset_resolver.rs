@@ -0,0 +1,115 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Resolution of API Set imports (e.g. "api-ms-win-core-foo-l1-1-0.dll") to the DLLs implementing them.
+//!
+//! API Sets were introduced with Windows 7, along with the "apisetschema.dll" containing the API Set Map.
+//! Older Windows versions (and minimal images without that file) import every DLL by its file name,
+//! which is handled by the [`IdentityResolver`].
+
+use anyhow::{Context, Result};
+use nt_apiset::ApiSetMap;
+
+pub(crate) trait ApiSetResolver {
+    /// Returns the file name of the DLL to load for the passed import file name.
+    ///
+    /// If the file name is no API Set, it is returned unmodified.
+    /// If it is an API Set that is not available on this operating system, `None` is returned,
+    /// and the import is ignored like the PE loader does.
+    fn patch_dll_name(&self, dll_name: String) -> Result<Option<String>>;
+
+    /// Returns whether this resolver actually resolves API Sets.
+    fn resolves_api_sets(&self) -> bool;
+}
+
+/// Resolves API Sets via the API Set Map of an "apisetschema.dll".
+pub(crate) struct ApiSetMapResolver<'a> {
+    apiset_map: ApiSetMap<'a>,
+}
+
+impl<'a> ApiSetMapResolver<'a> {
+    pub(crate) fn new(apiset_map: ApiSetMap<'a>) -> Self {
+        Self { apiset_map }
+    }
+}
+
+impl ApiSetResolver for ApiSetMapResolver<'_> {
+    /// Looks up the passed import file name in the operating system's API Set Map.
+    ///
+    /// Among multiple value entries, the default one (with an empty name) is taken, otherwise the one with the
+    /// lexicographically smallest file name.
+    fn patch_dll_name(&self, dll_name: String) -> Result<Option<String>> {
+        if !is_apiset_name(&dll_name) {
+            // `dll_name` does not fulfill the requirements for having an API Set entry.
+            // So return the unmodified `dll_name`.
+            return Ok(Some(dll_name));
+        }
+
+        let lookup_name = dll_name.strip_suffix(".dll").unwrap();
+        let Some(Ok(namespace_entry)) = self.apiset_map.find_namespace_entry(lookup_name) else {
+            // Although `dll_name` has been validated as an API Set, it has no entry in the API Set Map.
+            // This indicates that the requested API Set import is not available on this operating system,
+            // and should be ignored.
+            // So return `None`.
+            return Ok(None);
+        };
+
+        let value_entries = namespace_entry.value_entries().with_context(|| {
+            format!("ApiSetNamespaceEntry::value_entries failed for \"{dll_name}\"")
+        })?;
+
+        // Select the value entry deterministically, independent of the order in the API Set Map:
+        // The default entry (with an empty name) is preferred, followed by the lexicographically smallest value.
+        let mut values = Vec::new();
+        for value_entry in value_entries {
+            let name = value_entry
+                .name()
+                .with_context(|| format!("ApiSetValueEntry::name failed for \"{dll_name}\""))?;
+            let value = value_entry
+                .value()
+                .with_context(|| format!("ApiSetValueEntry::value failed for \"{dll_name}\""))?;
+            values.push((!name.is_empty(), value.to_string_lossy()));
+        }
+
+        let Some((_, value)) = values.into_iter().min() else {
+            // Although `dll_name` has been validated as an API Set and has a namespace entry, it does not have
+            // a single value entry in the API Set Map.
+            // Return `None` like above.
+            return Ok(None);
+        };
+
+        if value.is_empty() {
+            // Although `dll_name` has been validated as an API Set and has a namespace entry with a value entry,
+            // that value entry is empty.
+            // Return `None` like above.
+            return Ok(None);
+        }
+
+        Ok(Some(value))
+    }
+
+    fn resolves_api_sets(&self) -> bool {
+        true
+    }
+}
+
+/// Passes all import file names through unmodified, for systems without API Sets.
+pub(crate) struct IdentityResolver;
+
+impl ApiSetResolver for IdentityResolver {
+    fn patch_dll_name(&self, dll_name: String) -> Result<Option<String>> {
+        Ok(Some(dll_name))
+    }
+
+    fn resolves_api_sets(&self) -> bool {
+        false
+    }
+}
+
+/// Returns whether the given import file name can have an API Set entry, which requires a lowercase ".dll"
+/// extension and an "api-" or "ext-" prefix.
+pub(crate) fn is_apiset_name(dll_name: &str) -> bool {
+    dll_name
+        .strip_suffix(".dll")
+        .is_some_and(|name| name.starts_with("api-") || name.starts_with("ext-"))
+}
@@ -0,0 +1,124 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::debug;
+
+use crate::registry::{RegistryKeyNode, RegistryWorker};
+use crate::{BcdBootEntry, SafeBootMode};
+
+/// GUID of the "{bootmgr}" object, which references the default boot entry.
+const BOOTMGR_GUID: &str = "{9dea862c-5cdd-4e70-acc1-f32b344d4795}";
+/// GUID of the "{dbgsettings}" object, which boot entries inherit their debugger settings from.
+const DBGSETTINGS_GUID: &str = "{4636856e-540f-4170-a130-a84776f4c654}";
+
+// BCD element types used here, as defined in the Windows SDK (`BcdBootMgrElementTypes` etc.).
+const BCD_BOOTMGR_OBJECT_DEFAULT_OBJECT: &str = "23000003";
+const BCD_LIBRARY_STRING_DESCRIPTION: &str = "12000004";
+const BCD_LIBRARY_INTEGER_DEBUGGER_TYPE: &str = "15000011";
+const BCD_OSLOADER_STRING_SYSTEM_ROOT: &str = "22000002";
+const BCD_OSLOADER_INTEGER_SAFE_BOOT: &str = "25000080";
+const BCD_OSLOADER_BOOLEAN_WINPE_MODE: &str = "26000022";
+const BCD_OSLOADER_BOOLEAN_KERNEL_DEBUGGER_ENABLED: &str = "260000A0";
+
+/// Reads the default boot entry of the BCD store at the given path.
+pub fn read_default_boot_entry(bcd_path: &Path) -> Result<BcdBootEntry> {
+    let registry_worker = RegistryWorker::new_target_hive_file(bcd_path)?;
+    let hive = registry_worker.hive()?;
+
+    let bootmgr = hive.key_node(&format!("Objects\\{BOOTMGR_GUID}"))?;
+    let default_guid = element(&bootmgr, BCD_BOOTMGR_OBJECT_DEFAULT_OBJECT)
+        .and_then(|element| element.value("Element"))
+        .and_then(|value| value.sz_data())
+        .context("The BCD store has no default boot entry")?;
+    debug!("Default boot entry of the BCD store is {default_guid}");
+
+    let os_loader = hive
+        .key_node(&format!("Objects\\{default_guid}"))
+        .with_context(|| format!("The default boot entry {default_guid} does not exist"))?;
+
+    let description = string_element(&os_loader, BCD_LIBRARY_STRING_DESCRIPTION);
+    let system_root = string_element(&os_loader, BCD_OSLOADER_STRING_SYSTEM_ROOT);
+
+    let safe_boot = integer_element(&os_loader, BCD_OSLOADER_INTEGER_SAFE_BOOT).map(|safe_boot| {
+        match safe_boot {
+            1 => SafeBootMode::Network,
+            // "Minimal" and "DsRepair" both load the drivers listed under "SafeBoot\Minimal".
+            _ => SafeBootMode::Minimal,
+        }
+    });
+
+    let winpe = boolean_element(&os_loader, BCD_OSLOADER_BOOLEAN_WINPE_MODE).unwrap_or(false);
+
+    let kd_driver = if boolean_element(&os_loader, BCD_OSLOADER_BOOLEAN_KERNEL_DEBUGGER_ENABLED)
+        .unwrap_or(false)
+    {
+        // The debugger type is usually inherited from the "{dbgsettings}" object.
+        let debugger_type = integer_element(&os_loader, BCD_LIBRARY_INTEGER_DEBUGGER_TYPE)
+            .or_else(|| {
+                let dbgsettings = hive
+                    .key_node(&format!("Objects\\{DBGSETTINGS_GUID}"))
+                    .ok()?;
+                integer_element(&dbgsettings, BCD_LIBRARY_INTEGER_DEBUGGER_TYPE)
+            })
+            .unwrap_or(0);
+        kd_driver_for_debugger_type(debugger_type).map(str::to_string)
+    } else {
+        None
+    };
+
+    Ok(BcdBootEntry {
+        guid: default_guid,
+        description,
+        system_root,
+        safe_boot,
+        kd_driver,
+        winpe,
+    })
+}
+
+fn element<'d, 'h>(
+    object: &RegistryKeyNode<'d, 'h>,
+    element_type: &str,
+) -> Result<RegistryKeyNode<'d, 'h>> {
+    object.subkey("Elements")?.subkey(element_type)
+}
+
+fn boolean_element(object: &RegistryKeyNode, element_type: &str) -> Option<bool> {
+    let data = element(object, element_type)
+        .and_then(|element| element.value("Element"))
+        .and_then(|value| value.binary_data())
+        .ok()?;
+    Some(data.iter().any(|byte| *byte != 0))
+}
+
+fn integer_element(object: &RegistryKeyNode, element_type: &str) -> Option<u64> {
+    let data = element(object, element_type)
+        .and_then(|element| element.value("Element"))
+        .and_then(|value| value.binary_data())
+        .ok()?;
+
+    let mut bytes = [0u8; 8];
+    let length = data.len().min(bytes.len());
+    bytes[..length].copy_from_slice(&data[..length]);
+    Some(u64::from_le_bytes(bytes))
+}
+
+fn string_element(object: &RegistryKeyNode, element_type: &str) -> Option<String> {
+    element(object, element_type)
+        .and_then(|element| element.value("Element"))
+        .and_then(|value| value.sz_data())
+        .ok()
+}
+
+fn kd_driver_for_debugger_type(debugger_type: u64) -> Option<&'static str> {
+    match debugger_type {
+        0 => Some("kdcom"),
+        1 => Some("kd1394"),
+        2 => Some("kdusb"),
+        3 => Some("kdnet"),
+        _ => None,
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::collections::HashMap;
+
+use crate::diff::longest_increasing_subsequence;
+use crate::NtLoadOrderEntry;
+
+/// Prefix of the lines starting a new boot in a boot log (every boot is appended to the same file).
+const SESSION_HEADER_PREFIX: &str = "Microsoft (R) Windows (R)";
+
+/// Line prefixes for loaded drivers, in the formats of current and older Windows versions.
+const LOADED_PREFIXES: &[&str] = &["Loaded driver ", "BOOTLOG_LOADED "];
+
+/// Line prefixes for drivers that failed to load, in the formats of current and older Windows versions.
+const NOT_LOADED_PREFIXES: &[&str] = &["Did not load driver ", "BOOTLOG_NOT_LOADED "];
+
+/// A predicted entry that has been loaded at a different position than predicted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BootLogMismatch {
+    pub entry: NtLoadOrderEntry,
+    /// Index of the entry in the predicted load order.
+    pub predicted_index: usize,
+    /// Index of the driver among all drivers loaded according to the boot log.
+    pub actual_index: usize,
+}
+
+/// The result of [`reconcile_with_boot_log`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReconcileReport {
+    /// Predicted entries that have been loaded, but not in the predicted order relative to the other ones.
+    ///
+    /// As few entries as possible are reported here, so loading a single driver too late doesn't report all
+    /// others as well.
+    pub out_of_order: Vec<BootLogMismatch>,
+    /// Predicted entries that the boot log reports as not loaded.
+    pub not_loaded: Vec<NtLoadOrderEntry>,
+    /// Predicted entries that don't appear in the boot log at all.
+    pub missing: Vec<NtLoadOrderEntry>,
+    /// Paths of drivers that have been loaded before the last predicted one, but were not predicted.
+    ///
+    /// Drivers loaded afterwards are not reported, because the boot log also lists the drivers loaded
+    /// by the kernel after the bootloader.
+    pub unexpected: Vec<String>,
+}
+
+impl ReconcileReport {
+    /// Returns whether the boot log confirms the predicted load order without any discrepancies.
+    pub fn is_consistent(&self) -> bool {
+        self.out_of_order.is_empty()
+            && self.not_loaded.is_empty()
+            && self.missing.is_empty()
+            && self.unexpected.is_empty()
+    }
+}
+
+struct BootLogLine {
+    path: String,
+    loaded: bool,
+}
+
+/// Compares a predicted load order against a Windows boot log ("ntbtlog.txt"), which lists the drivers
+/// in the order they have been loaded.
+///
+/// Only the last boot of the log is considered. Entries are matched by their image paths (ignoring a
+/// `\SystemRoot\` prefix) or, failing that, by their file names, all case-insensitively.
+///
+/// The boot log is usually stored in UTF-16, so it needs to be converted to a string first.
+pub fn reconcile_with_boot_log(entries: &[NtLoadOrderEntry], boot_log: &str) -> ReconcileReport {
+    let lines = parse_boot_log(boot_log);
+
+    let mut indexes_by_path = HashMap::new();
+    let mut indexes_by_file_name = HashMap::new();
+    for (index, line) in lines.iter().enumerate() {
+        let path = normalize_path(&line.path);
+        indexes_by_file_name
+            .entry(file_name(&path).to_string())
+            .or_insert(index);
+        indexes_by_path.entry(path).or_insert(index);
+    }
+
+    // Indexes of the loaded drivers among all loaded drivers.
+    let actual_indexes = lines
+        .iter()
+        .scan(0, |loaded_count, line| {
+            let actual_index = *loaded_count;
+            *loaded_count += usize::from(line.loaded);
+            Some(actual_index)
+        })
+        .collect::<Vec<_>>();
+
+    let mut report = ReconcileReport::default();
+    let mut matched_line_indexes = vec![false; lines.len()];
+    let mut loaded = Vec::new();
+
+    for (predicted_index, entry) in entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| !entry.image_path.is_empty())
+    {
+        let path = normalize_path(&entry.image_path);
+        let line_index = indexes_by_path
+            .get(&path)
+            .or_else(|| indexes_by_file_name.get(file_name(&path)))
+            .copied();
+
+        match line_index {
+            Some(line_index) => {
+                matched_line_indexes[line_index] = true;
+
+                if lines[line_index].loaded {
+                    loaded.push((predicted_index, actual_indexes[line_index]));
+                } else {
+                    report.not_loaded.push(entry.clone());
+                }
+            }
+            None => report.missing.push(entry.clone()),
+        }
+    }
+
+    // Drivers whose actual indexes form the longest increasing subsequence have been loaded in order.
+    let in_order_indexes = longest_increasing_subsequence(
+        &loaded
+            .iter()
+            .map(|(_, actual_index)| *actual_index)
+            .collect::<Vec<_>>(),
+    );
+    report.out_of_order = loaded
+        .into_iter()
+        .filter(|(_, actual_index)| !in_order_indexes.contains(actual_index))
+        .map(|(predicted_index, actual_index)| BootLogMismatch {
+            entry: entries[predicted_index].clone(),
+            predicted_index,
+            actual_index,
+        })
+        .collect();
+
+    if let Some(last_matched_index) = matched_line_indexes.iter().rposition(|&matched| matched) {
+        report.unexpected = lines[..last_matched_index]
+            .iter()
+            .zip(&matched_line_indexes)
+            .filter(|(line, &matched)| line.loaded && !matched)
+            .map(|(line, _)| line.path.clone())
+            .collect();
+    }
+
+    report
+}
+
+/// Returns the driver lines of the last boot in the boot log.
+fn parse_boot_log(boot_log: &str) -> Vec<BootLogLine> {
+    let mut lines = Vec::new();
+
+    for line in boot_log.lines() {
+        let line = line.trim_start_matches('\u{feff}').trim();
+
+        if line.starts_with(SESSION_HEADER_PREFIX) {
+            lines.clear();
+        } else if let Some(path) = strip_any_prefix(line, LOADED_PREFIXES) {
+            lines.push(BootLogLine {
+                path: path.to_string(),
+                loaded: true,
+            });
+        } else if let Some(path) = strip_any_prefix(line, NOT_LOADED_PREFIXES) {
+            lines.push(BootLogLine {
+                path: path.to_string(),
+                loaded: false,
+            });
+        }
+    }
+
+    lines
+}
+
+fn strip_any_prefix<'a>(line: &'a str, prefixes: &[&str]) -> Option<&'a str> {
+    prefixes
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+        .map(str::trim)
+}
+
+/// Lowercases an image path and strips the prefixes that refer to the system root.
+fn normalize_path(path: &str) -> String {
+    let path = path.to_ascii_lowercase();
+    let path = path.strip_prefix("\\??\\").unwrap_or(&path);
+    let path = path.strip_prefix("\\systemroot\\").unwrap_or(path);
+    path.to_string()
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('\\').next().unwrap_or(path)
+}
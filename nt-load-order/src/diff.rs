@@ -0,0 +1,161 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::collections::{HashMap, HashSet};
+
+use crate::NtLoadOrderEntry;
+
+/// How an entry differs between two load orders.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LoadOrderChange {
+    /// The entry only exists in the new load order.
+    Added,
+    /// The entry only exists in the old load order.
+    Removed,
+    /// The entry exists in both load orders, but its position relative to the other common entries has changed.
+    Moved,
+    /// The entry exists in both load orders at the same relative position.
+    Unchanged,
+}
+
+/// An entry of the result of [`diff_load_orders`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoadOrderDiffEntry {
+    /// The entry of the new load order, or of the old one if it has been removed.
+    pub entry: NtLoadOrderEntry,
+    pub change: LoadOrderChange,
+    /// Index of the entry in the old load order.
+    pub old_index: Option<usize>,
+    /// Index of the entry in the new load order.
+    pub new_index: Option<usize>,
+}
+
+/// Compares two load orders, matching their entries by name (case-insensitively).
+///
+/// The result contains all entries of `new` in their order, with every removed entry following the entry
+/// it came after in `old`.
+/// As few entries as possible are reported as [`LoadOrderChange::Moved`], so moving a single entry to the end
+/// doesn't mark all other entries as moved.
+pub fn diff_load_orders(
+    old: &[NtLoadOrderEntry],
+    new: &[NtLoadOrderEntry],
+) -> Vec<LoadOrderDiffEntry> {
+    let old_indexes = old
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| (entry.name.to_ascii_lowercase(), index))
+        .collect::<HashMap<_, _>>();
+    let new_names = new
+        .iter()
+        .map(|entry| entry.name.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+
+    // Common entries whose old indexes form the longest increasing subsequence keep their relative order.
+    let common_old_indexes = new_names
+        .iter()
+        .filter_map(|name| old_indexes.get(name).copied())
+        .collect::<Vec<_>>();
+    let unchanged_old_indexes = longest_increasing_subsequence(&common_old_indexes);
+
+    // Group the removed entries by the closest preceding entry that also exists in `new`.
+    let new_name_set = new_names.iter().collect::<HashSet<_>>();
+    let mut removed_after = HashMap::<Option<String>, Vec<usize>>::new();
+    let mut previous_name = None;
+
+    for (index, entry) in old.iter().enumerate() {
+        let name = entry.name.to_ascii_lowercase();
+        if new_name_set.contains(&name) {
+            previous_name = Some(name);
+        } else {
+            removed_after
+                .entry(previous_name.clone())
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut diff = Vec::with_capacity(new.len() + old.len());
+    let mut push_removed = |diff: &mut Vec<LoadOrderDiffEntry>, previous_name: Option<String>| {
+        for index in removed_after.remove(&previous_name).unwrap_or_default() {
+            diff.push(LoadOrderDiffEntry {
+                entry: old[index].clone(),
+                change: LoadOrderChange::Removed,
+                old_index: Some(index),
+                new_index: None,
+            });
+        }
+    };
+
+    push_removed(&mut diff, None);
+
+    for (new_index, (entry, name)) in new.iter().zip(new_names).enumerate() {
+        let old_index = old_indexes.get(&name).copied();
+        let change = match old_index {
+            Some(old_index) if unchanged_old_indexes.contains(&old_index) => {
+                LoadOrderChange::Unchanged
+            }
+            Some(_) => LoadOrderChange::Moved,
+            None => LoadOrderChange::Added,
+        };
+
+        diff.push(LoadOrderDiffEntry {
+            entry: entry.clone(),
+            change,
+            old_index,
+            new_index: Some(new_index),
+        });
+
+        if old_index.is_some() {
+            push_removed(&mut diff, Some(name));
+        }
+    }
+
+    diff
+}
+
+/// Returns the values of the longest strictly increasing subsequence of `values`.
+pub(crate) fn longest_increasing_subsequence(values: &[usize]) -> HashSet<usize> {
+    // `tails[i]` is the index of the smallest last value of all increasing subsequences of length `i + 1`.
+    let mut tails = Vec::<usize>::new();
+    let mut predecessors = vec![None; values.len()];
+
+    for (index, &value) in values.iter().enumerate() {
+        let length = tails.partition_point(|&tail| values[tail] < value);
+        if length > 0 {
+            predecessors[index] = Some(tails[length - 1]);
+        }
+
+        if length == tails.len() {
+            tails.push(index);
+        } else {
+            tails[length] = index;
+        }
+    }
+
+    let mut subsequence = HashSet::new();
+    let mut index = tails.last().copied();
+    while let Some(i) = index {
+        subsequence.insert(values[i]);
+        index = predecessors[i];
+    }
+
+    subsequence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_increasing_subsequence() {
+        assert_eq!(longest_increasing_subsequence(&[]), HashSet::new());
+        assert_eq!(
+            longest_increasing_subsequence(&[1, 2, 3, 0]),
+            HashSet::from([1, 2, 3])
+        );
+        assert_eq!(
+            longest_increasing_subsequence(&[3, 0, 1, 4, 2]),
+            HashSet::from([0, 1, 2])
+        );
+    }
+}
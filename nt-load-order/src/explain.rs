@@ -0,0 +1,116 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::NtLoadOrderEntry;
+
+/// The steps of [`NtLoadOrder::get`](crate::NtLoadOrder::get), in the order they are run.
+#[derive(Clone, Copy)]
+pub(crate) enum Step {
+    LoadFromRegistry,
+    SortByTagAndGroup,
+    SortByHardcodedGroups,
+    SortByHardcodedServiceLists,
+    PinServices,
+    AddKernelBinaries,
+    AddImports,
+}
+
+/// Collects the decisions of all steps that affected a single entry.
+pub(crate) struct Explanation<'a> {
+    name: &'a str,
+    lines: Vec<String>,
+}
+
+impl<'a> Explanation<'a> {
+    pub(crate) fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Records how the given step has affected the entry, if it is part of the entries after that step.
+    pub(crate) fn add_step<'e, I>(&mut self, step: Step, entries: I)
+    where
+        I: Iterator<Item = &'e NtLoadOrderEntry> + Clone,
+    {
+        let Some((index, entry)) = entries
+            .clone()
+            .enumerate()
+            .find(|(_, entry)| entry.name.eq_ignore_ascii_case(self.name))
+        else {
+            return;
+        };
+        let position = index + 1;
+
+        let line = match step {
+            Step::LoadFromRegistry => {
+                let group = match &entry.group {
+                    Some(group) => format!("group \"{}\"", group.display_name),
+                    None => "no group".to_string(),
+                };
+                let tag = match entry.tag {
+                    Some(tag) => format!("tag {tag}"),
+                    None => "no tag".to_string(),
+                };
+
+                format!(
+                    "Loaded from the registry at position {position} as \"{}\" with {group} and {tag}",
+                    entry.reason
+                )
+            }
+            Step::SortByTagAndGroup => {
+                format!("Sorted by tag and group to position {position}")
+            }
+            Step::SortByHardcodedGroups => match &entry.moved_by_hardcoded_group {
+                Some(group) => {
+                    format!("Moved to position {position} due to hardcoded \"{group}\" group")
+                }
+                None => format!("Not moved by hardcoded groups, now at position {position}"),
+            },
+            Step::SortByHardcodedServiceLists => match &entry.moved_by_hardcoded_list {
+                Some(list) => {
+                    format!("Moved to position {position} due to hardcoded \"{list}\" list")
+                }
+                None => {
+                    format!("Not moved by hardcoded service lists, now at position {position}")
+                }
+            },
+            Step::PinServices => {
+                if entry.pinned {
+                    format!("Pinned to position {position}")
+                } else {
+                    format!("Not pinned, now at position {position}")
+                }
+            }
+            Step::AddKernelBinaries => {
+                format!("Kernel binaries added, now at position {position}")
+            }
+            Step::AddImports => {
+                let imports = entries
+                    .filter(|import| {
+                        import.imported_by.as_deref() == Some(entry.image_path.as_str())
+                    })
+                    .map(|import| format!("\"{}\"", import.name))
+                    .collect::<Vec<_>>();
+
+                let mut line = format!(
+                    "Imports added, finally at position {position} as \"{}\"",
+                    entry.reason
+                );
+                if !imports.is_empty() {
+                    line += &format!(", imports {}", imports.join(", "));
+                }
+
+                line
+            }
+        };
+
+        self.lines.push(line);
+    }
+
+    /// Returns the recorded decisions, or `None` if the entry was not part of any step.
+    pub(crate) fn into_lines(self) -> Option<Vec<String>> {
+        (!self.lines.is_empty()).then_some(self.lines)
+    }
+}
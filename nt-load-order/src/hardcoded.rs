@@ -0,0 +1,37 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Groups and service lists hardcoded into the Windows bootloader.
+
+/// Groups that the bootloader puts first in this order, irrespective of the ServiceGroupOrder.
+pub const HARDCODED_GROUPS: &[&str] = &[
+    "Early-Launch",
+    "Core Platform Extensions",
+    "Core Security Extensions",
+];
+
+/// Named lists of image paths (relative to the system root, in lowercase) that the bootloader loads first
+/// in this order, irrespective of the group/tag sorting.
+/// These take precedence over the [`HARDCODED_GROUPS`].
+pub const HARDCODED_SERVICE_LISTS: &[(&str, &[&str])] = &[
+    (
+        "Core Driver Services",
+        &[
+            "system32\\drivers\\verifierext.sys",
+            "system32\\drivers\\wdf01000.sys",
+            "system32\\drivers\\acpiex.sys",
+            "system32\\drivers\\cng.sys",
+            "system32\\drivers\\mssecflt.sys",
+            "system32\\drivers\\sgrmagent.sys",
+            "system32\\drivers\\lxss.sys",
+            "system32\\drivers\\palcore.sys",
+        ],
+    ),
+    (
+        "TPM Core Driver Services",
+        &[
+            "system32\\drivers\\acpisim.sys",
+            "system32\\drivers\\acpi.sys",
+        ],
+    ),
+];
@@ -12,39 +12,153 @@
 //!
 //! * [Part 1: WinDbg'ing our way into the Windows bootloader](https://colinfinck.de/posts/nt-load-order-part-1/)
 //! * [Part 2: More than you ever wanted to know](https://colinfinck.de/posts/nt-load-order-part-2/)
+//!
+//! Every decision of the analysis is logged via the [log](https://crates.io/crates/log) crate at the
+//! `debug` and `trace` levels. Install any logger to follow it.
 
 #![doc(html_logo_url = "https://colinfinck.de/img/software/nt-load-order.svg")]
 
+mod apiset_resolver;
+mod bcd;
+mod boot_log;
+mod diff;
+mod explain;
+pub mod hardcoded;
+mod mermaid;
 mod registry;
+mod services;
 mod steps;
+mod tree;
+mod windbg;
+
+/// Internal sorting steps, exposed for the benchmarks in `benches/`.
+/// This is not part of the public API and may change at any time.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::steps::{
+        sort_by_hardcoded_groups, sort_by_hardcoded_service_lists, sort_by_tag_and_group,
+        RegistryInfo,
+    };
+
+    use crate::{NtLoadOrderEntry, NtLoadOrderEntryKind};
+
+    /// Returns an entry with the given properties, leaving all optional ones unset.
+    pub fn new_entry(
+        name: String,
+        image_path: String,
+        reason: String,
+        kind: NtLoadOrderEntryKind,
+    ) -> NtLoadOrderEntry {
+        NtLoadOrderEntry::new(name, image_path, reason, kind)
+    }
+}
+
+use std::collections::{HashMap, HashSet};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use dlv_list::VecList;
 
+use crate::explain::{Explanation, Step};
 use crate::registry::RegistryWorker;
+use crate::services::raw_services;
 use crate::steps::{
-    add_basic_kernel_binaries, add_imports, add_kernel_binary, load_from_registry,
-    sort_by_hardcoded_groups, sort_by_hardcoded_service_lists, sort_by_tag_and_group,
+    add_basic_kernel_binaries, add_imports, add_kernel_binary, apply_driver_verifier,
+    detect_kd_drivers, exclude_services, hardware_config_ids, import_closure,
+    load_apiset_schema_info, load_boot_execute, load_from_registry, pin_services,
+    resolve_image_path, sort_by_hardcoded_groups, sort_by_hardcoded_service_lists,
+    sort_by_tag_and_group, warn_about_excluded_imports, LoadFromRegistryOptions,
+    APISETSCHEMA_IMAGE_PATH,
 };
+use crate::tree::build_tree;
+
+pub use crate::boot_log::{reconcile_with_boot_log, BootLogMismatch, ReconcileReport};
+pub use crate::diff::{diff_load_orders, LoadOrderChange, LoadOrderDiffEntry};
+pub use crate::mermaid::to_mermaid;
+pub use crate::services::{RegistryValueData, ServiceRaw};
+pub use crate::steps::{GroupOrderMismatches, RegistryInfo};
+pub use crate::tree::LoadOrderNodes;
+pub use crate::windbg::to_windbg_modules;
+
+// Hardcoded for now, but will work for 99.9% of the cases :)
+const BOOT_FILE_SYSTEM: &str = "ntfs";
+const CONTROL_SET: u8 = 1;
+
+/// System root layers and drive mappings for opening image files.
+type ImportsSystemRoots = (Vec<String>, Option<HashMap<char, String>>);
 
 #[derive(Clone)]
 pub struct NtLoadOrder {
     /// Optional path to a target SystemRoot directory.
-    /// If not set, the running operating system is analyzed.
+    /// If not set, the running operating system is analyzed, which is only supported on Windows.
     ///
     /// Defaults to `None`.
     system_root: Option<String>,
-    /// Optional KD driver to load (e.g. "kdcom").
-    kd_driver: Option<String>,
+    /// File name of the SYSTEM hive in the "System32\config" directory of the target SystemRoot.
+    /// Set this to analyze a backup or recovery hive (e.g. "SYSTEM.SAV") instead.
+    ///
+    /// Defaults to `None`, which uses "SYSTEM".
+    system_hive_filename: Option<String>,
+    /// Whether to analyze the control set marked as current instead of "ControlSet001".
+    ///
+    /// On the running system, "CurrentControlSet" is opened directly and resolved by Windows.
+    /// For a target SystemRoot, which has no such link, the number is read from the "Current" value
+    /// of the "Select" key.
+    ///
+    /// Defaults to `false`.
+    current_control_set: bool,
+    /// Optional path to a SystemRoot directory to load the image files from, when they are not located
+    /// in the same directory as the SYSTEM hive (e.g. for a hive backed up separately from the installation
+    /// media).
+    /// The registry is still read from [`Self::system_root`] (or the running system if that is not set),
+    /// while imports and the "apisetschema.dll" are only looked up here.
+    /// [`Self::drive_mappings`] apply to the image paths resolved against this directory.
+    ///
+    /// Defaults to `None`, which loads the image files from the system root.
+    import_root: Option<String>,
+    /// Optional directories to load the image files from, for system roots made up of layers
+    /// (e.g. a base image with an overlay of serviced files, or an expanded container image).
+    /// Every file is taken from the first directory that has it.
+    /// This replaces [`Self::import_root`] and the system root for loading image files, and cannot be combined
+    /// with the former.
+    /// [`Self::drive_mappings`] apply to the image paths resolved against these directories.
+    ///
+    /// Defaults to an empty list, which loads the image files from the import root or system root.
+    import_roots: Vec<String>,
+    /// Mappings from drive letters to directories, used for resolving image paths that point to another
+    /// volume of a target system (e.g. "D:\drivers\foo.sys").
+    /// Only used when a target SystemRoot or import root is analyzed.
+    ///
+    /// Defaults to an empty map, which records such images as being outside of the system root.
+    drive_mappings: HashMap<char, String>,
+    /// KD drivers to load in this order (e.g. "kdcom").
+    ///
+    /// Defaults to `None`, which detects the KD driver from the "DEBUGPORT" option in the
+    /// "Control\SystemStartOptions" value of the registry, falling back to "kdcom".
+    kd_drivers: Option<Vec<String>>,
     /// Optional vendor string of the CPU to run the target operating system (e.g. "AuthenticAMD").
     /// If set, a matching "mcupdate_*.dll" binary will be added to the loaded kernel binaries.
     cpu_vendor: Option<String>,
+    /// Whether to detect the CPU vendor of the running system if no `cpu_vendor` is set.
+    /// This only happens when analyzing the local system.
+    ///
+    /// Defaults to `false`.
+    #[cfg(feature = "autodetect-cpu-vendor")]
+    autodetect_cpu_vendor: bool,
     /// Whether to sort the fetched services by their tags
     /// and groups based on the ServiceGroupOrder and
     /// GroupOrderList.
     ///
     /// Defaults to `true`.
     sort_by_tag_and_group: bool,
+    /// Whether to reverse the order of the service keys before sorting by tag and group, like the bootloader does.
+    /// Disabling this is only useful for isolating ordering bugs.
+    ///
+    /// Defaults to `true`.
+    reverse_registry_order: bool,
     /// Whether to sort the fetched services based on
     /// groups hardcoded into the bootloader
     /// (which precede all other groups).
@@ -65,42 +179,605 @@ pub struct NtLoadOrder {
     add_kernel_binaries: bool,
     /// Whether to add imports of modules in the load order.
     ///
+    /// This is the only step that needs to open any files besides the SYSTEM hive.
+    /// Disable it to determine the load order from the registry alone.
+    ///
     /// Defaults to `true`.
     add_imports: bool,
+    /// Whether a missing import file aborts the analysis.
+    ///
+    /// If disabled, such imports are added as [`NtLoadOrderEntryKind::UnresolvedImport`] placeholders
+    /// instead, named after the requested file and with an empty image path.
+    ///
+    /// Defaults to `true`.
+    strict_imports: bool,
+    /// Whether to add the imports of the kernel binaries.
+    ///
+    /// If disabled, the kernel binaries are still added at the beginning of the load order, but their files
+    /// are not opened at all.
+    /// Imports of services are added as usual, even if they are also imported by a kernel binary.
+    ///
+    /// Defaults to `true`.
+    expand_kernel_binary_imports: bool,
+    /// Whether the imports of the kernel binaries are added before those of the services.
+    ///
+    /// The kernel binaries always stay at the beginning of the load order.
+    /// If enabled, their imports directly follow them, and services imported by a kernel binary are moved up.
+    /// If disabled, the services and their imports come first, and only the kernel binary imports not loaded by
+    /// then are appended at the end.
+    ///
+    /// Defaults to `true`, which matches the bootloader loading the kernel binaries along with their imports
+    /// before any boot driver.
+    kernel_binary_imports_first: bool,
+    /// Whether to append the native applications of the "BootExecute" value of "Control\Session Manager"
+    /// (e.g. autochk) as [`NtLoadOrderEntryKind::BootExecute`] entries.
+    ///
+    /// These are no drivers and run in a different phase:
+    /// The Session Manager starts them after all boot drivers have been loaded, so they are always at the end.
+    /// Their imports are not added.
+    ///
+    /// Defaults to `false`.
+    include_boot_execute: bool,
+    /// Whether to drop imports that are user-mode images, keeping only kernel-mode images.
+    ///
+    /// An import is considered user-mode if its PE subsystem is known and not `IMAGE_SUBSYSTEM_NATIVE`
+    /// (e.g. a Windows GUI or console DLL pulled in via an API Set).
+    /// Kernel binaries and services are always kept, and so are imports whose file could not be analyzed.
+    /// This cannot be combined with [`include_boot_execute`](Self::include_boot_execute),
+    /// because those applications are user-mode.
+    ///
+    /// Defaults to `false`.
+    kernel_mode_only: bool,
+    /// Whether to analyze a WinPE or setup boot image, which may lack some keys of a regular installation.
+    /// The following keys become optional in this mode:
+    ///
+    /// * "HardwareConfig" (without it, "StartOverride" subkeys of services are ignored)
+    /// * "Control\ServiceGroupOrder" and "Control\GroupOrderList" (without them, services are not sorted by group)
+    /// * The service key of the boot file system
+    ///
+    /// If the requested control set does not exist, the one marked as current in the "Select" key is used.
+    ///
+    /// This mode is also enabled automatically if a "Control\MiniNT" key is found.
+    ///
+    /// Defaults to `false`.
+    winpe: bool,
+    /// Whether to fail on malformed service values instead of ignoring them.
+    ///
+    /// In strict mode, a "Start", "StartOverride" or "Tag" value of the wrong type is an error, and so is every
+    /// problem that would otherwise be reported in [`NtLoadOrderEntry::warnings`] while reading a service.
+    /// Keys that are missing altogether are errors anyway, except for those made optional by [`Self::winpe`].
+    ///
+    /// Defaults to `false`.
+    strict: bool,
+    /// Hardware configuration whose "StartOverride" values are applied
+    /// (see [`list_hardware_configs`](Self::list_hardware_configs)).
+    ///
+    /// Defaults to `None`, which uses the last booted one from the "HardwareConfig\LastId" value.
+    hardware_config_id: Option<u32>,
+    /// Whether to analyze the load order with Driver Verifier enabled.
+    ///
+    /// Driver Verifier makes the bootloader additionally load "verifierext.sys" as part of the hardcoded
+    /// "Core Driver Services" list, and verified boot drivers are annotated in their reason.
+    /// The positions of all other entries don't change.
+    ///
+    /// Defaults to `None`, which detects Driver Verifier from the "VerifyDrivers" value in
+    /// "Control\Session Manager\Memory Management".
+    driver_verifier: Option<bool>,
+    /// Names of services to leave out of the load order, for a what-if analysis without editing the registry.
+    ///
+    /// Names are compared case-insensitively and may contain the wildcards `*` and `?`.
+    /// An excluded service is still loaded if another image imports it, which is reported in the
+    /// [`NtLoadOrderEntry::warnings`] of that import.
+    ///
+    /// Defaults to an empty list.
+    exclude_services: Vec<String>,
+    /// Names of services to treat as boot-start regardless of their "Start" value, for a what-if analysis
+    /// of promoting a driver to boot-start.
+    ///
+    /// These services are placed like any other boot driver according to their group and tag.
+    /// Names are compared case-insensitively.
+    ///
+    /// Defaults to an empty list.
+    force_boot_services: Vec<String>,
+    /// Ordering tables to use instead of those read from the registry, for a what-if analysis.
+    /// [`get_registry_info`](Self::get_registry_info) still returns the tables of the registry.
+    ///
+    /// Defaults to `None`.
+    group_order_override: Option<GroupOrder>,
+    /// Names of services to pin directly after the kernel binaries, in the given order.
+    ///
+    /// Like the kernel binaries, pinned services are not moved by any sorting step.
+    /// Their imports are added after the imports of the kernel binaries (or after all other entries if
+    /// [`kernel_binary_imports_first`](Self::kernel_binary_imports_first) is `false`).
+    /// This is meant for a what-if analysis of a bootloader that loads certain drivers first.
+    /// Names are compared case-insensitively, and every name must be part of the load order.
+    ///
+    /// Defaults to an empty list.
+    pin_services: Vec<String>,
+    /// Safe Mode to analyze, if any.
+    /// Only boot drivers whose service name or group is listed under the corresponding "Control\SafeBoot"
+    /// subkey are kept then.
+    ///
+    /// Defaults to `None`.
+    safe_boot: Option<SafeBootMode>,
+    /// Whether to memory-map the SYSTEM hive of a target system root instead of reading it into memory.
+    /// This saves memory for large hives, but the hive file must not be modified during the analysis.
+    ///
+    /// Defaults to `false`.
+    #[cfg(feature = "mmap")]
+    memory_map_hive: bool,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct NtLoadOrderEntry {
     pub name: String,
     pub image_path: String,
     pub group: Option<NtLoadOrderEntryGroup>,
     pub tag: Option<u32>,
     pub reason: String,
-    /// The first few kernel binaries have fixed positions that don't move.
-    /// Mark them differently here.
-    pub is_kernel_binary: bool,
+    /// How this entry ended up in the load order.
+    pub kind: NtLoadOrderEntryKind,
+    /// Value of the "Type" of the service (e.g. 0x2 for `SERVICE_FILE_SYSTEM_DRIVER`).
+    /// Use [`service_type_name`](Self::service_type_name) to get a human-readable name.
+    ///
+    /// `None` for entries that are no services or services without a valid "Type" value.
+    pub service_type: Option<u32>,
+    /// Value of the "ErrorControl" of the service, which decides what happens if it fails to load:
+    ///
+    /// * 0 (`SERVICE_ERROR_IGNORE`): The failure is ignored.
+    /// * 1 (`SERVICE_ERROR_NORMAL`): The failure is logged and booting continues.
+    /// * 2 (`SERVICE_ERROR_SEVERE`): The last known good configuration is tried, otherwise booting continues.
+    /// * 3 (`SERVICE_ERROR_CRITICAL`): The last known good configuration is tried, otherwise the system bugchecks.
+    ///
+    /// `None` for entries that are no services or services without a valid "ErrorControl" value.
+    pub error_control: Option<u32>,
+    /// Whether the "ErrorControl" of the service is `SERVICE_ERROR_CRITICAL`,
+    /// so that a failure of this boot driver is catastrophic.
+    pub critical: bool,
+    /// Services that must be started before this one, as listed in its "DependOnService" value.
+    ///
+    /// This is only informational, because the bootloader doesn't consider dependencies.
+    /// Empty for entries that are no services or services without this value.
+    pub depend_on_service: Vec<String>,
+    /// Groups that must be started before this service, as listed in its "DependOnGroup" value.
+    ///
+    /// Like `depend_on_service`, this is only informational.
+    pub depend_on_group: Vec<String>,
+    /// Whether the image file has an embedded Authenticode signature (i.e. a security directory).
+    /// Catalog-signed images don't have one.
+    ///
+    /// This is only determined when adding imports, because that step opens the image files anyway.
+    /// It is `None` otherwise or if the image file could not be opened.
+    pub signed_embedded: Option<bool>,
+    /// Machine type from the PE file header of the image file (e.g. 0x8664 for x64).
+    /// Use [`machine_name`](Self::machine_name) to get a human-readable name.
+    ///
+    /// Hybrid images are detected and reported as ARM64X (0xa64e) or ARM64EC (0xa641), although their
+    /// file headers say ARM64 or x64.
+    ///
+    /// Like `signed_embedded`, this is only determined when adding imports.
+    pub machine: Option<u16>,
+    /// Subsystem from the PE optional header of the image file (e.g. 1 for `IMAGE_SUBSYSTEM_NATIVE`).
+    ///
+    /// Like `signed_embedded`, this is only determined when adding imports.
+    pub subsystem: Option<u16>,
+    /// Name of the hardcoded group (e.g. "Early-Launch") that moved this entry to the front.
+    pub moved_by_hardcoded_group: Option<String>,
+    /// Name of the hardcoded service list (e.g. "Core Driver Services") that moved this entry to the front.
+    pub moved_by_hardcoded_list: Option<String>,
+    /// Whether this service has been pinned directly after the kernel binaries via
+    /// [`NtLoadOrder::pin_services`].
+    pub pinned: bool,
+    /// Name of the API Set (e.g. "api-ms-win-core-xyz-l1-1-0") that was redirected to this import.
+    /// `None` if this entry is no import or has been imported directly.
+    pub via_api_set: Option<String>,
+    /// Image path of the image that caused this entry to be loaded as an import.
+    /// `None` if this entry has not been loaded as an import.
+    pub imported_by: Option<String>,
+    /// Anomalies found while analyzing this entry (e.g. registry values of an unexpected type).
+    pub warnings: Vec<String>,
 }
 
-#[derive(Clone)]
+impl NtLoadOrderEntry {
+    /// Returns an entry with the given properties, leaving all optional ones unset.
+    pub(crate) fn new(
+        name: String,
+        image_path: String,
+        reason: String,
+        kind: NtLoadOrderEntryKind,
+    ) -> Self {
+        Self {
+            name,
+            image_path,
+            group: None,
+            tag: None,
+            reason,
+            kind,
+            service_type: None,
+            error_control: None,
+            critical: false,
+            depend_on_service: Vec::new(),
+            depend_on_group: Vec::new(),
+            signed_embedded: None,
+            machine: None,
+            subsystem: None,
+            moved_by_hardcoded_group: None,
+            moved_by_hardcoded_list: None,
+            pinned: false,
+            via_api_set: None,
+            imported_by: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    #[deprecated = "use `kind` instead"]
+    pub fn is_kernel_binary(&self) -> bool {
+        self.kind == NtLoadOrderEntryKind::KernelBinary
+    }
+
+    /// Returns whether this is an import with a PE subsystem other than `IMAGE_SUBSYSTEM_NATIVE`,
+    /// i.e. a user-mode image (see [`NtLoadOrder::kernel_mode_only`]).
+    pub fn is_user_mode_import(&self) -> bool {
+        const IMAGE_SUBSYSTEM_NATIVE: u16 = 1;
+
+        self.kind == NtLoadOrderEntryKind::Import
+            && self
+                .subsystem
+                .is_some_and(|subsystem| subsystem != IMAGE_SUBSYSTEM_NATIVE)
+    }
+
+    /// Returns a human-readable name of the [`machine`](Self::machine) type of the image file.
+    ///
+    /// Returns `None` if the machine type is unknown or has not been determined.
+    pub fn machine_name(&self) -> Option<&'static str> {
+        let name = match self.machine? {
+            0x014c => "x86",
+            0x01c4 => "ARM",
+            0x0200 => "IA64",
+            0x8664 => "x64",
+            0xa641 => "ARM64EC",
+            0xa64e => "ARM64X",
+            0xaa64 => "ARM64",
+            _ => return None,
+        };
+
+        Some(name)
+    }
+
+    /// Returns the full path to the image file of this entry on a system with the given system root.
+    ///
+    /// Prefixes like `\SystemRoot\` are handled the same way as during import resolution.
+    /// Absolute image paths are resolved via the given drive mappings (see [`NtLoadOrder::drive_mappings`]).
+    /// Pass `None` for the local system, where absolute image paths are returned as they are.
+    ///
+    /// Returns `None` for entries without an image path and for image paths that cannot be mapped.
+    pub fn resolved_path(
+        &self,
+        system_root: &str,
+        drive_mappings: Option<&HashMap<char, String>>,
+    ) -> Option<PathBuf> {
+        if self.image_path.is_empty() {
+            return None;
+        }
+
+        resolve_image_path(
+            &self.image_path,
+            vec![system_root.to_string()],
+            drive_mappings.cloned(),
+        )
+    }
+
+    /// Returns a human-readable name of the [`service_type`](Self::service_type).
+    ///
+    /// Returns `None` if the service type is unknown or has not been determined.
+    pub fn service_type_name(&self) -> Option<&'static str> {
+        // SERVICE_INTERACTIVE_PROCESS may be combined with the Win32 service types.
+        let name = match self.service_type? & !0x100 {
+            0x1 => "Kernel Driver",
+            0x2 => "File System Driver",
+            0x4 => "Adapter",
+            0x8 => "Recognizer Driver",
+            0x10 => "Win32 Own Process",
+            0x20 => "Win32 Share Process",
+            _ => return None,
+        };
+
+        Some(name)
+    }
+}
+
+/// An entry of the load order together with the entries it has imported.
+///
+/// See [`NtLoadOrder::get_tree`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoadOrderNode {
+    pub entry: NtLoadOrderEntry,
+    /// Entries imported by this entry, in load order.
+    /// Note that the bootloader loads them before this entry.
+    pub children: Vec<LoadOrderNode>,
+}
+
+impl LoadOrderNode {
+    /// Returns an iterator over this node and all its descendants, depth-first,
+    /// with every node coming before its children.
+    pub fn iter(&self) -> LoadOrderNodes<'_> {
+        LoadOrderNodes { stack: vec![self] }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NtLoadOrderEntryKind {
+    /// A hardcoded kernel binary (e.g. "ntoskrnl.exe").
+    /// These have fixed positions at the beginning of the list that don't move.
+    KernelBinary,
+    /// A boot-start service.
+    Service,
+    /// The boot file system driver.
+    BootFileSystem,
+    /// A native application run by the Session Manager after all boot drivers have been loaded
+    /// (see [`NtLoadOrder::include_boot_execute`]).
+    BootExecute,
+    /// An image that is only loaded because it is imported by another one.
+    Import,
+    /// An import whose file could not be found.
+    /// This is only added if [`NtLoadOrder::strict_imports`] is disabled.
+    UnresolvedImport,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct NtLoadOrderEntryGroup {
     /// The original name of this group, used for displaying.
     pub display_name: String,
     /// The key used for looking up this group in the `groups` HashMap.
-    /// This is a lowercased version of `display_name`.
+    /// This is a lowercased version of `display_name` without surrounding whitespace.
     pub search_key: String,
 }
 
+/// Returns the [`NtLoadOrderEntryGroup::search_key`] for a group name.
+///
+/// Group names are lowercased Unicode-aware, because third-party drivers may use localized group names.
+pub(crate) fn group_search_key(group_name: &str) -> String {
+    group_name.trim().to_lowercase()
+}
+
+/// Information about the "apisetschema.dll" used for resolving API Set imports.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApiSetSchemaInfo {
+    /// Full path to the "apisetschema.dll" file.
+    pub path: PathBuf,
+    /// Version of the API Set Map in its ".apiset" section.
+    /// Only version 6 (Windows 10 and later) is supported for resolving imports.
+    pub version: u32,
+}
+
+/// The boot entry of a BCD store, as read by [`BcdBootEntry::from_bcd_store`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BcdBootEntry {
+    /// GUID of the boot entry object (e.g. "{b2721d73-1db4-4c62-bf78-c548a880142d}").
+    pub guid: String,
+    /// Name of the boot entry, as shown in the boot menu.
+    pub description: Option<String>,
+    /// System root relative to the boot device (e.g. "\Windows").
+    /// As the device is not resolved, this is not applied to [`NtLoadOrder`] automatically.
+    pub system_root: Option<String>,
+    /// Safe Mode the entry boots into, if any.
+    pub safe_boot: Option<SafeBootMode>,
+    /// KD driver for the debug transport, if the kernel debugger is enabled.
+    pub kd_driver: Option<String>,
+    /// Whether the entry boots into Windows PE.
+    pub winpe: bool,
+}
+
+impl BcdBootEntry {
+    /// Reads the default boot entry of the BCD store at the given path (usually "\Boot\BCD" or
+    /// "\EFI\Microsoft\Boot\BCD" on the system partition).
+    pub fn from_bcd_store<P: AsRef<Path>>(bcd_path: P) -> Result<Self> {
+        bcd::read_default_boot_entry(bcd_path.as_ref())
+    }
+}
+
+/// Replacement ordering tables for analyzing hypothetical boot configurations,
+/// see [`NtLoadOrder::group_order_override`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GroupOrder {
+    /// Replaces the groups listed in "Control\ServiceGroupOrder", in load order.
+    pub service_group_order: Option<Vec<String>>,
+    /// Replaces the tag order of the given groups in "Control\GroupOrderList".
+    /// Groups not listed here keep their tag order from the registry.
+    pub group_order_lists: HashMap<String, Vec<u32>>,
+}
+
+impl GroupOrder {
+    /// Parses a group order from a simple text format, where every section lists one value per line:
+    ///
+    /// ```text
+    /// ; Comments start with a semicolon.
+    /// [ServiceGroupOrder]
+    /// Boot Bus Extender
+    /// Boot File System
+    ///
+    /// [GroupOrderList\Boot Bus Extender]
+    /// 2
+    /// 1
+    /// ```
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut group_order = Self::default();
+        let mut current_group_order_list = None;
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let line_number = line_index + 1;
+
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(section) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                if section.eq_ignore_ascii_case("ServiceGroupOrder") {
+                    group_order.service_group_order = Some(Vec::new());
+                    current_group_order_list = None;
+                } else if let Some((_, group)) = section
+                    .split_once('\\')
+                    .filter(|(key, _)| key.eq_ignore_ascii_case("GroupOrderList"))
+                {
+                    let group = group.trim().to_string();
+                    group_order
+                        .group_order_lists
+                        .insert(group.clone(), Vec::new());
+                    current_group_order_list = Some(group);
+                } else {
+                    bail!("Unknown section \"{section}\" in line {line_number}");
+                }
+
+                continue;
+            }
+
+            if let Some(group) = &current_group_order_list {
+                let tag = line
+                    .parse::<u32>()
+                    .with_context(|| format!("Invalid tag \"{line}\" in line {line_number}"))?;
+                group_order
+                    .group_order_lists
+                    .get_mut(group)
+                    .unwrap()
+                    .push(tag);
+            } else if let Some(service_group_order) = &mut group_order.service_group_order {
+                service_group_order.push(line.to_string());
+            } else {
+                bail!("Line {line_number} is outside of any section");
+            }
+        }
+
+        Ok(group_order)
+    }
+
+    /// Replaces the ordering tables read from the registry.
+    fn apply(&self, registry_info: &mut RegistryInfo) {
+        if let Some(service_group_order) = &self.service_group_order {
+            registry_info.service_group_order = service_group_order.clone();
+        }
+
+        for (group, tags) in &self.group_order_lists {
+            registry_info
+                .groups
+                .insert(group_search_key(group), tags.iter().copied().collect());
+        }
+    }
+}
+
+/// Statistics about the image files opened while adding imports, returned by
+/// [`NtLoadOrder::get_with_import_stats`].
+#[derive(Clone, Debug, Default)]
+pub struct ImportStats {
+    /// Every image file whose imports have been added, in the order they were opened.
+    pub files: Vec<ImageFileStats>,
+}
+
+impl ImportStats {
+    /// Returns the number of opened image files.
+    pub fn total_files(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns the size of all opened image files, in bytes.
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    /// Returns the time spent opening and parsing all image files.
+    pub fn total_duration(&self) -> Duration {
+        self.files.iter().map(|file| file.duration).sum()
+    }
+}
+
+/// Statistics about a single image file, see [`ImportStats`].
+#[derive(Clone, Debug)]
+pub struct ImageFileStats {
+    pub image_path: String,
+    /// Size of the file, in bytes.
+    pub size: u64,
+    /// Time spent opening (memory-mapping) and parsing the file, without handling its imports.
+    pub duration: Duration,
+}
+
+/// The load order as determined from the registry, returned by [`NtLoadOrder::get_sorted`].
+#[derive(Clone)]
+pub struct SortedLoadOrder {
+    nt_load_order: NtLoadOrder,
+    entries: Vec<NtLoadOrderEntry>,
+    excluded_entries: Vec<NtLoadOrderEntry>,
+}
+
+impl SortedLoadOrder {
+    /// Returns the entries without any imports, in load order.
+    pub fn entries(&self) -> &[NtLoadOrderEntry] {
+        &self.entries
+    }
+
+    /// Returns the entries with their imports added, like [`NtLoadOrder::get`] would.
+    ///
+    /// The imports are added on a copy, so this can be called repeatedly.
+    pub fn with_imports(&self) -> Result<Vec<NtLoadOrderEntry>> {
+        let entries = self.entries.iter().cloned().collect();
+        let entries =
+            self.nt_load_order
+                .run_add_imports_step(entries, &self.excluded_entries, None, None)?;
+        Ok(entries.into_iter().collect())
+    }
+
+    /// Returns the entries without any imports, consuming this load order.
+    pub fn into_entries(self) -> Vec<NtLoadOrderEntry> {
+        self.entries
+    }
+}
+
+/// Driver set loaded in Windows Safe Mode.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SafeBootMode {
+    /// Only the services listed under "Control\SafeBoot\Minimal".
+    Minimal,
+    /// Only the services listed under "Control\SafeBoot\Network".
+    Network,
+}
+
 impl NtLoadOrder {
     pub fn new() -> Self {
         Self {
             system_root: None,
-            kd_driver: None,
+            system_hive_filename: None,
+            current_control_set: false,
+            import_root: None,
+            import_roots: Vec::new(),
+            drive_mappings: HashMap::new(),
+            kd_drivers: None,
             cpu_vendor: None,
+            #[cfg(feature = "autodetect-cpu-vendor")]
+            autodetect_cpu_vendor: false,
             sort_by_tag_and_group: true,
+            reverse_registry_order: true,
             sort_by_hardcoded_groups: true,
             sort_by_hardcoded_service_lists: true,
             add_kernel_binaries: true,
             add_imports: true,
+            strict_imports: true,
+            expand_kernel_binary_imports: true,
+            kernel_binary_imports_first: true,
+            include_boot_execute: false,
+            kernel_mode_only: false,
+            winpe: false,
+            strict: false,
+            safe_boot: None,
+            hardware_config_id: None,
+            driver_verifier: None,
+            exclude_services: Vec::new(),
+            force_boot_services: Vec::new(),
+            group_order_override: None,
+            pin_services: Vec::new(),
+            #[cfg(feature = "mmap")]
+            memory_map_hive: false,
         }
     }
 
@@ -114,13 +791,137 @@ impl NtLoadOrder {
         self
     }
 
+    /// Returns information about the "apisetschema.dll" that is used when adding imports.
+    ///
+    /// This is useful for explaining differences in import resolution between Windows builds.
+    pub fn apiset_schema_info(&self) -> Result<ApiSetSchemaInfo> {
+        let (system_roots, _) = self.imports_system_roots()?;
+        load_apiset_schema_info(system_roots)
+    }
+
+    #[cfg(feature = "autodetect-cpu-vendor")]
+    pub fn autodetect_cpu_vendor(mut self, value: bool) -> Self {
+        self.autodetect_cpu_vendor = value;
+        self
+    }
+
+    /// Applies the Safe Mode, debug, and Windows PE settings of a boot entry read from a BCD store.
+    ///
+    /// A KD driver is only set if the kernel debugger is enabled, otherwise the detection from the registry is kept.
+    pub fn bcd_boot_entry(mut self, entry: &BcdBootEntry) -> Self {
+        if let Some(kd_driver) = &entry.kd_driver {
+            self.kd_drivers = Some(vec![kd_driver.clone()]);
+        }
+
+        self.safe_boot = entry.safe_boot;
+        self.winpe = entry.winpe;
+        self
+    }
+
     pub fn cpu_vendor(mut self, cpu_vendor: Option<String>) -> Self {
         self.cpu_vendor = cpu_vendor;
         self
     }
 
+    pub fn current_control_set(mut self, value: bool) -> Self {
+        self.current_control_set = value;
+        self
+    }
+
+    pub fn drive_mappings(mut self, drive_mappings: HashMap<char, String>) -> Self {
+        self.drive_mappings = drive_mappings;
+        self
+    }
+
+    pub fn driver_verifier(mut self, driver_verifier: Option<bool>) -> Self {
+        self.driver_verifier = driver_verifier;
+        self
+    }
+
+    pub fn exclude_services(mut self, exclude_services: Vec<String>) -> Self {
+        self.exclude_services = exclude_services;
+        self
+    }
+
+    pub fn expand_kernel_binary_imports(mut self, value: bool) -> Self {
+        self.expand_kernel_binary_imports = value;
+        self
+    }
+
+    pub fn force_boot_services(mut self, force_boot_services: Vec<String>) -> Self {
+        self.force_boot_services = force_boot_services;
+        self
+    }
+
+    pub fn group_order_override(mut self, group_order_override: Option<GroupOrder>) -> Self {
+        self.group_order_override = group_order_override;
+        self
+    }
+
+    pub fn hardware_config_id(mut self, hardware_config_id: Option<u32>) -> Self {
+        self.hardware_config_id = hardware_config_id;
+        self
+    }
+
+    pub fn import_root(mut self, import_root: Option<String>) -> Self {
+        self.import_root = import_root;
+        self
+    }
+
+    pub fn import_roots(mut self, import_roots: Vec<String>) -> Self {
+        self.import_roots = import_roots;
+        self
+    }
+
+    pub fn include_boot_execute(mut self, value: bool) -> Self {
+        self.include_boot_execute = value;
+        self
+    }
+
+    pub fn kernel_binary_imports_first(mut self, value: bool) -> Self {
+        self.kernel_binary_imports_first = value;
+        self
+    }
+
+    pub fn kernel_mode_only(mut self, value: bool) -> Self {
+        self.kernel_mode_only = value;
+        self
+    }
+
+    /// Sets a single KD driver to load, or none at all.
+    ///
+    /// This is a convenience method for [`kd_drivers`](Self::kd_drivers).
     pub fn kd_driver(mut self, kd_driver: Option<String>) -> Self {
-        self.kd_driver = kd_driver;
+        self.kd_drivers = Some(kd_driver.into_iter().collect());
+        self
+    }
+
+    /// Sets the KD drivers to load, overriding the detection from the registry.
+    pub fn kd_drivers(mut self, kd_drivers: Vec<String>) -> Self {
+        self.kd_drivers = Some(kd_drivers);
+        self
+    }
+
+    #[cfg(feature = "mmap")]
+    pub fn memory_map_hive(mut self, value: bool) -> Self {
+        self.memory_map_hive = value;
+        self
+    }
+
+    pub fn pin_services(mut self, pin_services: Vec<String>) -> Self {
+        self.pin_services = pin_services;
+        self
+    }
+
+    /// Not part of the public API, only meant for debugging the sorting steps.
+    #[doc(hidden)]
+    pub fn reverse_registry_order(mut self, value: bool) -> Self {
+        self.reverse_registry_order = value;
+        self
+    }
+
+    pub fn safe_boot(mut self, safe_boot: Option<SafeBootMode>) -> Self {
+        self.safe_boot = safe_boot;
         self
     }
 
@@ -139,77 +940,542 @@ impl NtLoadOrder {
         self
     }
 
+    pub fn strict(mut self, value: bool) -> Self {
+        self.strict = value;
+        self
+    }
+
+    pub fn strict_imports(mut self, value: bool) -> Self {
+        self.strict_imports = value;
+        self
+    }
+
+    pub fn system_hive_filename(mut self, system_hive_filename: Option<String>) -> Self {
+        self.system_hive_filename = system_hive_filename;
+        self
+    }
+
     pub fn system_root(mut self, system_root: Option<String>) -> Self {
         self.system_root = system_root;
         self
     }
 
+    pub fn winpe(mut self, value: bool) -> Self {
+        self.winpe = value;
+        self
+    }
+
     pub fn get(self) -> Result<Vec<NtLoadOrderEntry>> {
-        // Hardcoded for now, but will work for 99.9% of the cases :)
-        const BOOT_FILE_SYSTEM: &str = "ntfs";
-        const CONTROL_SET: u8 = 1;
+        self.run_steps(None, None)
+    }
 
-        let registry_worker = if let Some(system_root) = &self.system_root {
-            // Load services from target registry.
-            RegistryWorker::new_target(system_root)?
-        } else {
-            // Load services from local registry.
-            RegistryWorker::new_local()
-        };
+    /// Runs [`get`](Self::get) with this configuration for each of the given system roots,
+    /// overriding [`system_root`](Self::system_root).
+    ///
+    /// Returns the result of every system root in the given order, without stopping at the first error.
+    /// This is useful for comparing a directory of captured systems via [`diff_load_orders`].
+    pub fn get_many(
+        self,
+        system_roots: Vec<String>,
+    ) -> Vec<(String, Result<Vec<NtLoadOrderEntry>>)> {
+        system_roots
+            .into_iter()
+            .map(|system_root| {
+                let result = self
+                    .clone()
+                    .system_root(Some(system_root.clone()))
+                    .get()
+                    .with_context(|| format!("Analyzing system root \"{system_root}\" failed"));
+                (system_root, result)
+            })
+            .collect()
+    }
 
-        let registry_info = load_from_registry(&registry_worker, BOOT_FILE_SYSTEM, CONTROL_SET)?;
+    /// Like [`get`](Self::get), but additionally measures how long it took to open and parse every image file
+    /// while adding imports.
+    ///
+    /// This is useful for finding unusually large drivers or slow I/O (e.g. on a network share).
+    /// [`get`](Self::get) doesn't take any measurements.
+    pub fn get_with_import_stats(self) -> Result<(Vec<NtLoadOrderEntry>, ImportStats)> {
+        let mut import_stats = ImportStats::default();
+        let entries = self.run_steps(None, Some(&mut import_stats))?;
+        Ok((entries, import_stats))
+    }
+
+    /// Returns the boot drivers and ordering tables exactly as read from the registry, before any sorting.
+    ///
+    /// This is useful for showing the raw data side by side with the final load order.
+    pub fn get_registry_info(self) -> Result<RegistryInfo> {
+        self.validate()?;
+
+        let registry_worker = self.registry_worker()?;
+        let control_set_key_name = self.control_set_key_name(&registry_worker)?;
+        self.load_registry_info(&registry_worker, &control_set_key_name)
+    }
+
+    /// Returns every service key with all its values, regardless of whether it is a boot driver.
+    ///
+    /// This is meant for debugging registry contents that don't lead to the expected load order.
+    pub fn get_raw_services(self) -> Result<Vec<ServiceRaw>> {
+        self.validate()?;
+
+        let registry_worker = self.registry_worker()?;
+        let control_set_key_name = self.control_set_key_name(&registry_worker)?;
+        raw_services(&registry_worker, &control_set_key_name)
+    }
+
+    /// Sorts the given boot drivers and ordering tables like [`get`](Self::get), without reading the registry.
+    ///
+    /// This is useful for services that have been enumerated elsewhere (e.g. from an offline database).
+    /// Only the sorting steps enabled via the `sort_by_*` settings are run.
+    /// In particular, no kernel binaries and imports are added, and [`pin_services`](Self::pin_services)
+    /// is not applied.
+    pub fn sort(&self, registry_info: RegistryInfo) -> Vec<NtLoadOrderEntry> {
+        self.run_sort_steps(registry_info, None)
+            .into_iter()
+            .collect()
+    }
+
+    /// Like [`get`](Self::get), but stops before adding imports and keeps the result for adding them later.
+    ///
+    /// This allows toggling the imports (e.g. in a GUI) without reading and sorting the registry again.
+    /// [`add_imports`](Self::add_imports) is ignored here.
+    pub fn get_sorted(self) -> Result<SortedLoadOrder> {
+        let (entries, excluded_entries) = self.run_registry_steps(None)?;
+
+        Ok(SortedLoadOrder {
+            nt_load_order: self,
+            entries: entries.into_iter().collect(),
+            excluded_entries,
+        })
+    }
+
+    /// Like [`get`](Self::get), but arranges the load order in a tree, where every entry owns the entries
+    /// it has imported.
+    ///
+    /// Entries on the same level are returned in load order.
+    pub fn get_tree(self) -> Result<Vec<LoadOrderNode>> {
+        let entries = self.get()?;
+        Ok(build_tree(entries))
+    }
+
+    /// Returns the image paths of the files that the step for adding imports would open first,
+    /// without opening them.
+    ///
+    /// These are the "apisetschema.dll" followed by the kernel binaries and services in load order,
+    /// as determined from the registry alone.
+    /// Images outside of the system root are omitted, unless they can be resolved via
+    /// [`drive_mappings`](Self::drive_mappings) or the running system is analyzed.
+    /// The imports of these files are only known after opening them, so they are not part of the plan.
+    ///
+    /// This is useful for staging the files of a target system or predicting permission issues.
+    pub fn import_file_plan(mut self) -> Result<Vec<String>> {
+        let (system_roots, drive_mappings) = self.imports_system_roots()?;
+
+        self.add_imports = false;
+        let entries = self.get()?;
+
+        let mut seen_image_paths = HashSet::new();
+        let plan = iter::once(APISETSCHEMA_IMAGE_PATH.to_string())
+            .chain(entries.into_iter().map(|entry| entry.image_path))
+            .filter(|image_path| {
+                resolve_image_path(image_path, system_roots.clone(), drive_mappings.clone())
+                    .is_some()
+                    && seen_image_paths.insert(image_path.to_ascii_lowercase())
+            })
+            .collect();
+
+        Ok(plan)
+    }
+
+    /// Returns the decisions of all steps that affected the entry with the given name (case-insensitive),
+    /// in the order they were made.
+    ///
+    /// This is useful for understanding a surprising position of a driver in the load order.
+    pub fn explain(self, name: &str) -> Result<Vec<String>> {
+        let mut explanation = Explanation::new(name);
+        self.run_steps(Some(&mut explanation), None)?;
+
+        explanation
+            .into_lines()
+            .with_context(|| format!("\"{name}\" is not part of the load order"))
+    }
+
+    /// Runs all enabled steps, optionally recording their decisions about a single entry.
+    fn run_steps(
+        self,
+        mut explanation: Option<&mut Explanation>,
+        import_stats: Option<&mut ImportStats>,
+    ) -> Result<Vec<NtLoadOrderEntry>> {
+        let (mut entries, excluded_entries) =
+            self.run_registry_steps(explanation.as_deref_mut())?;
+
+        if self.add_imports {
+            entries =
+                self.run_add_imports_step(entries, &excluded_entries, explanation, import_stats)?;
+        }
+
+        Ok(entries.into_iter().collect())
+    }
+
+    fn load_registry_info(
+        &self,
+        registry_worker: &RegistryWorker,
+        control_set_key_name: &str,
+    ) -> Result<RegistryInfo> {
+        load_from_registry(
+            registry_worker,
+            BOOT_FILE_SYSTEM,
+            control_set_key_name,
+            LoadFromRegistryOptions {
+                winpe: self.winpe,
+                safe_boot: self.safe_boot,
+                hardware_config_id: self.hardware_config_id,
+                force_boot_services: &self.force_boot_services,
+                strict: self.strict,
+            },
+        )
+    }
+
+    /// Runs all steps up to adding the kernel binaries, which only need the registry.
+    ///
+    /// Returns the sorted entries along with the entries removed via [`Self::exclude_services`].
+    fn run_registry_steps(
+        &self,
+        mut explanation: Option<&mut Explanation>,
+    ) -> Result<(VecList<NtLoadOrderEntry>, Vec<NtLoadOrderEntry>)> {
+        self.validate()?;
+
+        let registry_worker = self.registry_worker()?;
+        let control_set_key_name = self.control_set_key_name(&registry_worker)?;
+        let mut registry_info = self.load_registry_info(&registry_worker, &control_set_key_name)?;
+
+        if let Some(group_order_override) = &self.group_order_override {
+            group_order_override.apply(&mut registry_info);
+        }
+
+        apply_driver_verifier(
+            &registry_worker,
+            &control_set_key_name,
+            self.driver_verifier,
+            &mut registry_info.entries,
+        )?;
+
+        let excluded_entries = exclude_services(&mut registry_info.entries, &self.exclude_services);
+
+        if let Some(explanation) = &mut explanation {
+            explanation.add_step(Step::LoadFromRegistry, registry_info.entries.iter());
+        }
 
+        let mut entries = self.run_sort_steps(registry_info, explanation.as_deref_mut());
+
+        if !self.pin_services.is_empty() {
+            pin_services(&mut entries, &self.pin_services)?;
+            if let Some(explanation) = &mut explanation {
+                explanation.add_step(Step::PinServices, entries.iter());
+            }
+        }
+
+        if self.add_kernel_binaries {
+            let kd_drivers = match &self.kd_drivers {
+                Some(kd_drivers) => kd_drivers.clone(),
+                None => detect_kd_drivers(&registry_worker, &control_set_key_name)?,
+            };
+
+            self.add_kernel_binaries_to(&mut entries, &kd_drivers);
+            if let Some(explanation) = &mut explanation {
+                explanation.add_step(Step::AddKernelBinaries, entries.iter());
+            }
+        }
+
+        if self.include_boot_execute {
+            for entry in load_boot_execute(&registry_worker, &control_set_key_name)? {
+                entries.push_back(entry);
+            }
+        }
+
+        Ok((entries, excluded_entries))
+    }
+
+    /// Runs the sorting steps enabled via the `sort_by_*` settings.
+    fn run_sort_steps(
+        &self,
+        registry_info: RegistryInfo,
+        mut explanation: Option<&mut Explanation>,
+    ) -> VecList<NtLoadOrderEntry> {
         let mut entries = if self.sort_by_tag_and_group {
-            sort_by_tag_and_group(registry_info)
+            let entries = sort_by_tag_and_group(registry_info, self.reverse_registry_order);
+            if let Some(explanation) = &mut explanation {
+                explanation.add_step(Step::SortByTagAndGroup, entries.iter());
+            }
+            entries
         } else {
             registry_info.entries.into_iter().collect::<VecList<_>>()
         };
 
         if self.sort_by_hardcoded_groups {
             sort_by_hardcoded_groups(&mut entries);
+            if let Some(explanation) = &mut explanation {
+                explanation.add_step(Step::SortByHardcodedGroups, entries.iter());
+            }
         }
 
         if self.sort_by_hardcoded_service_lists {
             sort_by_hardcoded_service_lists(&mut entries);
+            if let Some(explanation) = &mut explanation {
+                explanation.add_step(Step::SortByHardcodedServiceLists, entries.iter());
+            }
         }
 
-        if self.add_kernel_binaries {
-            let mut last = add_basic_kernel_binaries(&mut entries);
-
-            if let Some(kd_driver) = &self.kd_driver {
-                last = add_kernel_binary(
-                    &mut entries,
-                    last,
-                    kd_driver.clone(),
-                    format!("System32\\{kd_driver}.dll"),
-                );
+        entries
+    }
+
+    fn run_add_imports_step(
+        &self,
+        entries: VecList<NtLoadOrderEntry>,
+        excluded_entries: &[NtLoadOrderEntry],
+        explanation: Option<&mut Explanation>,
+        import_stats: Option<&mut ImportStats>,
+    ) -> Result<VecList<NtLoadOrderEntry>> {
+        let (system_roots, drive_mappings) = self.imports_system_roots()?;
+        let mut entries = add_imports(
+            entries,
+            system_roots,
+            drive_mappings,
+            self.strict_imports,
+            self.expand_kernel_binary_imports,
+            self.kernel_binary_imports_first,
+            import_stats,
+        )?;
+        warn_about_excluded_imports(&mut entries, excluded_entries);
+
+        if self.kernel_mode_only {
+            entries.retain(|entry| !entry.is_user_mode_import());
+        }
+
+        if let Some(explanation) = explanation {
+            explanation.add_step(Step::AddImports, entries.iter());
+        }
+
+        Ok(entries)
+    }
+
+    /// Asynchronous variant of [`get`](Self::get) for use in Tokio applications.
+    ///
+    /// The analysis still reads the registry and image files using blocking calls,
+    /// but it is offloaded to a thread via [`tokio::task::spawn_blocking`] and won't block the async runtime.
+    /// This function must therefore be called from within a Tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub async fn get_async(self) -> Result<Vec<NtLoadOrderEntry>> {
+        tokio::task::spawn_blocking(move || self.get())
+            .await
+            .context("The blocking task of NtLoadOrder::get_async failed")?
+    }
+
+    /// Checks the options for invalid values and contradictory combinations.
+    ///
+    /// This is called at the beginning of [`get`](Self::get), but may be called earlier to report
+    /// errors before doing any work.
+    pub fn validate(&self) -> Result<()> {
+        if self.system_root.is_none()
+            && self.import_root.is_none()
+            && self.import_roots.is_empty()
+            && !self.drive_mappings.is_empty()
+        {
+            bail!(
+                "Drive mappings can only be used when analyzing a target SystemRoot or import root"
+            );
+        }
+
+        if self.import_root.is_some() && !self.import_roots.is_empty() {
+            bail!("An import root cannot be combined with layered import roots");
+        }
+
+        if self.system_root.is_none() && self.system_hive_filename.is_some() {
+            bail!("A SYSTEM hive file name can only be used when analyzing a target SystemRoot");
+        }
+
+        if let Some(system_hive_filename) = &self.system_hive_filename {
+            if system_hive_filename.is_empty() || system_hive_filename.contains(['\\', '/']) {
+                bail!("Invalid SYSTEM hive file name \"{system_hive_filename}\", expected a file name like \"SYSTEM.SAV\"");
             }
+        }
 
-            if let Some(cpu_vendor) = &self.cpu_vendor {
-                add_kernel_binary(
-                    &mut entries,
-                    last,
-                    "mcupdate".to_string(),
-                    format!("System32\\mcupdate_{cpu_vendor}.dll"),
-                );
+        if let Some(drive) = self
+            .drive_mappings
+            .keys()
+            .find(|drive| !drive.is_ascii_alphabetic())
+        {
+            bail!("Invalid drive letter '{drive}' in drive mappings");
+        }
+
+        for kd_driver in self.kd_drivers.iter().flatten() {
+            if kd_driver.is_empty() || kd_driver.contains(['\\', '/', '.']) {
+                bail!("Invalid KD driver name \"{kd_driver}\", expected a file name without extension like \"kdcom\"");
             }
         }
 
-        if self.add_imports {
-            let system_root = if let Some(system_root) = &self.system_root {
-                // Load imports from the target system root.
-                system_root.clone()
-            } else {
-                // Get the local system root from the environment variable.
-                std::env::var("SystemRoot")
-                    .context("Could not read SystemRoot environment variable")?
-            };
+        if self.cpu_vendor.as_deref() == Some("") {
+            bail!("CPU vendor must not be empty");
+        }
 
-            entries = add_imports(entries, system_root)?;
+        if self.kernel_mode_only && self.include_boot_execute {
+            bail!("Boot Execute applications cannot be included when only kernel-mode images are requested");
         }
 
+        Ok(())
+    }
+
+    /// Checks that the SYSTEM hive of the target SystemRoot can be opened and looks like a SYSTEM hive.
+    ///
+    /// This is called at the beginning of [`get`](Self::get), but may be called earlier to report
+    /// a wrong hive file (like SOFTWARE) before doing any work.
+    /// The registry of the local system is always accepted.
+    pub fn validate_system_hive(&self) -> Result<()> {
+        self.validate()?;
+        self.registry_worker()?;
+        Ok(())
+    }
+
+    /// Returns the transitive imports of a single image in the order they are loaded,
+    /// without analyzing the registry.
+    ///
+    /// `image_path` is given like a service "ImagePath" (e.g. "System32\drivers\acpi.sys").
+    /// The image itself is not part of the returned entries.
+    pub fn import_closure(&self, image_path: &str) -> Result<Vec<NtLoadOrderEntry>> {
+        self.validate()?;
+
+        let (system_roots, drive_mappings) = self.imports_system_roots()?;
+        let entries = import_closure(
+            image_path,
+            system_roots,
+            drive_mappings,
+            self.strict_imports,
+        )?;
         Ok(entries.into_iter().collect())
     }
+
+    /// Returns the IDs of all hardware configurations under the "HardwareConfig" key, in ascending order.
+    ///
+    /// Each of them can be analyzed by passing it to [`hardware_config_id`](Self::hardware_config_id).
+    pub fn list_hardware_configs(&self) -> Result<Vec<u32>> {
+        self.validate()?;
+
+        let registry_worker = self.registry_worker()?;
+        hardware_config_ids(&registry_worker)
+    }
+
+    /// Returns just the kernel binaries at the beginning of the load order, without touching the registry.
+    ///
+    /// These are "ntoskrnl.exe", "hal.dll", the KD drivers (if set via [`kd_drivers`](Self::kd_drivers)),
+    /// and the mcupdate library (if set via [`cpu_vendor`](Self::cpu_vendor)).
+    ///
+    /// Detecting the KD drivers requires the registry, so they are only included if set explicitly.
+    pub fn kernel_binaries(&self) -> Vec<NtLoadOrderEntry> {
+        let kd_drivers = self.kd_drivers.as_deref().unwrap_or_default();
+        let mut entries = VecList::new();
+        self.add_kernel_binaries_to(&mut entries, kd_drivers);
+        entries.into_iter().collect()
+    }
+
+    /// Adds the kernel binaries in front of the passed entries.
+    fn add_kernel_binaries_to(
+        &self,
+        entries: &mut VecList<NtLoadOrderEntry>,
+        kd_drivers: &[String],
+    ) {
+        let mut last = add_basic_kernel_binaries(entries);
+
+        for kd_driver in kd_drivers {
+            last = add_kernel_binary(
+                entries,
+                last,
+                kd_driver.clone(),
+                format!("System32\\{kd_driver}.dll"),
+            );
+        }
+
+        if let Some(cpu_vendor) = self.effective_cpu_vendor() {
+            add_kernel_binary(
+                entries,
+                last,
+                "mcupdate".to_string(),
+                format!("System32\\mcupdate_{cpu_vendor}.dll"),
+            );
+        }
+    }
+
+    /// Returns the configured CPU vendor or, if enabled, the detected one of the local system.
+    fn effective_cpu_vendor(&self) -> Option<String> {
+        if self.cpu_vendor.is_some() {
+            return self.cpu_vendor.clone();
+        }
+
+        #[cfg(all(
+            feature = "autodetect-cpu-vendor",
+            any(target_arch = "x86", target_arch = "x86_64")
+        ))]
+        if self.autodetect_cpu_vendor && self.system_root.is_none() {
+            return raw_cpuid::CpuId::new()
+                .get_vendor_info()
+                .map(|vendor_info| vendor_info.as_str().to_owned());
+        }
+
+        None
+    }
+
+    /// Returns the name of the control set key to read, relative to the SYSTEM hive.
+    fn control_set_key_name(&self, registry_worker: &RegistryWorker) -> Result<String> {
+        let control_set = if !self.current_control_set {
+            CONTROL_SET
+        } else if registry_worker.is_local() {
+            return Ok("CurrentControlSet".to_string());
+        } else {
+            registry_worker
+                .hive()?
+                .current_control_set()
+                .context("Could not determine the current control set")?
+        };
+
+        Ok(format!("ControlSet{control_set:03}"))
+    }
+
+    fn registry_worker(&self) -> Result<RegistryWorker> {
+        if let Some(system_root) = &self.system_root {
+            // Load services from target registry.
+            self.new_target_registry_worker(system_root)
+        } else {
+            // Load services from local registry.
+            RegistryWorker::new_local()
+        }
+    }
+
+    fn new_target_registry_worker(&self, system_root: &str) -> Result<RegistryWorker> {
+        let hive_file_name = self.system_hive_filename.as_deref().unwrap_or("SYSTEM");
+
+        #[cfg(feature = "mmap")]
+        if self.memory_map_hive {
+            return RegistryWorker::new_target_mapped(system_root, hive_file_name);
+        }
+
+        RegistryWorker::new_target(system_root, hive_file_name)
+    }
+
+    /// Returns the system root layers and drive mappings for opening image files.
+    fn imports_system_roots(&self) -> Result<ImportsSystemRoots> {
+        if !self.import_roots.is_empty() {
+            // Load imports from the layered target system roots.
+            Ok((self.import_roots.clone(), Some(self.drive_mappings.clone())))
+        } else if let Some(system_root) = self.import_root.as_ref().or(self.system_root.as_ref()) {
+            // Load imports from the target system root.
+            Ok((vec![system_root.clone()], Some(self.drive_mappings.clone())))
+        } else {
+            // Get the local system root from the registry.
+            // Absolute paths to other volumes can be opened as they are.
+            let system_root = RegistryWorker::local_system_root()?;
+            Ok((vec![system_root], None))
+        }
+    }
 }
 
 impl Default for NtLoadOrder {
@@ -217,3 +1483,21 @@ impl Default for NtLoadOrder {
         Self::new()
     }
 }
+
+/// Returns the entries in reverse load order, which is the common approximation of the unload order.
+///
+/// Kernel binaries are never unloaded, so they stay at the end in their original order.
+/// The true unload order can differ, because Windows unloads drivers on demand (e.g. when their devices
+/// are removed) and most boot drivers are never unloaded at all.
+pub fn unload_order(entries: &[NtLoadOrderEntry]) -> Vec<NtLoadOrderEntry> {
+    let (kernel_binaries, others): (Vec<_>, Vec<_>) = entries
+        .iter()
+        .partition(|entry| entry.kind == NtLoadOrderEntryKind::KernelBinary);
+
+    others
+        .into_iter()
+        .rev()
+        .chain(kernel_binaries)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,69 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fmt::Write;
+
+use crate::LoadOrderNode;
+
+/// Formats the tree returned by [`NtLoadOrder::get_tree`](crate::NtLoadOrder::get_tree) as a Mermaid flowchart,
+/// which can be embedded into Markdown documents (e.g. on GitHub).
+///
+/// Every entry becomes a node labeled with its name, and every import becomes an edge from the importing entry:
+///
+/// ```text
+/// graph TD
+///     n0["ntoskrnl"]
+///     n1["PSHED.dll"]
+///     n2["pci"]
+///     n0 --> n1
+/// ```
+///
+/// Nodes are declared depth-first, with every node coming before its children.
+pub fn to_mermaid(nodes: &[LoadOrderNode]) -> String {
+    let mut declarations = String::new();
+    let mut edges = String::new();
+    let mut next_id = 0;
+
+    for node in nodes {
+        add_node(node, &mut next_id, &mut declarations, &mut edges);
+    }
+
+    format!("graph TD\n{declarations}{edges}")
+}
+
+/// Declares the node and its descendants along with the edges to their children.
+fn add_node(
+    node: &LoadOrderNode,
+    next_id: &mut usize,
+    declarations: &mut String,
+    edges: &mut String,
+) {
+    let id = *next_id;
+    *next_id += 1;
+
+    let label = mermaid_label(&node.entry.name);
+    writeln!(declarations, "    n{id}[\"{label}\"]").unwrap();
+
+    for child in &node.children {
+        writeln!(edges, "    n{id} --> n{next_id}").unwrap();
+        add_node(child, next_id, declarations, edges);
+    }
+}
+
+/// Escapes the characters that would end a quoted Mermaid label or be interpreted as markup.
+fn mermaid_label(name: &str) -> String {
+    let mut label = String::with_capacity(name.len());
+
+    for c in name.chars() {
+        match c {
+            '"' => label.push_str("#quot;"),
+            '#' => label.push_str("#35;"),
+            '<' => label.push_str("#lt;"),
+            '>' => label.push_str("#gt;"),
+            c if c.is_control() => label.push(' '),
+            c => label.push(c),
+        }
+    }
+
+    label
+}
@@ -1,11 +1,18 @@
 // Copyright 2025 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+#[cfg(target_os = "windows")]
 mod local;
 mod target;
+#[cfg(feature = "transaction-logs")]
+mod transaction_logs;
 
-use anyhow::Result;
+use std::marker::PhantomData;
+use std::path::Path;
 
+use anyhow::{Context, Result};
+
+#[cfg(target_os = "windows")]
 use self::local::{
     LocalRegistryHive, LocalRegistryKeyNode, LocalRegistryKeyValue, LocalRegistryKeyValues,
     LocalRegistrySubKeys, LocalRegistryWorker,
@@ -15,6 +22,10 @@ use self::target::{
     TargetRegistrySubKeys, TargetRegistryWorker,
 };
 
+#[cfg(not(target_os = "windows"))]
+const LOCAL_UNSUPPORTED_MESSAGE: &str =
+    "Analyzing the local system is only supported on Windows, please set a target system root";
+
 pub enum RegistryWorker {
     #[cfg(target_os = "windows")]
     Local(LocalRegistryWorker),
@@ -23,23 +34,59 @@ pub enum RegistryWorker {
 
 impl RegistryWorker {
     #[cfg(target_os = "windows")]
-    pub fn new_local() -> Self {
+    pub fn new_local() -> Result<Self> {
         let worker = LocalRegistryWorker;
-        Self::Local(worker)
+        Ok(Self::Local(worker))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn new_local() -> Result<Self> {
+        anyhow::bail!(LOCAL_UNSUPPORTED_MESSAGE)
+    }
+
+    /// Returns the system root directory of the local system.
+    #[cfg(target_os = "windows")]
+    pub fn local_system_root() -> Result<String> {
+        LocalRegistryWorker.system_root()
     }
 
-    pub fn new_target(system_root: &str) -> Result<Self> {
-        let worker = TargetRegistryWorker::new(system_root)?;
+    #[cfg(not(target_os = "windows"))]
+    pub fn local_system_root() -> Result<String> {
+        anyhow::bail!(LOCAL_UNSUPPORTED_MESSAGE)
+    }
+
+    pub fn new_target(system_root: &str, hive_file_name: &str) -> Result<Self> {
+        let worker = TargetRegistryWorker::new(system_root, hive_file_name)?;
+        Ok(Self::Target(worker))
+    }
+
+    pub fn new_target_hive_file(hive_path: &Path) -> Result<Self> {
+        let worker = TargetRegistryWorker::from_hive_file(hive_path)?;
         Ok(Self::Target(worker))
     }
 
-    pub fn hive(&self) -> Result<RegistryHive> {
+    #[cfg(feature = "mmap")]
+    pub fn new_target_mapped(system_root: &str, hive_file_name: &str) -> Result<Self> {
+        let worker = TargetRegistryWorker::new_mapped(system_root, hive_file_name)?;
+        Ok(Self::Target(worker))
+    }
+
+    pub fn hive(&self) -> Result<RegistryHive<'_>> {
         match self {
             #[cfg(target_os = "windows")]
             Self::Local(worker) => worker.hive().map(RegistryHive::Local),
             Self::Target(worker) => worker.hive().map(RegistryHive::Target),
         }
     }
+
+    /// Returns whether this worker reads the registry of the running system.
+    pub fn is_local(&self) -> bool {
+        match self {
+            #[cfg(target_os = "windows")]
+            Self::Local(_) => true,
+            Self::Target(_) => false,
+        }
+    }
 }
 
 pub enum RegistryHive<'d> {
@@ -49,6 +96,17 @@ pub enum RegistryHive<'d> {
 }
 
 impl<'d> RegistryHive<'d> {
+    /// Returns the number of the control set marked as current in the "Select" key.
+    pub fn current_control_set(&self) -> Result<u8> {
+        let current = self
+            .key_node("Select")
+            .and_then(|select| select.value("Current"))
+            .and_then(|current| current.dword_data())
+            .context("Could not read the \"Current\" value of the \"Select\" key")?;
+
+        u8::try_from(current).with_context(|| format!("Invalid current control set {current}"))
+    }
+
     pub fn key_node<'h>(&'h self, path: &str) -> Result<RegistryKeyNode<'d, 'h>> {
         match self {
             #[cfg(target_os = "windows")]
@@ -73,6 +131,38 @@ impl<'d, 'h> RegistryKeyNode<'d, 'h> {
         }
     }
 
+    /// Returns the first subkey whose name matches the predicate, without opening the remaining subkeys.
+    #[cfg_attr(not(test), expect(dead_code, reason = "no step needs it yet"))]
+    pub fn find_subkey<P>(&self, predicate: P) -> Result<Option<RegistryKeyNode<'d, 'h>>>
+    where
+        P: Fn(&str) -> bool,
+    {
+        for subkey in self.subkeys()? {
+            let subkey = subkey?;
+            if predicate(subkey.name()) {
+                return Ok(Some(subkey));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the first value whose name matches the predicate, without reading the remaining values.
+    #[cfg_attr(not(test), expect(dead_code, reason = "no step needs it yet"))]
+    pub fn find_value<P>(&self, predicate: P) -> Result<Option<RegistryKeyValue<'d, 'h>>>
+    where
+        P: Fn(&str) -> bool,
+    {
+        for value in self.values()? {
+            let value = value?;
+            if predicate(value.name()) {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
     pub fn subkey(&self, name: &str) -> Result<RegistryKeyNode<'d, 'h>> {
         match self {
             #[cfg(target_os = "windows")]
@@ -85,7 +175,9 @@ impl<'d, 'h> RegistryKeyNode<'d, 'h> {
         match self {
             #[cfg(target_os = "windows")]
             Self::Local(key_node) => Ok(RegistrySubKeys::Local(key_node.subkeys())),
-            Self::Target(key_node) => key_node.subkeys().map(RegistrySubKeys::Target),
+            Self::Target(key_node) => key_node
+                .subkeys()
+                .map(|iter| RegistrySubKeys::Target(iter, PhantomData)),
         }
     }
 
@@ -101,7 +193,9 @@ impl<'d, 'h> RegistryKeyNode<'d, 'h> {
         match self {
             #[cfg(target_os = "windows")]
             Self::Local(key_node) => Ok(RegistryKeyValues::Local(key_node.values())),
-            Self::Target(key_node) => key_node.values().map(RegistryKeyValues::Target),
+            Self::Target(key_node) => key_node
+                .values()
+                .map(|iter| RegistryKeyValues::Target(iter, PhantomData)),
         }
     }
 }
@@ -145,6 +239,22 @@ impl RegistryKeyValue<'_, '_> {
         }
     }
 
+    pub fn qword_data(&self) -> Result<u64> {
+        match self {
+            #[cfg(target_os = "windows")]
+            Self::Local(value) => value.qword_data(),
+            Self::Target(value) => value.qword_data(),
+        }
+    }
+
+    pub fn raw_sz_data(&self) -> Result<Vec<u16>> {
+        match self {
+            #[cfg(target_os = "windows")]
+            Self::Local(value) => value.raw_sz_data(),
+            Self::Target(value) => value.raw_sz_data(),
+        }
+    }
+
     pub fn sz_data(&self) -> Result<String> {
         match self {
             #[cfg(target_os = "windows")]
@@ -157,7 +267,8 @@ impl RegistryKeyValue<'_, '_> {
 pub enum RegistryKeyValues<'d, 'h, 'n> {
     #[cfg(target_os = "windows")]
     Local(LocalRegistryKeyValues<'n>),
-    Target(TargetRegistryKeyValues<'d, 'h>),
+    /// The `PhantomData` keeps the `'n` lifetime in use on platforms without a local registry.
+    Target(TargetRegistryKeyValues<'d, 'h>, PhantomData<&'n ()>),
 }
 
 impl<'d, 'h> Iterator for RegistryKeyValues<'d, 'h, '_> {
@@ -167,7 +278,7 @@ impl<'d, 'h> Iterator for RegistryKeyValues<'d, 'h, '_> {
         match self {
             #[cfg(target_os = "windows")]
             Self::Local(iter) => Some(iter.next()?.map(RegistryKeyValue::Local)),
-            Self::Target(iter) => Some(iter.next()?.map(RegistryKeyValue::Target)),
+            Self::Target(iter, _) => Some(iter.next()?.map(RegistryKeyValue::Target)),
         }
     }
 }
@@ -175,7 +286,8 @@ impl<'d, 'h> Iterator for RegistryKeyValues<'d, 'h, '_> {
 pub enum RegistrySubKeys<'d, 'h, 'n> {
     #[cfg(target_os = "windows")]
     Local(LocalRegistrySubKeys<'n>),
-    Target(TargetRegistrySubKeys<'d, 'h>),
+    /// The `PhantomData` keeps the `'n` lifetime in use on platforms without a local registry.
+    Target(TargetRegistrySubKeys<'d, 'h>, PhantomData<&'n ()>),
 }
 
 impl<'d, 'h> Iterator for RegistrySubKeys<'d, 'h, '_> {
@@ -185,7 +297,47 @@ impl<'d, 'h> Iterator for RegistrySubKeys<'d, 'h, '_> {
         match self {
             #[cfg(target_os = "windows")]
             Self::Local(iter) => Some(iter.next()?.map(RegistryKeyNode::Local)),
-            Self::Target(iter) => Some(iter.next()?.map(RegistryKeyNode::Target)),
+            Self::Target(iter, _) => Some(iter.next()?.map(RegistryKeyNode::Target)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_worker() -> RegistryWorker {
+        let hive_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/basic/System32/config/SYSTEM");
+        RegistryWorker::new_target_hive_file(&hive_path).unwrap()
+    }
+
+    #[test]
+    fn test_find_subkey_and_value() {
+        let registry_worker = fixture_worker();
+        let hive = registry_worker.hive().unwrap();
+        let select_key_node = hive.key_node("Select").unwrap();
+        let root_key_node = hive.key_node("").unwrap();
+
+        let subkey = root_key_node
+            .find_subkey(|name| name.eq_ignore_ascii_case("select"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(subkey.name(), "Select");
+        assert!(root_key_node
+            .find_subkey(|name| name == "NonExistent")
+            .unwrap()
+            .is_none());
+
+        let value = select_key_node
+            .find_value(|name| name.eq_ignore_ascii_case("current"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(value.name(), "Current");
+        assert_eq!(value.dword_data().unwrap(), 1);
+        assert!(select_key_node
+            .find_value(|name| name == "NonExistent")
+            .unwrap()
+            .is_none());
+    }
+}
@@ -1,8 +1,8 @@
 // Copyright 2025 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use anyhow::{Context, Result};
-use winreg::enums::HKEY_LOCAL_MACHINE;
+use anyhow::{bail, Context, Result};
+use winreg::enums::{RegType, HKEY_LOCAL_MACHINE};
 use winreg::types::FromRegValue;
 use winreg::{EnumKeys, EnumValues, RegKey, RegValue};
 
@@ -14,6 +14,21 @@ impl LocalRegistryWorker {
         let system_key = hklm.open_subkey("SYSTEM")?;
         Ok(LocalRegistryHive { system_key })
     }
+
+    /// Returns the "SystemRoot" recorded in the SOFTWARE hive, falling back to the environment variable
+    /// of the same name.
+    pub fn system_root(&self) -> Result<String> {
+        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+        let system_root = hklm
+            .open_subkey("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion")
+            .and_then(|current_version| current_version.get_value::<String, _>("SystemRoot"));
+
+        match system_root {
+            Ok(system_root) => Ok(system_root),
+            Err(_) => std::env::var("SystemRoot")
+                .context("Could not read SystemRoot environment variable"),
+        }
+    }
 }
 
 pub struct LocalRegistryHive {
@@ -99,6 +114,28 @@ impl LocalRegistryKeyValue {
         &self.name
     }
 
+    pub fn qword_data(&self) -> Result<u64> {
+        let data = u64::from_reg_value(&self.value)?;
+        Ok(data)
+    }
+
+    /// Returns the UTF-16 code units of a string value without any conversion, including all NUL characters.
+    pub fn raw_sz_data(&self) -> Result<Vec<u16>> {
+        if !matches!(self.value.vtype, RegType::REG_SZ | RegType::REG_EXPAND_SZ) {
+            bail!(
+                "Value \"{}\" is not a REG_SZ or REG_EXPAND_SZ value",
+                self.name
+            );
+        }
+
+        Ok(self
+            .value
+            .bytes
+            .chunks_exact(2)
+            .map(|two_bytes| u16::from_le_bytes(two_bytes.try_into().unwrap()))
+            .collect())
+    }
+
     pub fn sz_data(&self) -> Result<String> {
         let data = String::from_reg_value(&self.value)?;
         Ok(data)
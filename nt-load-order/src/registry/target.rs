@@ -1,32 +1,198 @@
 // Copyright 2025 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::path::PathBuf;
+#[cfg(feature = "gzip")]
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
-use nt_hive::{Hive, KeyNode, KeyValue, KeyValueData, KeyValues, NtHiveError, SubKeyNodes};
+use log::debug;
+use nt_hive::{
+    Hive, KeyNode, KeyValue, KeyValueData, KeyValueDataType, KeyValues, NtHiveError, SubKeyNodes,
+};
 
 pub struct TargetRegistryWorker {
-    system_hive_data: Vec<u8>,
+    system_hive_data: HiveData,
 }
 
 impl TargetRegistryWorker {
-    pub fn new(system_root: &str) -> Result<Self> {
-        let mut system_path = PathBuf::from(system_root);
-        system_path.push("system32");
-        system_path.push("config");
-        system_path.push("SYSTEM");
+    pub fn new(system_root: &str, hive_file_name: &str) -> Result<Self> {
+        let system_path = system_hive_path(system_root, hive_file_name);
+        let worker = Self::from_hive_file(&system_path)?;
+        worker.ensure_system_hive(&system_path)?;
+        Ok(worker)
+    }
+
+    /// Reads any hive file (like a BCD store) instead of the SYSTEM hive of a system root.
+    pub fn from_hive_file(hive_path: &Path) -> Result<Self> {
+        let system_hive_data = std::fs::read(hive_path)
+            .with_context(|| format!("Could not read file \"{}\"", hive_path.display()))?;
+        let system_hive_data = decompress_if_needed(system_hive_data)
+            .with_context(|| format!("Could not decompress file \"{}\"", hive_path.display()))?;
+        let system_hive_data = replay_transaction_logs(hive_path, system_hive_data)?;
+
+        Ok(Self {
+            system_hive_data: HiveData::Owned(system_hive_data),
+        })
+    }
+
+    /// Like [`Self::new`], but memory-maps the SYSTEM hive instead of reading it into memory.
+    ///
+    /// Compressed hives still need to be decompressed into memory.
+    #[cfg(feature = "mmap")]
+    pub fn new_mapped(system_root: &str, hive_file_name: &str) -> Result<Self> {
+        let system_path = system_hive_path(system_root, hive_file_name);
 
-        let system_hive_data = std::fs::read(&system_path)
-            .with_context(|| format!("Could not read file \"{}\"", system_path.display()))?;
+        let file = std::fs::File::open(&system_path)
+            .with_context(|| format!("Could not open file \"{}\"", system_path.display()))?;
 
-        Ok(Self { system_hive_data })
+        // SAFETY: The hive file must not be modified while it is mapped.
+        // This is documented for `NtLoadOrder::memory_map_hive`.
+        let mmap = unsafe { memmap2::Mmap::map(&file) }
+            .with_context(|| format!("Could not map file \"{}\"", system_path.display()))?;
+
+        let system_hive_data = if mmap.starts_with(GZIP_MAGIC) {
+            let system_hive_data = decompress_if_needed(mmap.to_vec()).with_context(|| {
+                format!("Could not decompress file \"{}\"", system_path.display())
+            })?;
+            let system_hive_data = replay_transaction_logs(&system_path, system_hive_data)?;
+            HiveData::Owned(system_hive_data)
+        } else if cfg!(feature = "transaction-logs") && is_dirty(&mmap) {
+            // Transaction logs can only be applied to a copy in memory.
+            let system_hive_data = replay_transaction_logs(&system_path, mmap.to_vec())?;
+            HiveData::Owned(system_hive_data)
+        } else {
+            HiveData::Mapped(mmap)
+        };
+
+        let worker = Self { system_hive_data };
+        worker.ensure_system_hive(&system_path)?;
+        Ok(worker)
     }
 
-    pub fn hive(&self) -> Result<TargetRegistryHive> {
-        let hive = Hive::new(self.system_hive_data.as_ref()).context("Hive::new failed")?;
+    pub fn hive(&self) -> Result<TargetRegistryHive<'_>> {
+        let data = self.system_hive_data.as_ref();
+        let hive = if is_dirty(data) {
+            // Without replaying the transaction logs, we can still analyze the last flushed state.
+            debug!("The hive has not been fully flushed, analyzing its last flushed state");
+            Hive::without_validation(data).context("Hive::without_validation failed")?
+        } else {
+            Hive::new(data).context("Hive::new failed")?
+        };
         Ok(TargetRegistryHive { hive })
     }
+
+    /// Returns whether the hive looks like a SYSTEM hive, i.e. has a "Select" key and either "ControlSet001"
+    /// or the control set named by the "Current" value of "Select".
+    ///
+    /// This quickly catches other hives (like SOFTWARE) passed by mistake.
+    /// "CurrentControlSet" is not checked, because that link only exists in the registry of a running system.
+    pub fn is_system_hive(&self) -> bool {
+        let Ok(hive) = self.hive() else {
+            return false;
+        };
+        let Ok(select) = hive.key_node("Select") else {
+            return false;
+        };
+
+        if hive.key_node("ControlSet001").is_ok() {
+            return true;
+        }
+
+        select
+            .value("Current")
+            .and_then(|current| current.dword_data())
+            .is_ok_and(|current| hive.key_node(&format!("ControlSet{current:03}")).is_ok())
+    }
+
+    fn ensure_system_hive(&self, hive_path: &Path) -> Result<()> {
+        // Report errors parsing the hive as they are.
+        self.hive()?;
+
+        if !self.is_system_hive() {
+            bail!(
+                "\"{}\" doesn't look like a SYSTEM hive, because it has no \"Select\" key or no control set key",
+                hive_path.display()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Data of the SYSTEM hive, either read into memory or memory-mapped.
+enum HiveData {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl AsRef<[u8]> for HiveData {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(mmap) => mmap,
+        }
+    }
+}
+
+fn system_hive_path(system_root: &str, hive_file_name: &str) -> PathBuf {
+    // Spelled like the directory on disk and all other "System32" paths of this crate,
+    // so that the hive is also found on a case-sensitive file system.
+    let mut system_path = PathBuf::from(system_root);
+    system_path.push("System32");
+    system_path.push("config");
+    system_path.push(hive_file_name);
+    system_path
+}
+
+/// The magic bytes at the beginning of every gzip stream.
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// Decompresses the hive data if it has been compressed (as forensic hive dumps often are).
+/// Compressed data is detected by its magic bytes rather than the file extension.
+#[cfg(feature = "gzip")]
+fn decompress_if_needed(data: Vec<u8>) -> Result<Vec<u8>> {
+    if !data.starts_with(GZIP_MAGIC) {
+        return Ok(data);
+    }
+
+    let mut decompressed_data = Vec::new();
+    flate2::read::MultiGzDecoder::new(data.as_slice())
+        .read_to_end(&mut decompressed_data)
+        .context("Failed to decompress gzip data")?;
+
+    Ok(decompressed_data)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn decompress_if_needed(data: Vec<u8>) -> Result<Vec<u8>> {
+    if data.starts_with(GZIP_MAGIC) {
+        bail!("The hive is gzip-compressed, but nt-load-order has been built without the \"gzip\" feature");
+    }
+
+    Ok(data)
+}
+
+/// Returns whether the sequence numbers in the base block of the hive differ,
+/// meaning that it has not been fully flushed and its latest changes are only in its transaction logs.
+fn is_dirty(data: &[u8]) -> bool {
+    data.len() >= 12 && data[4..8] != data[8..12]
+}
+
+#[cfg(feature = "transaction-logs")]
+fn replay_transaction_logs(hive_path: &Path, data: Vec<u8>) -> Result<Vec<u8>> {
+    if !is_dirty(&data) {
+        return Ok(data);
+    }
+
+    super::transaction_logs::replay_transaction_logs(hive_path, data)
+}
+
+#[cfg(not(feature = "transaction-logs"))]
+fn replay_transaction_logs(_hive_path: &Path, data: Vec<u8>) -> Result<Vec<u8>> {
+    Ok(data)
 }
 
 pub struct TargetRegistryHive<'d> {
@@ -171,6 +337,49 @@ impl TargetRegistryKeyValue<'_, '_> {
         &self.name
     }
 
+    /// Returns the UTF-16 code units of a string value without any conversion, including all NUL characters.
+    pub fn raw_sz_data(&self) -> Result<Vec<u16>> {
+        let data_type = self
+            .key_value
+            .data_type()
+            .with_context(|| format!("KeyValue::data_type failed for value \"{}\"", self.name))?;
+        if !matches!(
+            data_type,
+            KeyValueDataType::RegSZ | KeyValueDataType::RegExpandSZ
+        ) {
+            bail!(
+                "Value \"{}\" is not a REG_SZ or REG_EXPAND_SZ value",
+                self.name
+            );
+        }
+
+        let key_value_data = self
+            .key_value
+            .data()
+            .with_context(|| format!("KeyValue::data failed for value \"{}\"", self.name))?;
+
+        // A long string may be split over several Big Data segments.
+        let data = match key_value_data {
+            KeyValueData::Small(small_data) => small_data.to_vec(),
+            KeyValueData::Big(big_data) => big_data
+                .collect::<Result<Vec<&[u8]>, NtHiveError>>()?
+                .concat(),
+        };
+
+        Ok(data
+            .chunks_exact(2)
+            .map(|two_bytes| u16::from_le_bytes(two_bytes.try_into().unwrap()))
+            .collect())
+    }
+
+    pub fn qword_data(&self) -> Result<u64> {
+        let data = self
+            .key_value
+            .qword_data()
+            .with_context(|| format!("KeyValue::qword_data failed for value \"{}\"", self.name))?;
+        Ok(data)
+    }
+
     pub fn sz_data(&self) -> Result<String> {
         let data = self
             .key_value
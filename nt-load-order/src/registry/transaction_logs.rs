@@ -0,0 +1,384 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Replay of the transaction logs ("SYSTEM.LOG1" and "SYSTEM.LOG2") of a hive that has not been fully flushed.
+//!
+//! Only the log format introduced with Windows 8.1 is supported.
+//! It is described in <https://github.com/msuhanov/regf/blob/master/Windows%20registry%20file%20format%20specification.md>.
+
+use std::ffi::OsString;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::debug;
+
+const BASE_BLOCK_SIZE: usize = 4096;
+/// Hive bins are always allocated in multiples of this size.
+const HBIN_ALIGNMENT: usize = 4096;
+/// Only the first sector of a base block is stored in a log file (and covered by the checksum).
+const LOG_BASE_BLOCK_SIZE: usize = 512;
+const LOG_ENTRY_HEADER_SIZE: usize = 40;
+const FILE_TYPE_PRIMARY: u32 = 0;
+const FILE_TYPE_LOG_NEW_FORMAT: u32 = 6;
+const MARVIN32_SEED: u64 = 0x82ef4d887a4e55c5;
+
+/// Applies the transaction logs next to the given hive file to the data of the dirty hive.
+pub fn replay_transaction_logs(hive_path: &Path, mut hive_data: Vec<u8>) -> Result<Vec<u8>> {
+    let mut logs = Vec::new();
+    for extension in [".LOG1", ".LOG2"] {
+        let mut log_path = OsString::from(hive_path);
+        log_path.push(extension);
+        let log_path = Path::new(&log_path);
+
+        if !log_path.exists() {
+            continue;
+        }
+
+        let log_data = std::fs::read(log_path)
+            .with_context(|| format!("Could not read file \"{}\"", log_path.display()))?;
+        match log_sequence_number(&log_data) {
+            Some(sequence_number) => logs.push((sequence_number, log_data)),
+            None => debug!(
+                "Ignoring \"{}\", which is not a valid transaction log in the Windows 8.1 format",
+                log_path.display()
+            ),
+        }
+    }
+
+    // Windows alternates between both logs, so the older one needs to be applied first.
+    logs.sort_by_key(|(sequence_number, _)| *sequence_number);
+
+    if !apply_logs(&mut hive_data, &logs) {
+        debug!(
+            "No applicable transaction log entries found for \"{}\", using its last flushed state",
+            hive_path.display()
+        );
+    }
+
+    Ok(hive_data)
+}
+
+/// Applies the entries of the given logs (sorted by their sequence numbers) to the hive data,
+/// and updates its base block accordingly.
+///
+/// Returns whether any entry has been applied.
+fn apply_logs(hive_data: &mut Vec<u8>, logs: &[(u32, Vec<u8>)]) -> bool {
+    // All changes up to the secondary sequence number have already been written to the hive.
+    let mut next_sequence_number = read_u32(hive_data, 8);
+    let mut last_log_base_block = None;
+    let primary_hive_bins_data_size = hive_data.len().saturating_sub(BASE_BLOCK_SIZE);
+
+    'logs: for (_, log_data) in logs {
+        // The hive can't grow by more than the data stored in the log.
+        let max_hive_bins_data_size = primary_hive_bins_data_size.saturating_add(log_data.len());
+
+        for entry in LogEntries::new(log_data) {
+            if entry.sequence_number < next_sequence_number {
+                continue;
+            } else if entry.sequence_number > next_sequence_number {
+                break;
+            }
+
+            if entry.hive_bins_data_size as usize > max_hive_bins_data_size {
+                debug!(
+                    "Stopping at transaction log entry {} with an implausible hive bins data size of {}",
+                    entry.sequence_number, entry.hive_bins_data_size
+                );
+                break 'logs;
+            }
+
+            let Some(following_sequence_number) = next_sequence_number.checked_add(1) else {
+                debug!(
+                    "Stopping at transaction log entry {}, because its sequence number cannot be incremented",
+                    entry.sequence_number
+                );
+                break 'logs;
+            };
+
+            debug!("Applying transaction log entry {}", entry.sequence_number);
+            apply_log_entry(hive_data, &entry);
+            next_sequence_number = following_sequence_number;
+            last_log_base_block = Some(&log_data[..LOG_BASE_BLOCK_SIZE]);
+        }
+    }
+
+    let Some(log_base_block) = last_log_base_block else {
+        return false;
+    };
+
+    // The base block of the log describes the hive after all its changes.
+    hive_data[..LOG_BASE_BLOCK_SIZE].copy_from_slice(log_base_block);
+    let sequence_number = next_sequence_number - 1;
+    write_u32(hive_data, 4, sequence_number);
+    write_u32(hive_data, 8, sequence_number);
+    write_u32(hive_data, 28, FILE_TYPE_PRIMARY);
+    let hive_bins_data_size = (hive_data.len() - BASE_BLOCK_SIZE) as u32;
+    write_u32(hive_data, 40, hive_bins_data_size);
+    let checksum = base_block_checksum(hive_data);
+    write_u32(hive_data, 508, checksum);
+
+    true
+}
+
+/// Returns the sequence number of the first entry of a log file, if it is a valid log file in the new format.
+fn log_sequence_number(log_data: &[u8]) -> Option<u32> {
+    if log_data.len() < LOG_BASE_BLOCK_SIZE
+        || &log_data[..4] != b"regf"
+        || read_u32(log_data, 28) != FILE_TYPE_LOG_NEW_FORMAT
+        || read_u32(log_data, 508) != base_block_checksum(log_data)
+    {
+        return None;
+    }
+
+    Some(read_u32(log_data, 4))
+}
+
+struct LogEntry<'a> {
+    sequence_number: u32,
+    hive_bins_data_size: u32,
+    /// Offsets (relative to the first hive bin) and data of the dirty pages.
+    dirty_pages: Vec<(usize, &'a [u8])>,
+}
+
+/// Iterates through the valid entries of a log file, stopping at the first invalid one.
+struct LogEntries<'a> {
+    log_data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> LogEntries<'a> {
+    fn new(log_data: &'a [u8]) -> Self {
+        Self {
+            log_data,
+            offset: LOG_BASE_BLOCK_SIZE,
+        }
+    }
+
+    fn parse_entry(&self) -> Option<(LogEntry<'a>, usize)> {
+        let entry_data = self.log_data.get(self.offset..)?;
+        if entry_data.len() < LOG_ENTRY_HEADER_SIZE || &entry_data[..4] != b"HvLE" {
+            return None;
+        }
+
+        let size = read_u32(entry_data, 4) as usize;
+        if size < LOG_ENTRY_HEADER_SIZE || size % 512 != 0 || size > entry_data.len() {
+            return None;
+        }
+        let entry_data = &entry_data[..size];
+
+        // Validate the hashes to detect entries that have not been fully written.
+        if read_u64(entry_data, 24) != marvin32(&entry_data[LOG_ENTRY_HEADER_SIZE..])
+            || read_u64(entry_data, 32) != marvin32(&entry_data[..32])
+        {
+            return None;
+        }
+
+        let sequence_number = read_u32(entry_data, 12);
+        let hive_bins_data_size = read_u32(entry_data, 16);
+        if hive_bins_data_size as usize % HBIN_ALIGNMENT != 0 {
+            return None;
+        }
+
+        let dirty_pages_count = read_u32(entry_data, 20) as usize;
+
+        let mut page_offset =
+            LOG_ENTRY_HEADER_SIZE.checked_add(dirty_pages_count.checked_mul(8)?)?;
+        let mut dirty_pages = Vec::with_capacity(dirty_pages_count);
+
+        for i in 0..dirty_pages_count {
+            let reference_offset = LOG_ENTRY_HEADER_SIZE + i * 8;
+            let offset = read_u32(entry_data, reference_offset) as usize;
+            let page_size = read_u32(entry_data, reference_offset + 4) as usize;
+
+            let page = entry_data.get(page_offset..page_offset.checked_add(page_size)?)?;
+            if offset.checked_add(page_size)? > hive_bins_data_size as usize {
+                return None;
+            }
+
+            dirty_pages.push((offset, page));
+            page_offset += page_size;
+        }
+
+        let entry = LogEntry {
+            sequence_number,
+            hive_bins_data_size,
+            dirty_pages,
+        };
+        Some((entry, size))
+    }
+}
+
+impl<'a> Iterator for LogEntries<'a> {
+    type Item = LogEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (entry, size) = self.parse_entry()?;
+        self.offset += size;
+        Some(entry)
+    }
+}
+
+fn apply_log_entry(hive_data: &mut Vec<u8>, entry: &LogEntry) {
+    let hive_size = BASE_BLOCK_SIZE + entry.hive_bins_data_size as usize;
+    hive_data.resize(hive_size, 0);
+
+    // Dirty pages have been checked to lie within the hive bins data when parsing the entry.
+    for (offset, page) in &entry.dirty_pages {
+        let start = BASE_BLOCK_SIZE + offset;
+        hive_data[start..start + page.len()].copy_from_slice(page);
+    }
+}
+
+/// Calculates the XOR-32 checksum of the first 508 bytes of a base block, like Windows does.
+fn base_block_checksum(base_block: &[u8]) -> u32 {
+    let checksum = base_block[..508]
+        .chunks_exact(4)
+        .fold(0, |checksum, dword| {
+            checksum ^ u32::from_le_bytes(dword.try_into().unwrap())
+        });
+
+    match checksum {
+        0 => 1,
+        u32::MAX => u32::MAX - 1,
+        checksum => checksum,
+    }
+}
+
+/// The Marvin32 hash function used for the log entry hashes.
+fn marvin32(data: &[u8]) -> u64 {
+    marvin32_with_seed(data, MARVIN32_SEED)
+}
+
+fn marvin32_with_seed(data: &[u8], seed: u64) -> u64 {
+    fn block(p0: &mut u32, p1: &mut u32) {
+        *p1 ^= *p0;
+        *p0 = p0.rotate_left(20);
+        *p0 = p0.wrapping_add(*p1);
+        *p1 = p1.rotate_left(9);
+        *p1 ^= *p0;
+        *p0 = p0.rotate_left(27);
+        *p0 = p0.wrapping_add(*p1);
+        *p1 = p1.rotate_left(19);
+    }
+
+    let mut p0 = seed as u32;
+    let mut p1 = (seed >> 32) as u32;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        p0 = p0.wrapping_add(u32::from_le_bytes(chunk.try_into().unwrap()));
+        block(&mut p0, &mut p1);
+    }
+
+    // Pad the remaining bytes with a single 0x80 byte.
+    let remainder = chunks.remainder();
+    let mut last = 0x80u32 << (8 * remainder.len());
+    for (i, byte) in remainder.iter().enumerate() {
+        last |= u32::from(*byte) << (8 * i);
+    }
+    p0 = p0.wrapping_add(last);
+    block(&mut p0, &mut p1);
+    block(&mut p0, &mut p1);
+
+    (u64::from(p1) << 32) | u64::from(p0)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn write_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a hive with the given secondary sequence number and a single empty hive bin.
+    fn hive_data(secondary_sequence_number: u32) -> Vec<u8> {
+        let mut hive_data = vec![0; BASE_BLOCK_SIZE + HBIN_ALIGNMENT];
+        write_u32(&mut hive_data, 8, secondary_sequence_number);
+        hive_data
+    }
+
+    /// Returns a log with a single entry, which fills the first hive bin with 0xff bytes.
+    fn log(sequence_number: u32, hive_bins_data_size: u32) -> (u32, Vec<u8>) {
+        let mut entry = vec![0; LOG_ENTRY_HEADER_SIZE + 8];
+        entry[..4].copy_from_slice(b"HvLE");
+        write_u32(&mut entry, 12, sequence_number);
+        write_u32(&mut entry, 16, hive_bins_data_size);
+        write_u32(&mut entry, 20, 1);
+        write_u32(&mut entry, LOG_ENTRY_HEADER_SIZE + 4, HBIN_ALIGNMENT as u32);
+        entry.extend([0xff; HBIN_ALIGNMENT]);
+        entry.resize(entry.len().next_multiple_of(512), 0);
+
+        let size = entry.len() as u32;
+        write_u32(&mut entry, 4, size);
+        let hash = marvin32(&entry[LOG_ENTRY_HEADER_SIZE..]);
+        entry[24..32].copy_from_slice(&hash.to_le_bytes());
+        let hash = marvin32(&entry[..32]);
+        entry[32..40].copy_from_slice(&hash.to_le_bytes());
+
+        let mut log_data = vec![0; LOG_BASE_BLOCK_SIZE];
+        log_data.extend(entry);
+        (sequence_number, log_data)
+    }
+
+    #[test]
+    fn test_apply_logs() {
+        let mut hive_data = hive_data(1);
+        assert!(apply_logs(&mut hive_data, &[log(1, HBIN_ALIGNMENT as u32)]));
+        assert_eq!(hive_data.len(), BASE_BLOCK_SIZE + HBIN_ALIGNMENT);
+        assert!(hive_data[BASE_BLOCK_SIZE..]
+            .iter()
+            .all(|byte| *byte == 0xff));
+        assert_eq!(read_u32(&hive_data, 8), 1);
+    }
+
+    #[test]
+    fn test_apply_logs_sequence_number_overflow() {
+        let mut hive_data = hive_data(u32::MAX);
+        assert!(!apply_logs(
+            &mut hive_data,
+            &[log(u32::MAX, HBIN_ALIGNMENT as u32)]
+        ));
+        assert_eq!(hive_data, self::hive_data(u32::MAX));
+    }
+
+    #[test]
+    fn test_apply_logs_implausible_size() {
+        // Far more than the hive and the log.
+        let mut hive_data = hive_data(1);
+        assert!(!apply_logs(&mut hive_data, &[log(1, 0x4000_0000)]));
+        assert_eq!(hive_data, self::hive_data(1));
+
+        // Not a multiple of the hive bin size.
+        assert!(!apply_logs(
+            &mut hive_data,
+            &[log(1, HBIN_ALIGNMENT as u32 + 1)]
+        ));
+        assert_eq!(hive_data, self::hive_data(1));
+    }
+
+    #[test]
+    fn test_marvin32() {
+        // Test vectors of the reference implementation.
+        const SEED: u64 = 0x004fb61a001bdbcc;
+        assert_eq!(marvin32_with_seed(&[], SEED), 0x30ed35c100cd3c7d);
+        assert_eq!(marvin32_with_seed(&[0xaf], SEED), 0x48e73fc77d75ddc1);
+        assert_eq!(marvin32_with_seed(&[0xe7, 0x0f], SEED), 0xb5f6e1fc485dbff8);
+        assert_eq!(
+            marvin32_with_seed(&[0x37, 0xf4, 0x95], SEED),
+            0xf0b07c789b8cf7e8
+        );
+        assert_eq!(
+            marvin32_with_seed(&[0x86, 0x42, 0xdc, 0x59], SEED),
+            0x7008f2e87e9cf556
+        );
+    }
+}
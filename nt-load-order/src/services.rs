@@ -0,0 +1,141 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use anyhow::{Context, Result};
+
+use crate::registry::{RegistryKeyValue, RegistryWorker};
+
+/// Decoded data of a registry value, returned as part of [`ServiceRaw`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RegistryValueData {
+    Dword(u32),
+    Qword(u64),
+    /// A REG_SZ or REG_EXPAND_SZ value (without expanding any environment variables).
+    String(String),
+    MultiString(Vec<String>),
+    /// Any other value, including REG_BINARY.
+    Binary(Vec<u8>),
+    /// A value whose data could not be read, along with the error.
+    Unreadable(String),
+}
+
+/// A service key with all its values, as returned by [`NtLoadOrder::get_raw_services`](crate::NtLoadOrder::get_raw_services).
+///
+/// The well-known values are decoded into their own fields, which are `None` (or empty)
+/// if the value doesn't exist or has an unexpected type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServiceRaw {
+    /// Name of the service key.
+    pub name: String,
+    pub start: Option<u32>,
+    pub group: Option<String>,
+    pub tag: Option<u32>,
+    pub image_path: Option<String>,
+    pub service_type: Option<u32>,
+    pub error_control: Option<u32>,
+    pub depend_on_service: Vec<String>,
+    pub depend_on_group: Vec<String>,
+    /// All values of the service key in the order they are stored, including the ones above.
+    pub values: Vec<(String, RegistryValueData)>,
+}
+
+impl ServiceRaw {
+    /// Returns the data of the value with the given name (compared case-insensitively).
+    pub fn value(&self, name: &str) -> Option<&RegistryValueData> {
+        self.values
+            .iter()
+            .find(|(value_name, _)| value_name.eq_ignore_ascii_case(name))
+            .map(|(_, data)| data)
+    }
+
+    fn dword(&self, name: &str) -> Option<u32> {
+        match self.value(name)? {
+            RegistryValueData::Dword(data) => Some(*data),
+            _ => None,
+        }
+    }
+
+    fn string(&self, name: &str) -> Option<String> {
+        match self.value(name)? {
+            RegistryValueData::String(data) => Some(data.clone()),
+            _ => None,
+        }
+    }
+
+    fn string_list(&self, name: &str) -> Vec<String> {
+        match self.value(name) {
+            Some(RegistryValueData::MultiString(data)) => data.clone(),
+            Some(RegistryValueData::String(data)) => vec![data.clone()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Reads every service key of the given control set, regardless of its "Start" value.
+pub(crate) fn raw_services(
+    registry_worker: &RegistryWorker,
+    control_set_key_name: &str,
+) -> Result<Vec<ServiceRaw>> {
+    let hive = registry_worker.hive()?;
+    let services_key_node = hive
+        .key_node(&format!("{control_set_key_name}\\Services"))
+        .with_context(|| {
+            format!("Control set \"{control_set_key_name}\" has no \"Services\" subkey")
+        })?;
+
+    let mut services = Vec::new();
+    for service in services_key_node.subkeys()? {
+        let service = service?;
+
+        let mut values = Vec::new();
+        for value in service.values()? {
+            let value = value?;
+            values.push((value.name().to_string(), decode_value(&value)));
+        }
+
+        let mut service = ServiceRaw {
+            name: service.name().to_string(),
+            start: None,
+            group: None,
+            tag: None,
+            image_path: None,
+            service_type: None,
+            error_control: None,
+            depend_on_service: Vec::new(),
+            depend_on_group: Vec::new(),
+            values,
+        };
+        service.start = service.dword("Start");
+        service.group = service.string("Group");
+        service.tag = service.dword("Tag");
+        service.image_path = service.string("ImagePath");
+        service.service_type = service.dword("Type");
+        service.error_control = service.dword("ErrorControl");
+        service.depend_on_service = service.string_list("DependOnService");
+        service.depend_on_group = service.string_list("DependOnGroup");
+
+        services.push(service);
+    }
+
+    Ok(services)
+}
+
+/// Decodes a value by trying the accessors of all supported types, falling back to the raw bytes.
+///
+/// Errors are kept in the returned data, so a single unreadable value doesn't hide all others.
+fn decode_value(value: &RegistryKeyValue) -> RegistryValueData {
+    if let Ok(data) = value.dword_data() {
+        RegistryValueData::Dword(data)
+    } else if let Ok(data) = value.qword_data() {
+        RegistryValueData::Qword(data)
+    } else if let Ok(data) = value.multi_sz_data() {
+        RegistryValueData::MultiString(data)
+    } else if let Ok(data) = value.sz_data() {
+        RegistryValueData::String(data)
+    } else {
+        match value.binary_data() {
+            Ok(data) => RegistryValueData::Binary(data),
+            Err(e) => RegistryValueData::Unreadable(format!("{e:#}")),
+        }
+    }
+}
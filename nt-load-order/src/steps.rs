@@ -3,14 +3,30 @@
 
 mod add_imports;
 mod add_kernel_binaries;
+mod apply_driver_verifier;
+mod detect_kd_drivers;
+mod exclude_services;
+mod load_boot_execute;
 mod load_from_registry;
+mod pin_services;
 mod sort_by_hardcoded_groups;
 mod sort_by_hardcoded_service_lists;
 mod sort_by_tag_and_group;
 
-pub use add_imports::add_imports;
+pub use add_imports::{
+    add_imports, import_closure, load_apiset_schema_info, resolve_image_path,
+    APISETSCHEMA_IMAGE_PATH,
+};
 pub use add_kernel_binaries::{add_basic_kernel_binaries, add_kernel_binary};
-pub use load_from_registry::load_from_registry;
+pub use apply_driver_verifier::apply_driver_verifier;
+pub use detect_kd_drivers::detect_kd_drivers;
+pub use exclude_services::{exclude_services, warn_about_excluded_imports};
+pub use load_boot_execute::load_boot_execute;
+pub use load_from_registry::{
+    hardware_config_ids, load_from_registry, GroupOrderMismatches, LoadFromRegistryOptions,
+    RegistryInfo,
+};
+pub use pin_services::pin_services;
 pub use sort_by_hardcoded_groups::sort_by_hardcoded_groups;
 pub use sort_by_hardcoded_service_lists::sort_by_hardcoded_service_lists;
 pub use sort_by_tag_and_group::sort_by_tag_and_group;
@@ -34,7 +50,10 @@ pub(crate) fn move_matching_elements_to_front<F>(
 ) where
     F: FnMut(&mut NtLoadOrderEntry) -> bool,
 {
-    let mut current = entries.back_index().unwrap();
+    let Some(mut current) = entries.back_index() else {
+        // There is nothing to move in an empty list.
+        return;
+    };
 
     // Push group members to front.
     loop {
@@ -65,3 +84,117 @@ pub(crate) fn move_matching_elements_to_front<F>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::NtLoadOrderEntryKind;
+
+    fn entries(names: &[&str]) -> VecList<NtLoadOrderEntry> {
+        names
+            .iter()
+            .map(|name| {
+                NtLoadOrderEntry::new(
+                    name.to_string(),
+                    String::new(),
+                    String::new(),
+                    NtLoadOrderEntryKind::Service,
+                )
+            })
+            .collect()
+    }
+
+    fn names(entries: &VecList<NtLoadOrderEntry>) -> Vec<&str> {
+        entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_move_single_element() {
+        let mut entries = entries(&["a", "b", "c"]);
+        let mut first_moved = None;
+        move_matching_elements_to_front(&mut entries, &mut first_moved, |entry| entry.name == "c");
+
+        assert_eq!(names(&entries), ["c", "a", "b"]);
+        assert_eq!(entries.get(first_moved.unwrap()).unwrap().name, "c");
+    }
+
+    #[test]
+    fn test_move_multiple_elements() {
+        let mut entries = entries(&["a", "b", "c", "d"]);
+        let mut first_moved = None;
+        move_matching_elements_to_front(&mut entries, &mut first_moved, |entry| {
+            entry.name == "b" || entry.name == "d"
+        });
+
+        // The moved elements keep their relative order.
+        assert_eq!(names(&entries), ["b", "d", "a", "c"]);
+        assert_eq!(entries.get(first_moved.unwrap()).unwrap().name, "d");
+    }
+
+    #[test]
+    fn test_move_front_element() {
+        // Moving the element that is already at the front must not call `move_before` with itself.
+        let mut entries = entries(&["a", "b", "c"]);
+        let mut first_moved = None;
+        move_matching_elements_to_front(&mut entries, &mut first_moved, |entry| entry.name == "a");
+
+        assert_eq!(names(&entries), ["a", "b", "c"]);
+        assert_eq!(entries.get(first_moved.unwrap()).unwrap().name, "a");
+    }
+
+    #[test]
+    fn test_move_no_element() {
+        let mut entries = entries(&["a", "b"]);
+        let mut first_moved = None;
+        move_matching_elements_to_front(&mut entries, &mut first_moved, |_| false);
+
+        assert_eq!(names(&entries), ["a", "b"]);
+        assert!(first_moved.is_none());
+    }
+
+    #[test]
+    fn test_move_in_empty_list() {
+        let mut entries = entries(&[]);
+        let mut first_moved = None;
+        move_matching_elements_to_front(&mut entries, &mut first_moved, |_| true);
+
+        assert!(entries.is_empty());
+        assert!(first_moved.is_none());
+    }
+
+    #[test]
+    fn test_sorts_on_empty_list() {
+        let registry_info = RegistryInfo {
+            entries: Vec::new(),
+            groups: HashMap::new(),
+            service_group_order: vec!["Boot Bus Extender".to_string()],
+        };
+        let mut entries = sort_by_tag_and_group(registry_info, true);
+        sort_by_hardcoded_groups(&mut entries);
+        sort_by_hardcoded_service_lists(&mut entries);
+
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn test_move_stops_at_first_moved() {
+        let mut entries = entries(&["a", "b", "c", "d"]);
+        let mut first_moved = None;
+        move_matching_elements_to_front(&mut entries, &mut first_moved, |entry| entry.name == "c");
+        assert_eq!(names(&entries), ["c", "a", "b", "d"]);
+
+        // The second call must not look at "c" and anything before it, which has already been sorted.
+        // Otherwise, it would move "c" again and loop forever.
+        let mut visited = Vec::new();
+        move_matching_elements_to_front(&mut entries, &mut first_moved, |entry| {
+            visited.push(entry.name.clone());
+            entry.name == "b" || entry.name == "c"
+        });
+
+        assert_eq!(visited, ["d", "b", "a"]);
+        assert_eq!(names(&entries), ["b", "c", "a", "d"]);
+        assert_eq!(entries.get(first_moved.unwrap()).unwrap().name, "c");
+    }
+}
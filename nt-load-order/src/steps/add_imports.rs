@@ -1,138 +1,665 @@
 // Copyright 2025 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 use anyhow::{bail, Context, Result};
-use dlv_list::VecList;
+use dlv_list::{Index, VecList};
+use log::{debug, trace};
 use nt_apiset::ApiSetMap;
-use pelite::pe64::{Pe, PeFile};
-use pelite::FileMap;
+use pelite::image::{
+    IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG, IMAGE_DIRECTORY_ENTRY_SECURITY, IMAGE_FILE_MACHINE_AMD64,
+};
+use pelite::pe64::{self, Pe};
+use pelite::{FileMap, PeFile, Wrap};
 
-use crate::NtLoadOrderEntry;
+use crate::apiset_resolver::{is_apiset_name, ApiSetMapResolver, ApiSetResolver, IdentityResolver};
+use crate::{
+    ApiSetSchemaInfo, ImageFileStats, ImportStats, NtLoadOrderEntry, NtLoadOrderEntryKind,
+};
 
 pub fn add_imports(
     mut entries: VecList<NtLoadOrderEntry>,
-    system_root: String,
+    system_roots: Vec<String>,
+    drive_mappings: Option<HashMap<char, String>>,
+    strict_imports: bool,
+    expand_kernel_binary_imports: bool,
+    kernel_binary_imports_first: bool,
+    import_stats: Option<&mut ImportStats>,
 ) -> Result<VecList<NtLoadOrderEntry>> {
     // Prepare the path handler.
-    let path_handler = PathHandler::new(system_root);
+    let path_handler = PathHandler::new(system_roots, drive_mappings);
 
     // Load the apisetschema.dll
-    let apisetschema_file_path = path_handler.full_path_name("System32\\apisetschema.dll");
-    let apisetschema_file_map = FileMap::open(&apisetschema_file_path)
-        .with_context(|| format!("FileMap::open failed for \"{apisetschema_file_path}\""))?;
-    let apisetschema_pe_file = PeFile::from_bytes(&apisetschema_file_map)
-        .with_context(|| format!("PeFile::from_bytes failed for \"{apisetschema_file_path}\""))?;
-    let apiset_map = ApiSetMap::try_from_pe64(apisetschema_pe_file).with_context(|| {
-        format!("ApiSetMap::try_from_pe64 failed for \"{apisetschema_file_path}\"")
-    })?;
+    let apisetschema_file_path = path_handler.system_root_path(APISETSCHEMA_IMAGE_PATH);
+    let apisetschema_file_map = open_apiset_schema(&apisetschema_file_path)?;
+    let apiset_resolver =
+        new_apiset_resolver(apisetschema_file_map.as_ref(), &apisetschema_file_path)?;
+
+    // Some services omit the ".sys" extension or use ".dll" where a ".sys" file exists (and vice versa).
+    for entry in entries
+        .iter_mut()
+        .filter(|entry| entry.kind != NtLoadOrderEntryKind::KernelBinary)
+    {
+        apply_alternative_extension(&path_handler, entry);
+    }
 
     // Prepare the import handler.
-    let mut import_handler = ImportHandler::new(&path_handler, apiset_map);
+    let mut import_handler = ImportHandler::new(&path_handler, apiset_resolver, strict_imports);
+    if import_stats.is_some() {
+        import_handler.import_stats = Some(ImportStats::default());
+    }
+
+    // Remember the services by image path, so that imports of registered services keep their metadata.
+    import_handler.services = entries
+        .iter()
+        .skip_while(|entry| entry.kind == NtLoadOrderEntryKind::KernelBinary)
+        .map(|entry| (entry.image_path.to_ascii_lowercase(), entry.clone()))
+        .collect();
 
     // The hardcoded kernel binaries are treated differently than the remaining services.
     // They have fixed positions at the beginning of the list and don't move anymore.
     // Achieve this by adding them to `loaded_image_paths` before calling `handle_image`.
-    for entry in entries.iter().take_while(|entry| entry.is_kernel_binary) {
+    let mut drain = entries.drain().peekable();
+    let mut kernel_binary_indexes = Vec::new();
+
+    while let Some(entry) = drain.next_if(|entry| entry.kind == NtLoadOrderEntryKind::KernelBinary)
+    {
         import_handler
             .loaded_image_paths
             .insert(entry.image_path.to_ascii_lowercase());
-        import_handler.entries.push_back(entry.clone());
+        kernel_binary_indexes.push(import_handler.entries.push_back(entry));
     }
 
-    // Now add the imports of the passed kernel binaries.
-    let mut drain = entries.drain();
-    let mut current = drain.next();
+    // Pinned services directly follow the kernel binaries and get the same treatment.
+    let mut pinned_indexes = Vec::new();
 
-    while let Some(entry) = &current {
-        if !entry.is_kernel_binary {
-            break;
-        }
+    while let Some(entry) = drain.next_if(|entry| entry.pinned) {
+        import_handler
+            .loaded_image_paths
+            .insert(entry.image_path.to_ascii_lowercase());
+        pinned_indexes.push(import_handler.entries.push_back(entry));
+    }
 
-        import_handler.handle_image(&entry.image_path)?;
-        current = drain.next();
+    if !expand_kernel_binary_imports {
+        debug!("Not adding the imports of the kernel binaries");
+        kernel_binary_indexes.clear();
+    }
+
+    // Now add the imports of the passed kernel binaries.
+    if kernel_binary_imports_first {
+        add_kernel_binary_imports(&mut import_handler, &kernel_binary_indexes)?;
+        add_pinned_imports(&mut import_handler, &pinned_indexes)?;
     }
 
     // Handle the remaining services.
-    while let Some(entry) = current {
-        if import_handler
+    let mut boot_execute_entries = Vec::new();
+
+    for mut entry in drain {
+        if entry.kind == NtLoadOrderEntryKind::BootExecute {
+            // These run after all drivers, and we don't add the imports of user-mode applications.
+            boot_execute_entries.push(entry);
+        } else if import_handler
             .loaded_image_paths
             .insert(entry.image_path.to_ascii_lowercase())
         {
+            debug!("Adding imports of \"{}\"", entry.image_path);
+
+            if path_handler.full_path_name(&entry.image_path).is_none() {
+                // This image is located on a volume that we have no mapping for.
+                // Keep it in the load order, but we can't analyze its imports.
+                entry.reason = format!("{}, located outside of the system root", entry.reason);
+            }
+
             // Add the service first, then handle it for adding its imports.
             let entry_image_path = entry.image_path.clone();
-            import_handler.entries.push_back(entry);
-            import_handler.handle_image(&entry_image_path)?;
+            let index = import_handler.entries.push_back(entry);
+            let properties = import_handler.handle_image(&entry_image_path)?;
+            import_handler.apply_image_properties(index, properties);
+        } else {
+            debug!(
+                "\"{}\" has already been loaded as an import",
+                entry.image_path
+            );
         }
+    }
+
+    if !kernel_binary_imports_first {
+        add_kernel_binary_imports(&mut import_handler, &kernel_binary_indexes)?;
+        add_pinned_imports(&mut import_handler, &pinned_indexes)?;
+    }
+
+    for entry in boot_execute_entries {
+        import_handler.entries.push_back(entry);
+    }
 
-        current = drain.next();
+    if let (Some(import_stats), Some(collected_stats)) =
+        (import_stats, import_handler.import_stats.take())
+    {
+        *import_stats = collected_stats;
     }
 
     Ok(import_handler.entries)
 }
 
+/// Replaces the image path of a service whose file doesn't exist by one with another extension whose file does.
+fn apply_alternative_extension(path_handler: &PathHandler, entry: &mut NtLoadOrderEntry) {
+    let Some(file_path) = path_handler.full_path_name(&entry.image_path) else {
+        return;
+    };
+    if file_path.exists() {
+        return;
+    }
+
+    for alternative_image_path in alternative_image_paths(&entry.image_path) {
+        if path_handler
+            .full_path_name(&alternative_image_path)
+            .is_some_and(|file_path| file_path.exists())
+        {
+            debug!(
+                "Using \"{alternative_image_path}\" for the missing \"{}\"",
+                entry.image_path
+            );
+            entry.reason = format!(
+                "{}, using \"{alternative_image_path}\" instead of the missing \"{}\"",
+                entry.reason, entry.image_path
+            );
+            entry.image_path = alternative_image_path;
+            return;
+        }
+    }
+}
+
+/// Returns the image paths to try if the file of the given image path doesn't exist.
+fn alternative_image_paths(image_path: &str) -> Vec<String> {
+    let file_name_start = image_path.rfind('\\').map_or(0, |index| index + 1);
+
+    let Some(dot_index) = image_path[file_name_start..].rfind('.') else {
+        return vec![format!("{image_path}.sys"), format!("{image_path}.dll")];
+    };
+
+    let (stem, extension) = image_path.split_at(file_name_start + dot_index);
+    if extension.eq_ignore_ascii_case(".sys") {
+        vec![format!("{stem}.dll")]
+    } else if extension.eq_ignore_ascii_case(".dll") {
+        vec![format!("{stem}.sys")]
+    } else {
+        Vec::new()
+    }
+}
+
+fn add_kernel_binary_imports(
+    import_handler: &mut ImportHandler,
+    kernel_binary_indexes: &[Index<NtLoadOrderEntry>],
+) -> Result<()> {
+    for &index in kernel_binary_indexes {
+        let entry = import_handler.entries.get(index).unwrap();
+        let name = entry.name.clone();
+        let image_path = entry.image_path.clone();
+
+        // Kernel binaries are always added, even if they don't exist (e.g. a misspelled KD driver).
+        // Give a clear error message instead of failing in `handle_image`.
+        if let Some(file_path) = import_handler.path_handler.full_path_name(&image_path) {
+            if !file_path.exists() {
+                bail!(
+                    "Kernel binary \"{name}\" does not exist at \"{}\"",
+                    file_path.display()
+                );
+            }
+        }
+
+        let properties = import_handler.handle_image(&image_path)?;
+        import_handler.apply_image_properties(index, properties);
+    }
+
+    Ok(())
+}
+
+fn add_pinned_imports(
+    import_handler: &mut ImportHandler,
+    pinned_indexes: &[Index<NtLoadOrderEntry>],
+) -> Result<()> {
+    for &index in pinned_indexes {
+        let image_path = import_handler
+            .entries
+            .get(index)
+            .unwrap()
+            .image_path
+            .clone();
+        debug!("Adding imports of pinned \"{image_path}\"");
+
+        let properties = import_handler.handle_image(&image_path)?;
+        import_handler.apply_image_properties(index, properties);
+    }
+
+    Ok(())
+}
+
+/// Returns the transitive imports of a single image in the order they are loaded, without the image itself.
+pub fn import_closure(
+    image_path: &str,
+    system_roots: Vec<String>,
+    drive_mappings: Option<HashMap<char, String>>,
+    strict_imports: bool,
+) -> Result<VecList<NtLoadOrderEntry>> {
+    let path_handler = PathHandler::new(system_roots, drive_mappings);
+
+    let apisetschema_file_path = path_handler.system_root_path(APISETSCHEMA_IMAGE_PATH);
+    let apisetschema_file_map = open_apiset_schema(&apisetschema_file_path)?;
+    let apiset_resolver =
+        new_apiset_resolver(apisetschema_file_map.as_ref(), &apisetschema_file_path)?;
+
+    let Some(file_path) = path_handler.full_path_name(image_path) else {
+        bail!("\"{image_path}\" is located outside of the system root");
+    };
+    if !file_path.exists() {
+        bail!(
+            "\"{image_path}\" does not exist at \"{}\"",
+            file_path.display()
+        );
+    }
+
+    // Mark the image as loaded, so that circular imports don't add it again.
+    let mut import_handler = ImportHandler::new(&path_handler, apiset_resolver, strict_imports);
+    import_handler
+        .loaded_image_paths
+        .insert(image_path.to_ascii_lowercase());
+    import_handler.handle_image(image_path)?;
+
+    Ok(import_handler.entries)
+}
+
+/// Opens the "apisetschema.dll", returning `None` if it doesn't exist.
+///
+/// Windows versions before 7 don't have API Sets at all, and minimal images may lack this file.
+/// Imports are then resolved by their file names alone.
+fn open_apiset_schema(file_path: &Path) -> Result<Option<FileMap>> {
+    if !file_path.exists() {
+        debug!(
+            "\"{}\" does not exist, not resolving API Sets",
+            file_path.display()
+        );
+        return Ok(None);
+    }
+
+    let file_map = FileMap::open(file_path)
+        .with_context(|| format!("FileMap::open failed for \"{}\"", file_path.display()))?;
+    Ok(Some(file_map))
+}
+
+/// Returns the resolver for API Set imports, which needs an API Set Map from the "apisetschema.dll".
+/// Without one, imports are resolved by their file names alone.
+fn new_apiset_resolver<'a>(
+    file_map: Option<&'a FileMap>,
+    file_path: &Path,
+) -> Result<Box<dyn ApiSetResolver + 'a>> {
+    match file_map {
+        Some(file_map) => {
+            let apiset_map = parse_apiset_map(file_map, file_path)?;
+            Ok(Box::new(ApiSetMapResolver::new(apiset_map)))
+        }
+        None => Ok(Box::new(IdentityResolver)),
+    }
+}
+
+fn parse_apiset_map<'a>(file_map: &'a FileMap, file_path: &Path) -> Result<ApiSetMap<'a>> {
+    let pe_file = pe64::PeFile::from_bytes(file_map)
+        .with_context(|| format!("PeFile::from_bytes failed for \"{}\"", file_path.display()))?;
+    let apiset_map = ApiSetMap::try_from_pe64(pe_file).with_context(|| {
+        format!(
+            "ApiSetMap::try_from_pe64 failed for \"{}\"",
+            file_path.display()
+        )
+    })?;
+
+    Ok(apiset_map)
+}
+
+/// Returns the path and API Set Map version of the "apisetschema.dll" that [`add_imports`] loads.
+pub fn load_apiset_schema_info(system_roots: Vec<String>) -> Result<ApiSetSchemaInfo> {
+    let path = PathHandler::new(system_roots, None).system_root_path(APISETSCHEMA_IMAGE_PATH);
+
+    let file_map = FileMap::open(&path)
+        .with_context(|| format!("FileMap::open failed for \"{}\"", path.display()))?;
+    let pe_file = pe64::PeFile::from_bytes(&file_map)
+        .with_context(|| format!("PeFile::from_bytes failed for \"{}\"", path.display()))?;
+
+    let section_header = pe_file
+        .section_headers()
+        .by_name(".apiset")
+        .with_context(|| format!("\"{}\" has no .apiset section", path.display()))?;
+    let section_bytes = pe_file.get_section_bytes(section_header).with_context(|| {
+        format!(
+            "Could not read the .apiset section of \"{}\"",
+            path.display()
+        )
+    })?;
+
+    // Every API Set Map begins with its version number.
+    let version = section_bytes
+        .get(..mem::size_of::<u32>())
+        .with_context(|| format!("The .apiset section of \"{}\" is too small", path.display()))?;
+    let version = u32::from_le_bytes(version.try_into().unwrap());
+
+    Ok(ApiSetSchemaInfo { path, version })
+}
+
+/// Returns the full path to an image path relative to the given system roots, like it is resolved for imports.
+pub fn resolve_image_path(
+    image_path: &str,
+    system_roots: Vec<String>,
+    drive_mappings: Option<HashMap<char, String>>,
+) -> Option<PathBuf> {
+    PathHandler::new(system_roots, drive_mappings).full_path_name(image_path)
+}
+
+/// Path to the API Set Schema, relative to the system root.
+pub const APISETSCHEMA_IMAGE_PATH: &str = "System32\\apisetschema.dll";
+
 struct PathHandler {
-    system_root: String,
+    /// Directories layered on top of each other to form the system root, with the topmost one first.
+    /// This always contains at least one directory.
+    system_roots: Vec<String>,
+    /// Mappings from uppercase drive letters to directories, used for resolving absolute image paths.
+    /// This is `None` when analyzing the local system, where absolute image paths can be opened as they are.
+    drive_mappings: Option<HashMap<char, String>>,
 }
 
 impl PathHandler {
-    fn new(system_root: String) -> Self {
-        Self { system_root }
+    fn new(system_roots: Vec<String>, drive_mappings: Option<HashMap<char, String>>) -> Self {
+        let drive_mappings = drive_mappings.map(|drive_mappings| {
+            drive_mappings
+                .into_iter()
+                .map(|(drive, path)| (drive.to_ascii_uppercase(), path))
+                .collect()
+        });
+
+        assert!(!system_roots.is_empty());
+
+        Self {
+            system_roots,
+            drive_mappings,
+        }
+    }
+
+    /// Returns the full path to the given image path, as found in the registry or determined via
+    /// [`Self::get_image_path`].
+    ///
+    /// Returns `None` if the image path points to a location outside of the system root,
+    /// which cannot be mapped to a directory of the analyzed system.
+    fn full_path_name(&self, image_path: &str) -> Option<PathBuf> {
+        // Image paths may be prefixed with the "\??\" object directory.
+        let image_path = image_path.strip_prefix("\\??\\").unwrap_or(image_path);
+
+        if let Some(prefix) = image_path.get(..SYSTEM_ROOT_PREFIX.len()) {
+            if prefix.eq_ignore_ascii_case(SYSTEM_ROOT_PREFIX) {
+                return Some(self.system_root_path(&image_path[SYSTEM_ROOT_PREFIX.len()..]));
+            }
+        }
+
+        let bytes = image_path.as_bytes();
+        let is_absolute = bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && bytes[2] == b'\\';
+        let is_rooted = bytes.first() == Some(&b'\\');
+
+        if !is_absolute && !is_rooted {
+            // This is the common case of an image path relative to the system root.
+            return Some(self.system_root_path(image_path));
+        }
+
+        let Some(drive_mappings) = &self.drive_mappings else {
+            // We are analyzing the local system, so the path can be opened as it is.
+            return Some(PathBuf::from(image_path));
+        };
+
+        if is_absolute {
+            let drive = char::from(bytes[0].to_ascii_uppercase());
+            let root = drive_mappings.get(&drive)?;
+            Some(join_path(root, &image_path[3..]))
+        } else {
+            // A rooted path without a drive letter refers to the boot volume of the running system,
+            // which we can't determine for a target system.
+            None
+        }
     }
 
-    fn full_path_name(&self, image_path: &str) -> String {
-        format!("{}\\{image_path}", self.system_root)
+    /// Returns the full path to a path relative to the system root, taken from the topmost layer that has it.
+    ///
+    /// If no layer has it, the path in the topmost layer is returned.
+    fn system_root_path(&self, relative_path: &str) -> PathBuf {
+        let mut paths = self
+            .system_roots
+            .iter()
+            .map(|system_root| join_path(system_root, relative_path));
+        let topmost_path = paths.next().unwrap();
+
+        if topmost_path.exists() {
+            topmost_path
+        } else {
+            paths.find(|path| path.exists()).unwrap_or(topmost_path)
+        }
     }
 
-    fn get_image_path(&self, file_name: &str) -> Result<String> {
+    /// Returns the image path of an imported file, searching the same directories as the bootloader.
+    ///
+    /// Imports of 32-bit images are looked up in "SysWOW64" first, which holds the 32-bit binaries
+    /// on 64-bit Windows and doesn't exist on 32-bit Windows.
+    ///
+    /// "Control\Session Manager\KnownDlls" is deliberately not consulted:
+    /// KnownDlls are a section object directory created by the Session Manager for user-mode processes.
+    /// The bootloader loads boot drivers and their imports long before that and never looks at this key.
+    fn get_image_path(&self, file_name: &str, importer_32bit: bool) -> Result<String> {
+        // Look in "SysWOW64" for imports of 32-bit images
+        if importer_32bit {
+            let image_path = format!("SysWOW64\\{file_name}");
+            if self.system_root_path(&image_path).exists() {
+                return Ok(image_path);
+            }
+        }
+
         // Look in "system32\drivers"
         let image_path = format!("System32\\drivers\\{file_name}");
-        let check_path = self.full_path_name(&image_path);
-        if Path::new(&check_path).exists() {
+        if self.system_root_path(&image_path).exists() {
             return Ok(image_path);
         }
 
         // Look in "system32"
         let image_path = format!("System32\\{file_name}");
-        let check_path = self.full_path_name(&image_path);
-        if Path::new(&check_path).exists() {
+        if self.system_root_path(&image_path).exists() {
             return Ok(image_path);
         }
 
         // Give up.
-        bail!("Cannot find \"{file_name}\" in {}", self.system_root)
+        bail!(
+            "Cannot find \"{file_name}\" in {}",
+            self.system_roots.join(", ")
+        )
+    }
+}
+
+/// Prefix of image paths that are explicitly specified relative to the system root.
+const SYSTEM_ROOT_PREFIX: &str = "\\SystemRoot\\";
+
+/// Joins a directory and a backslash-separated relative path using the separators of the running platform.
+fn join_path(directory: &str, relative_path: &str) -> PathBuf {
+    let mut path = PathBuf::from(directory);
+
+    for component in relative_path.split('\\').filter(|c| !c.is_empty()) {
+        path.push(component);
+    }
+
+    path
+}
+
+/// Properties of an image file, determined while handling its imports.
+struct ImageProperties {
+    signed_embedded: bool,
+    machine: u16,
+    subsystem: u16,
+    warnings: Vec<String>,
+}
+
+impl ImageProperties {
+    fn new(pe_file: &PeFile) -> Self {
+        let signed_embedded = pe_file
+            .data_directory()
+            .get(IMAGE_DIRECTORY_ENTRY_SECURITY)
+            .is_some_and(|directory| directory.VirtualAddress != 0 && directory.Size != 0);
+
+        // Hybrid images are identified by their CHPE metadata and reported with their own machine types.
+        let mut machine = pe_file.file_header().Machine;
+        if has_chpe_metadata(pe_file) {
+            machine = match machine {
+                IMAGE_FILE_MACHINE_ARM64 => IMAGE_FILE_MACHINE_ARM64X,
+                IMAGE_FILE_MACHINE_AMD64 => IMAGE_FILE_MACHINE_ARM64EC,
+                machine => machine,
+            };
+        }
+
+        let subsystem = match pe_file.optional_header() {
+            Wrap::T32(optional_header) => optional_header.Subsystem,
+            Wrap::T64(optional_header) => optional_header.Subsystem,
+        };
+
+        Self {
+            signed_embedded,
+            machine,
+            subsystem,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn apply(self, entry: &mut NtLoadOrderEntry) {
+        entry.signed_embedded = Some(self.signed_embedded);
+        entry.machine = Some(self.machine);
+        entry.subsystem = Some(self.subsystem);
+        entry.warnings.extend(self.warnings);
     }
 }
 
+const IMAGE_FILE_MACHINE_ARM64: u16 = 0xaa64;
+const IMAGE_FILE_MACHINE_ARM64EC: u16 = 0xa641;
+const IMAGE_FILE_MACHINE_ARM64X: u16 = 0xa64e;
+
+/// Returns whether the load configuration of the given image has a CHPE metadata pointer,
+/// which is only the case for ARM64X and ARM64EC images.
+///
+/// pelite doesn't know about this field, so it is read manually from the raw load configuration.
+fn has_chpe_metadata(pe_file: &PeFile) -> bool {
+    const CHPE_METADATA_POINTER_OFFSET: usize = 0xc8;
+    const U64_SIZE: usize = mem::size_of::<u64>();
+
+    let Some(directory) = pe_file
+        .data_directory()
+        .get(IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG)
+    else {
+        return false;
+    };
+
+    if directory.VirtualAddress == 0 {
+        return false;
+    }
+
+    let Ok(load_config) = pe_file.slice_bytes(directory.VirtualAddress) else {
+        return false;
+    };
+
+    // The first field of the load configuration is its size, which varies between Windows versions.
+    let size = load_config.get(..mem::size_of::<u32>()).map_or(0, |size| {
+        u32::from_le_bytes(size.try_into().unwrap()) as usize
+    });
+    if size < CHPE_METADATA_POINTER_OFFSET + U64_SIZE {
+        return false;
+    }
+
+    load_config
+        .get(CHPE_METADATA_POINTER_OFFSET..CHPE_METADATA_POINTER_OFFSET + U64_SIZE)
+        .is_some_and(|pointer| u64::from_le_bytes(pointer.try_into().unwrap()) != 0)
+}
+
 struct ImportHandler<'a, 'b> {
-    apiset_map: ApiSetMap<'b>,
+    apiset_resolver: Box<dyn ApiSetResolver + 'b>,
     entries: VecList<NtLoadOrderEntry>,
+    /// Statistics about the opened image files, only collected if this is `Some`.
+    import_stats: Option<ImportStats>,
     loaded_image_paths: HashSet<String>,
     path_handler: &'a PathHandler,
+    /// Registered services by lowercased image path.
+    services: HashMap<String, NtLoadOrderEntry>,
+    /// Whether a missing import file is an error instead of an unresolved import.
+    strict_imports: bool,
 }
 
 impl<'a, 'b> ImportHandler<'a, 'b> {
-    fn new(path_handler: &'a PathHandler, apiset_map: ApiSetMap<'b>) -> Self {
+    fn new(
+        path_handler: &'a PathHandler,
+        apiset_resolver: Box<dyn ApiSetResolver + 'b>,
+        strict_imports: bool,
+    ) -> Self {
         Self {
-            apiset_map,
+            apiset_resolver,
             entries: VecList::new(),
+            import_stats: None,
             loaded_image_paths: HashSet::new(),
             path_handler,
+            services: HashMap::new(),
+            strict_imports,
+        }
+    }
+
+    fn apply_image_properties(
+        &mut self,
+        index: Index<NtLoadOrderEntry>,
+        properties: Option<ImageProperties>,
+    ) {
+        if let Some(properties) = properties {
+            properties.apply(self.entries.get_mut(index).unwrap());
         }
     }
 
-    fn handle_image(&mut self, image_path: &str) -> Result<()> {
+    /// Adds the imports of the given image recursively.
+    ///
+    /// Returns the properties of the image file, or `None` if it could not be opened.
+    fn handle_image(&mut self, image_path: &str) -> Result<Option<ImageProperties>> {
+        let Some(file_path) = self.path_handler.full_path_name(image_path) else {
+            // This image is located outside of the system root, so we can't analyze its imports.
+            return Ok(None);
+        };
+
         // Open the file as a PE file.
-        let file_path = self.path_handler.full_path_name(image_path);
+        let start_time = self.import_stats.is_some().then(Instant::now);
         let file_map = FileMap::open(&file_path)
-            .with_context(|| format!("FileMap::open failed for \"{file_path}\""))?;
-        let pe_file = PeFile::from_bytes(&file_map)
-            .with_context(|| format!("PeFile::from_bytes failed for \"{file_path}\""))?;
+            .with_context(|| format!("FileMap::open failed for \"{}\"", file_path.display()))?;
+        let pe_file = PeFile::from_bytes(&file_map).with_context(|| {
+            format!("PeFile::from_bytes failed for \"{}\"", file_path.display())
+        })?;
 
+        if let (Some(import_stats), Some(start_time)) = (&mut self.import_stats, start_time) {
+            let duration = start_time.elapsed();
+
+            // The mapping is rounded up to the page size, so query the actual file size.
+            let size = file_path
+                .metadata()
+                .with_context(|| format!("Could not query \"{}\"", file_path.display()))?
+                .len();
+
+            import_stats.files.push(ImageFileStats {
+                image_path: image_path.to_string(),
+                size,
+                duration,
+            });
+        }
+
+        let mut properties = ImageProperties::new(&pe_file);
+
+        // For ARM64X images, this is the native import directory, which is also the one the bootloader uses.
         let Ok(imports) = pe_file.imports() else {
-            return Ok(());
+            return Ok(Some(properties));
         };
 
         for import in imports {
@@ -143,18 +670,47 @@ impl<'a, 'b> ImportHandler<'a, 'b> {
                 })?
                 .to_string();
 
-            let dll_name = self
-                .patch_dll_name(dll_name)
-                .with_context(|| format!("While handling imports of \"{file_path}\""))?;
+            if !self.apiset_resolver.resolves_api_sets() && is_apiset_name(&dll_name) {
+                properties.warnings.push(format!(
+                    "Cannot resolve API Set import \"{dll_name}\" without an \"apisetschema.dll\""
+                ));
+            }
+
+            let patched_dll_name = self
+                .apiset_resolver
+                .patch_dll_name(dll_name.clone())
+                .with_context(|| {
+                    format!("While handling imports of \"{}\"", file_path.display())
+                })?;
 
-            let Some(dll_name) = dll_name else {
+            let Some(patched_dll_name) = patched_dll_name else {
                 // An API Set Map lookup revealed that this import is not available on this operating system.
                 // It is therefore ignored by the PE loader.
+                debug!("Ignoring unavailable import \"{dll_name}\" of \"{image_path}\"");
                 continue;
             };
 
+            // Remember the API Set if the import has been redirected.
+            let via_api_set = (patched_dll_name != dll_name)
+                .then(|| dll_name.trim_end_matches(".dll").to_string());
+            if via_api_set.is_some() {
+                trace!("API Set \"{dll_name}\" is redirected to \"{patched_dll_name}\"");
+            }
+            let dll_name = patched_dll_name;
+
             // Determine the image path to the import file name.
-            let import_image_path = self.path_handler.get_image_path(&dll_name)?;
+            let import_image_path = match self
+                .path_handler
+                .get_image_path(&dll_name, matches!(pe_file, Wrap::T32(_)))
+            {
+                Ok(import_image_path) => import_image_path,
+                Err(e) if self.strict_imports => return Err(e),
+                Err(e) => {
+                    debug!("{e}");
+                    self.add_unresolved_import(dll_name, via_api_set, image_path);
+                    continue;
+                }
+            };
 
             // If this import has not been handled before, handle it now.
             if self
@@ -165,70 +721,80 @@ impl<'a, 'b> ImportHandler<'a, 'b> {
                 //
                 // This is exactly opposite to the way it's done for services, and adds to the confusing resulting
                 // load order of the Windows bootloader.
-                self.handle_image(&import_image_path)?;
-                self.entries.push_back(NtLoadOrderEntry {
-                    name: dll_name,
-                    image_path: import_image_path,
-                    group: None,
-                    tag: None,
-                    reason: format!("Import of \"{image_path}\""),
-                    is_kernel_binary: false,
-                });
+                debug!("Adding \"{import_image_path}\" as import of \"{image_path}\"");
+                let import_properties = self.handle_image(&import_image_path)?;
+
+                let entry = match self.services.get(&import_image_path.to_ascii_lowercase()) {
+                    Some(service) => {
+                        // This import is also registered as a service, but loaded earlier as an import.
+                        // Keep the metadata of the service.
+                        NtLoadOrderEntry {
+                            reason: format!(
+                                "{}; also imported by \"{image_path}\"",
+                                service.reason
+                            ),
+                            via_api_set,
+                            imported_by: Some(image_path.to_string()),
+                            ..service.clone()
+                        }
+                    }
+                    None => {
+                        let reason = match &via_api_set {
+                            Some(api_set) => {
+                                format!("Import of \"{image_path}\" via api-set \"{api_set}\"")
+                            }
+                            None => format!("Import of \"{image_path}\""),
+                        };
+
+                        NtLoadOrderEntry {
+                            via_api_set,
+                            imported_by: Some(image_path.to_string()),
+                            ..NtLoadOrderEntry::new(
+                                dll_name,
+                                import_image_path,
+                                reason,
+                                NtLoadOrderEntryKind::Import,
+                            )
+                        }
+                    }
+                };
+
+                let index = self.entries.push_back(entry);
+                self.apply_image_properties(index, import_properties);
+            } else {
+                trace!("Import \"{import_image_path}\" of \"{image_path}\" is already loaded");
             }
         }
 
-        Ok(())
+        Ok(Some(properties))
     }
 
-    /// Looks up the passed import file name in the operating system's API Set Map.
-    ///
-    /// If the file name does not fulfill the requirements for API Set Map entries, the passed file name is
-    /// returned unmodified.
-    /// If the file name has an API Set Map entry, the file name of the corresponding entry is returned.
-    /// Otherwise, if the file name has no such entry, `None` is returned.
-    fn patch_dll_name(&self, dll_name: String) -> Result<Option<String>> {
-        let Some(lookup_name) = dll_name.strip_suffix(".dll") else {
-            // `dll_name` has no lowercase .dll extension, which is a requirement for having an API Set entry.
-            // So return the unmodified `dll_name`.
-            return Ok(Some(dll_name));
-        };
-
-        if !lookup_name.starts_with("api-") && !lookup_name.starts_with("ext-") {
-            // `dll_name` does not start with "api-" or "ext-", which is a requirement for having an API Set entry.
-            // So return the unmodified `dll_name`.
-            return Ok(Some(dll_name));
-        }
-
-        let Some(Ok(namespace_entry)) = self.apiset_map.find_namespace_entry(lookup_name) else {
-            // Although `dll_name` has been validated as an API Set, it has no entry in the API Set Map.
-            // This indicates that the requested API Set import is not available on this operating system,
-            // and should be ignored.
-            // So return `None`.
-            return Ok(None);
-        };
-
-        let mut value_entries = namespace_entry.value_entries().with_context(|| {
-            format!("ApiSetNamespaceEntry::value_entries failed for \"{dll_name}\"")
-        })?;
-
-        let Some(value_entry) = value_entries.next() else {
-            // Although `dll_name` has been validated as an API Set and has a namespace entry, it does not have
-            // a single value entry in the API Set Map.
-            // Return `None` like above.
-            return Ok(None);
-        };
-
-        let value = value_entry
-            .value()
-            .with_context(|| format!("ApiSetValueEntry::value failed for \"{dll_name}\""))?;
-
-        if value.is_empty() {
-            // Although `dll_name` has been validated as an API Set and has a namespace entry with a value entry,
-            // that value entry is empty.
-            // Return `None` like above.
-            return Ok(None);
+    /// Adds a placeholder entry for an import that cannot be found, unless it has been added before.
+    fn add_unresolved_import(
+        &mut self,
+        dll_name: String,
+        via_api_set: Option<String>,
+        image_path: &str,
+    ) {
+        // Image paths always contain a backslash, so file names can't clash with them.
+        if !self
+            .loaded_image_paths
+            .insert(dll_name.to_ascii_lowercase())
+        {
+            return;
         }
 
-        Ok(Some(value.to_string_lossy()))
+        debug!("Adding unresolved import \"{dll_name}\" of \"{image_path}\"");
+        let reason = format!("Unresolved import of \"{dll_name}\" from \"{image_path}\"");
+        self.entries.push_back(NtLoadOrderEntry {
+            via_api_set,
+            imported_by: Some(image_path.to_string()),
+            ..NtLoadOrderEntry::new(
+                dll_name,
+                String::new(),
+                reason,
+                NtLoadOrderEntryKind::UnresolvedImport,
+            )
+        });
     }
 }
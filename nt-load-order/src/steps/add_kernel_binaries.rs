@@ -3,7 +3,7 @@
 
 use dlv_list::{Index, VecList};
 
-use crate::NtLoadOrderEntry;
+use crate::{NtLoadOrderEntry, NtLoadOrderEntryKind};
 
 /// Adds "ntoskrnl.exe" and "hal.dll".
 /// Returns the [`Index`] of the last added binary.
@@ -13,14 +13,12 @@ use crate::NtLoadOrderEntry;
 pub fn add_basic_kernel_binaries(
     entries: &mut VecList<NtLoadOrderEntry>,
 ) -> Index<NtLoadOrderEntry> {
-    let ntoskrnl = entries.push_front(NtLoadOrderEntry {
-        name: "ntoskrnl".to_string(),
-        image_path: "System32\\ntoskrnl.exe".to_string(),
-        group: None,
-        tag: None,
-        reason: "Kernel binary".to_string(),
-        is_kernel_binary: true,
-    });
+    let ntoskrnl = entries.push_front(NtLoadOrderEntry::new(
+        "ntoskrnl".to_string(),
+        "System32\\ntoskrnl.exe".to_string(),
+        "Kernel binary".to_string(),
+        NtLoadOrderEntryKind::KernelBinary,
+    ));
     add_kernel_binary(
         entries,
         ntoskrnl,
@@ -37,13 +35,11 @@ pub fn add_kernel_binary(
 ) -> Index<NtLoadOrderEntry> {
     entries.insert_after(
         after,
-        NtLoadOrderEntry {
+        NtLoadOrderEntry::new(
             name,
             image_path,
-            group: None,
-            tag: None,
-            reason: "Kernel binary".to_string(),
-            is_kernel_binary: true,
-        },
+            "Kernel binary".to_string(),
+            NtLoadOrderEntryKind::KernelBinary,
+        ),
     )
 }
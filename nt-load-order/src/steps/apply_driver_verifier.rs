@@ -0,0 +1,79 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use anyhow::Result;
+use log::debug;
+
+use crate::registry::RegistryWorker;
+use crate::{NtLoadOrderEntry, NtLoadOrderEntryKind};
+
+/// Image path of the Driver Verifier extension, which is part of the hardcoded "Core Driver Services" list.
+const VERIFIEREXT_IMAGE_PATH: &str = "System32\\drivers\\verifierext.sys";
+
+/// Adjusts the entries for Driver Verifier, if it is enabled.
+///
+/// `driver_verifier` forces Driver Verifier on or off, while `None` detects it from the "VerifyDrivers" value
+/// in "Control\Session Manager\Memory Management".
+/// With Driver Verifier enabled, the bootloader additionally loads "verifierext.sys", and every verified
+/// boot driver is annotated in its reason.
+/// The positions of all other entries don't change.
+pub fn apply_driver_verifier(
+    registry_worker: &RegistryWorker,
+    control_set_key_name: &str,
+    driver_verifier: Option<bool>,
+    entries: &mut Vec<NtLoadOrderEntry>,
+) -> Result<()> {
+    if driver_verifier == Some(false) {
+        return Ok(());
+    }
+
+    let hive = registry_worker.hive()?;
+    let verify_drivers = hive
+        .key_node(&format!(
+            "{control_set_key_name}\\Control\\Session Manager\\Memory Management"
+        ))
+        .and_then(|memory_management| memory_management.value("VerifyDrivers"))
+        .and_then(|value| value.sz_data())
+        .unwrap_or_default();
+    let verify_drivers = verify_drivers
+        .split_whitespace()
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>();
+
+    if driver_verifier.is_none() && verify_drivers.is_empty() {
+        return Ok(());
+    }
+
+    debug!("Driver Verifier is enabled for {verify_drivers:?}");
+
+    for entry in entries.iter_mut() {
+        let file_name = entry
+            .image_path
+            .rsplit('\\')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if verify_drivers
+            .iter()
+            .any(|verify_driver| verify_driver == "*" || *verify_driver == file_name)
+        {
+            entry.reason = format!("{}, verified by Driver Verifier", entry.reason);
+        }
+    }
+
+    if !entries.iter().any(|entry| {
+        entry
+            .image_path
+            .eq_ignore_ascii_case(VERIFIEREXT_IMAGE_PATH)
+    }) {
+        entries.push(NtLoadOrderEntry::new(
+            "verifierext".to_string(),
+            VERIFIEREXT_IMAGE_PATH.to_string(),
+            "Driver Verifier extension, added because Driver Verifier is enabled".to_string(),
+            NtLoadOrderEntryKind::Service,
+        ));
+    }
+
+    Ok(())
+}
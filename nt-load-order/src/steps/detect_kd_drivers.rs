@@ -0,0 +1,52 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use anyhow::Result;
+use log::debug;
+
+use crate::registry::RegistryWorker;
+
+/// Detects the KD driver for the debug transport configured in the "SystemStartOptions" value.
+///
+/// The bootloader writes the boot options of the BCD store into that value (e.g. "DEBUG DEBUGPORT=NET").
+/// If no debug port is found there, "kdcom" is returned, which Windows also loads when debugging is disabled.
+pub fn detect_kd_drivers(
+    registry_worker: &RegistryWorker,
+    control_set_key_name: &str,
+) -> Result<Vec<String>> {
+    let hive = registry_worker.hive()?;
+
+    let system_start_options = hive
+        .key_node(&format!("{control_set_key_name}\\Control"))
+        .and_then(|control| control.value("SystemStartOptions"))
+        .and_then(|value| value.sz_data())
+        .unwrap_or_default();
+
+    let kd_driver = system_start_options
+        .split_whitespace()
+        .filter_map(|option| {
+            let option = option.trim_start_matches('/').to_ascii_uppercase();
+            option
+                .strip_prefix("DEBUGPORT=")
+                .and_then(kd_driver_for_debug_port)
+        })
+        .next()
+        .unwrap_or("kdcom");
+
+    debug!("Detected KD driver \"{kd_driver}\" from \"{system_start_options}\"");
+    Ok(vec![kd_driver.to_string()])
+}
+
+fn kd_driver_for_debug_port(debug_port: &str) -> Option<&'static str> {
+    if debug_port.starts_with("COM") {
+        Some("kdcom")
+    } else if debug_port == "NET" {
+        Some("kdnet")
+    } else if debug_port == "1394" {
+        Some("kd1394")
+    } else if debug_port.starts_with("USB") {
+        Some("kdusb")
+    } else {
+        None
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use dlv_list::VecList;
+use log::debug;
+
+use crate::{NtLoadOrderEntry, NtLoadOrderEntryKind};
+
+/// Removes all entries whose names match one of the given patterns, and returns the removed entries.
+///
+/// Names are compared case-insensitively, and patterns may contain the wildcards `*` and `?`.
+pub fn exclude_services(
+    entries: &mut Vec<NtLoadOrderEntry>,
+    patterns: &[String],
+) -> Vec<NtLoadOrderEntry> {
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+
+    let (excluded_entries, kept_entries) = entries.drain(..).partition(|entry| {
+        patterns
+            .iter()
+            .any(|pattern| matches_pattern(&entry.name, pattern))
+    });
+    *entries = kept_entries;
+
+    for entry in &excluded_entries {
+        debug!("Excluding service \"{}\"", entry.name);
+    }
+
+    excluded_entries
+}
+
+/// Adds a warning to every import that is an excluded service, because another image imports it.
+pub fn warn_about_excluded_imports(
+    entries: &mut VecList<NtLoadOrderEntry>,
+    excluded_entries: &[NtLoadOrderEntry],
+) {
+    for entry in entries
+        .iter_mut()
+        .filter(|entry| entry.kind == NtLoadOrderEntryKind::Import)
+    {
+        if let Some(excluded_entry) = excluded_entries.iter().find(|excluded_entry| {
+            excluded_entry
+                .image_path
+                .eq_ignore_ascii_case(&entry.image_path)
+        }) {
+            entry.warnings.push(format!(
+                "Excluded service \"{}\" is still loaded, because it is imported",
+                excluded_entry.name
+            ));
+        }
+    }
+}
+
+/// Matches `name` case-insensitively against a pattern with the wildcards `*` (any characters)
+/// and `?` (a single character).
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let name = name.to_lowercase().chars().collect::<Vec<_>>();
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+
+    // Position after the last `*` in the pattern and the name position it currently matches.
+    let mut backtrack = None;
+    let (mut n, mut p) = (0, 0);
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                n += 1;
+                p += 1;
+            }
+            _ => {
+                // Let the last `*` match one more character.
+                let Some((star_p, star_n)) = backtrack else {
+                    return false;
+                };
+                backtrack = Some((star_p, star_n + 1));
+                p = star_p;
+                n = star_n + 1;
+            }
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("ACPI", "acpi"));
+        assert!(!matches_pattern("ACPI", "acp"));
+        assert!(matches_pattern("ACPI", "a*"));
+        assert!(matches_pattern("ACPI", "*"));
+        assert!(matches_pattern("ACPI", "?cp?"));
+        assert!(!matches_pattern("ACPI", "?cp"));
+        assert!(matches_pattern("intelpep", "*pep"));
+        assert!(matches_pattern("intelpep", "i*e*p"));
+        assert!(!matches_pattern("intelpep", "i*x*p"));
+        assert!(matches_pattern("", "*"));
+        assert!(!matches_pattern("", "?"));
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use anyhow::Result;
+use log::debug;
+
+use crate::registry::RegistryWorker;
+use crate::{NtLoadOrderEntry, NtLoadOrderEntryKind};
+
+/// Returns an entry for every native application in the "BootExecute" value of "Control\Session Manager"
+/// (e.g. "autocheck autochk *").
+///
+/// Returns no entries if the value doesn't exist.
+pub fn load_boot_execute(
+    registry_worker: &RegistryWorker,
+    control_set_key_name: &str,
+) -> Result<Vec<NtLoadOrderEntry>> {
+    let hive = registry_worker.hive()?;
+
+    let Ok(commands) = hive
+        .key_node(&format!("{control_set_key_name}\\Control\\Session Manager"))
+        .and_then(|session_manager| session_manager.value("BootExecute"))
+    else {
+        debug!("No \"BootExecute\" value found");
+        return Ok(Vec::new());
+    };
+
+    let entries = commands
+        .multi_sz_data()?
+        .iter()
+        .filter_map(|command| boot_execute_entry(command))
+        .collect();
+
+    Ok(entries)
+}
+
+fn boot_execute_entry(command: &str) -> Option<NtLoadOrderEntry> {
+    // The "autocheck" prefix only tells the Session Manager to not fail if the application is missing.
+    let mut words = command.split_whitespace().peekable();
+    words.next_if(|word| word.eq_ignore_ascii_case("autocheck"));
+    let name = words.next()?;
+
+    // Applications are searched in "System32", and the ".exe" extension may be omitted.
+    let image_path = if name.contains('.') {
+        format!("System32\\{name}")
+    } else {
+        format!("System32\\{name}.exe")
+    };
+    debug!("Adding \"{image_path}\" from \"BootExecute\" command \"{command}\"");
+
+    Some(NtLoadOrderEntry::new(
+        name.to_string(),
+        image_path,
+        format!("Boot Execute via \"{command}\""),
+        NtLoadOrderEntryKind::BootExecute,
+    ))
+}
@@ -1,62 +1,201 @@
 // Copyright 2025 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::mem;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use indexmap::IndexSet;
+use log::{debug, trace};
 
-use crate::registry::{RegistryKeyNode, RegistryKeyValue, RegistryWorker};
-use crate::{NtLoadOrderEntry, NtLoadOrderEntryGroup};
+use crate::registry::{RegistryHive, RegistryKeyNode, RegistryKeyValue, RegistryWorker};
+use crate::{
+    group_search_key, NtLoadOrderEntry, NtLoadOrderEntryGroup, NtLoadOrderEntryKind, SafeBootMode,
+};
 
+/// The data read from the registry, returned by [`crate::NtLoadOrder::get_registry_info`].
+#[derive(Clone, Debug)]
 pub struct RegistryInfo {
+    /// The boot drivers and the boot file system driver, in the order of their service keys.
     pub entries: Vec<NtLoadOrderEntry>,
+    /// The tag order of every group listed in "Control\GroupOrderList", by lowercased group name.
     pub groups: HashMap<String, IndexSet<u32>>,
+    /// The groups listed in "Control\ServiceGroupOrder", in load order.
     pub service_group_order: Vec<String>,
 }
 
+impl RegistryInfo {
+    /// Returns the groups that are only listed in one of "ServiceGroupOrder" and "GroupOrderList".
+    ///
+    /// This explains why certain drivers sort the way they do:
+    /// Tagged drivers of a group without a "GroupOrderList" entry are sorted by their raw tags (like "Core"),
+    /// and drivers of a group without a "ServiceGroupOrder" entry are sorted after all listed groups,
+    /// even if their tags are ordered by a "GroupOrderList" entry.
+    pub fn group_order_mismatches(&self) -> GroupOrderMismatches {
+        let service_group_order_keys = self
+            .service_group_order
+            .iter()
+            .map(|group_name| group_search_key(group_name))
+            .collect::<HashSet<_>>();
+
+        let only_in_service_group_order = self
+            .service_group_order
+            .iter()
+            .filter(|group_name| !self.groups.contains_key(&group_search_key(group_name)))
+            .map(|group_name| group_name.trim().to_string())
+            .collect();
+
+        let mut only_in_group_order_list = self
+            .groups
+            .keys()
+            .filter(|search_key| !service_group_order_keys.contains(*search_key))
+            .cloned()
+            .collect::<Vec<_>>();
+        only_in_group_order_list.sort();
+
+        GroupOrderMismatches {
+            only_in_service_group_order,
+            only_in_group_order_list,
+        }
+    }
+
+    /// Returns the name and group of every entry whose group is listed in neither "ServiceGroupOrder"
+    /// nor "GroupOrderList", in the order of their service keys.
+    ///
+    /// Such groups are usually misspelled or deprecated.
+    /// Their drivers are sorted after all listed groups, and tagged ones by their raw tags.
+    pub fn orphan_group_services(&self) -> Vec<(String, String)> {
+        let service_group_order_keys = self
+            .service_group_order
+            .iter()
+            .map(|group_name| group_search_key(group_name))
+            .collect::<HashSet<_>>();
+
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                let group = entry.group.as_ref()?;
+                let known = service_group_order_keys.contains(&group.search_key)
+                    || self.groups.contains_key(&group.search_key);
+                (!known).then(|| (entry.name.clone(), group.display_name.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Groups listed in only one of the ordering tables, returned by [`RegistryInfo::group_order_mismatches`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GroupOrderMismatches {
+    /// Groups listed in "ServiceGroupOrder", but without a "GroupOrderList" entry, in load order.
+    pub only_in_service_group_order: Vec<String>,
+    /// Lowercased names of the groups with a "GroupOrderList" entry, but not listed in "ServiceGroupOrder",
+    /// in alphabetical order.
+    pub only_in_group_order_list: Vec<String>,
+}
+
+/// Settings of [`crate::NtLoadOrder`] that affect [`load_from_registry`].
+#[derive(Clone, Copy, Debug)]
+pub struct LoadFromRegistryOptions<'a> {
+    pub winpe: bool,
+    pub safe_boot: Option<SafeBootMode>,
+    pub hardware_config_id: Option<u32>,
+    pub force_boot_services: &'a [String],
+    pub strict: bool,
+}
+
 pub fn load_from_registry(
     registry_worker: &RegistryWorker,
     boot_file_system: &str,
-    control_set: u8,
+    control_set_key_name: &str,
+    options: LoadFromRegistryOptions,
 ) -> Result<RegistryInfo> {
     const SERVICE_BOOT_START: u32 = 0;
 
-    let control_set_key_name = format!("ControlSet{control_set:03}");
-    let hive = registry_worker.hive()?;
+    let LoadFromRegistryOptions {
+        winpe,
+        safe_boot,
+        hardware_config_id,
+        force_boot_services,
+        strict,
+    } = options;
 
-    let hardware_config_id_string = hive
-        .key_node("HardwareConfig")?
-        .value("LastId")?
-        .dword_data()?
-        .to_string();
-
-    let service_group_order = hive
-        .key_node(&format!(
-            "{control_set_key_name}\\Control\\ServiceGroupOrder"
-        ))?
-        .value("List")?
-        .multi_sz_data()?;
+    let hive = registry_worker.hive()?;
+    let control_set_key_name = if winpe {
+        winpe_control_set(&hive, control_set_key_name)
+    } else {
+        control_set_key_name.to_string()
+    };
+    debug!("Using control set \"{control_set_key_name}\"");
+
+    // WinPE images are marked by the existence of a "MiniNT" key.
+    let winpe = winpe
+        || hive
+            .key_node(&format!("{control_set_key_name}\\Control\\MiniNT"))
+            .is_ok();
+    if winpe {
+        debug!("Analyzing in WinPE mode");
+    }
 
-    //
-    let group_order_list_key_node =
-        hive.key_node(&format!("{control_set_key_name}\\Control\\GroupOrderList"))?;
-    let group_order_list_values = group_order_list_key_node.values()?;
+    let hardware_config_id_string = match (hardware_config_id, hive.key_node("HardwareConfig")) {
+        (Some(hardware_config_id), _) => Some(hardware_config_id.to_string()),
+        (None, Ok(key_node)) => Some(key_node.value("LastId")?.dword_data()?.to_string()),
+        (None, Err(_)) if winpe => None,
+        (None, Err(e)) => return Err(e),
+    };
+
+    let service_group_order = match hive.key_node(&format!(
+        "{control_set_key_name}\\Control\\ServiceGroupOrder"
+    )) {
+        Ok(key_node) => key_node.value("List")?.multi_sz_data()?,
+        Err(_) if winpe => Vec::new(),
+        Err(e) => return Err(e),
+    };
+
+    // Every value of "GroupOrderList" is a tag array named after its group.
+    // This and the "List" of "ServiceGroupOrder" are the only ordering data the bootloader reads from "Control".
+    // There is no "Owners" or class-specific value here, and similar keys like "Control\ServiceProvider\Order"
+    // or "Control\NetworkProvider\Order" are only evaluated by user-mode components long after boot.
     let mut groups = HashMap::new();
+    let mut group_warnings = HashMap::new();
+
+    match hive.key_node(&format!("{control_set_key_name}\\Control\\GroupOrderList")) {
+        Ok(group_order_list_key_node) => {
+            for group in group_order_list_key_node.values()? {
+                let group = group?;
+                let (set, warning) = get_group_set(&group)?;
+                let search_key = group_search_key(group.name());
+
+                if let Some(warning) = warning {
+                    debug!("{warning}");
+                    if strict {
+                        bail!(warning);
+                    }
+
+                    group_warnings.insert(search_key.clone(), warning);
+                }
 
-    for group in group_order_list_values {
-        let group = group?;
-        let set = get_group_set(&group)?;
-
-        let group_search_key = group.name().to_ascii_lowercase();
-        groups.insert(group_search_key, set);
+                groups.insert(search_key, set);
+            }
+        }
+        Err(_) if winpe => (),
+        Err(e) => return Err(e),
     }
 
     //
-    let services_key_node = hive.key_node(&format!("{control_set_key_name}\\Services"))?;
+    // Only the local registry has a "CurrentControlSet" link, so target control sets are addressed by their number.
+    let services_key_node = hive
+        .key_node(&format!("{control_set_key_name}\\Services"))
+        .with_context(|| {
+            format!("Control set \"{control_set_key_name}\" has no \"Services\" subkey")
+        })?;
     let services_key_subkeys = services_key_node.subkeys()?;
 
+    for name in force_boot_services {
+        services_key_node.subkey(name).with_context(|| {
+            format!("Cannot force service \"{name}\" as boot-start, because it does not exist")
+        })?;
+    }
+
     let mut entries = Vec::new();
 
     for service in services_key_subkeys {
@@ -66,37 +205,84 @@ pub fn load_from_registry(
 
         // We first need to fetch the data of the "Start" value to later check if this service
         // is a boot driver.
-        if let Ok(start_value) = service.value("Start") {
-            if let Ok(start_dword) = start_value.dword_data() {
-                start_and_reason = Some((start_dword, "Boot Driver via its \"Start\" value"));
-            }
+        if let Some(start_dword) = optional_dword(&service, "Start", strict)? {
+            start_and_reason = Some((start_dword, "Boot Driver via its \"Start\" value"));
         }
 
         // This value may be overridden on a per-hardware-config basis in the "StartOverride" subkey.
         // Check this key as well.
-        if let Ok(start_override) = service.subkey("StartOverride") {
-            if let Ok(start_override_value) = start_override.value(&hardware_config_id_string) {
-                if let Ok(start_override_dword) = start_override_value.dword_data() {
-                    start_and_reason = Some((
-                        start_override_dword,
-                        "Boot Driver via its value in the \"StartOverride\" subkey",
-                    ));
-                }
+        if let (Some(hardware_config_id_string), Ok(start_override)) =
+            (&hardware_config_id_string, service.subkey("StartOverride"))
+        {
+            if let Some(start_override_dword) =
+                optional_dword(&start_override, hardware_config_id_string, strict)?
+            {
+                start_and_reason = Some((
+                    start_override_dword,
+                    "Boot Driver via its value in the \"StartOverride\" subkey",
+                ));
             }
         }
 
         // Now only add this service to the list if it's really a boot driver.
-        if let Some((start, reason)) = start_and_reason {
-            if start == SERVICE_BOOT_START {
-                add_service(&mut entries, &service, reason.to_string())?;
+        match start_and_reason {
+            Some((start, reason)) if start == SERVICE_BOOT_START => {
+                debug!("Adding service \"{}\": {reason}", service.name());
+                add_service(
+                    &mut entries,
+                    &service,
+                    reason.to_string(),
+                    NtLoadOrderEntryKind::Service,
+                    strict,
+                )?;
             }
+            _ if force_boot_services
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(service.name()))
+                && !service.name().eq_ignore_ascii_case(boot_file_system) =>
+            {
+                let reason = "Boot Driver via forced boot-start (simulation)";
+                debug!("Adding service \"{}\": {reason}", service.name());
+                add_service(
+                    &mut entries,
+                    &service,
+                    reason.to_string(),
+                    NtLoadOrderEntryKind::Service,
+                    strict,
+                )?;
+            }
+            Some((start, _)) => trace!(
+                "Skipping service \"{}\" with start type {start}",
+                service.name()
+            ),
+            None => trace!("Skipping service \"{}\" without start type", service.name()),
         }
     }
 
     // Add the boot file system as well.
-    let boot_file_system_node = services_key_node.subkey(boot_file_system)?;
-    let reason = "Boot File System Driver";
-    add_service(&mut entries, &boot_file_system_node, reason.to_string())?;
+    match services_key_node.subkey(boot_file_system) {
+        Ok(boot_file_system_node) => {
+            let reason = "Boot File System Driver";
+            debug!("Adding boot file system \"{boot_file_system}\"");
+            add_service(
+                &mut entries,
+                &boot_file_system_node,
+                reason.to_string(),
+                NtLoadOrderEntryKind::BootFileSystem,
+                strict,
+            )?;
+        }
+        Err(_) if winpe => debug!("Boot file system \"{boot_file_system}\" not found"),
+        Err(e) => return Err(e),
+    }
+
+    warn_about_group_lists(&service_group_order, &mut entries);
+    warn_about_group_order_lists(&group_warnings, &mut entries);
+    annotate_class_filters(&hive, &control_set_key_name, &mut entries)?;
+
+    if let Some(safe_boot) = safe_boot {
+        filter_safe_boot(&hive, &control_set_key_name, safe_boot, &mut entries)?;
+    }
 
     Ok(RegistryInfo {
         entries,
@@ -105,36 +291,280 @@ pub fn load_from_registry(
     })
 }
 
-fn get_group_set(group: &RegistryKeyValue) -> Result<IndexSet<u32>> {
+/// Returns the IDs of all hardware configurations, which are stored in the "Id" values of the subkeys
+/// of "HardwareConfig".
+pub fn hardware_config_ids(registry_worker: &RegistryWorker) -> Result<Vec<u32>> {
+    let hive = registry_worker.hive()?;
+    let hardware_config = hive.key_node("HardwareConfig")?;
+
+    let mut ids = Vec::new();
+    for subkey in hardware_config.subkeys()? {
+        let subkey = subkey?;
+
+        match subkey.value("Id").and_then(|value| value.dword_data()) {
+            Ok(id) => ids.push(id),
+            Err(_) => trace!(
+                "Skipping hardware configuration \"{}\" without \"Id\"",
+                subkey.name()
+            ),
+        }
+    }
+
+    ids.sort_unstable();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// Returns the control set to use for a WinPE image.
+///
+/// WinPE images may not come with the requested control set, so fall back to the one marked as current
+/// in the "Select" key.
+fn winpe_control_set(hive: &RegistryHive, control_set_key_name: &str) -> String {
+    if hive.key_node(control_set_key_name).is_ok() {
+        return control_set_key_name.to_string();
+    }
+
+    match hive.current_control_set() {
+        Ok(current) => format!("ControlSet{current:03}"),
+        Err(_) => control_set_key_name.to_string(),
+    }
+}
+
+/// Warns about services whose "Group" looks like a list of groups.
+///
+/// Windows treats the "Group" value as a single group name and doesn't split it at commas.
+/// Such a group is usually not listed in the ServiceGroupOrder, so the service is sorted like an ungrouped one.
+fn warn_about_group_lists(service_group_order: &[String], entries: &mut [NtLoadOrderEntry]) {
+    for entry in entries {
+        let Some(group) = &entry.group else {
+            continue;
+        };
+
+        if group.display_name.contains(',')
+            && !service_group_order
+                .iter()
+                .any(|group_name| group_search_key(group_name) == group.search_key)
+        {
+            debug!(
+                "Group \"{}\" of service \"{}\" looks like a list of groups",
+                group.display_name, entry.name
+            );
+            entry.warnings.push(format!(
+                "\"Group\" value \"{}\" contains a comma, but is treated as a single group name that is not in the ServiceGroupOrder",
+                group.display_name
+            ));
+        }
+    }
+}
+
+/// Adds the warnings about malformed "GroupOrderList" values to all services of the affected groups.
+fn warn_about_group_order_lists(
+    group_warnings: &HashMap<String, String>,
+    entries: &mut [NtLoadOrderEntry],
+) {
+    for entry in entries {
+        if let Some(warning) = entry
+            .group
+            .as_ref()
+            .and_then(|group| group_warnings.get(&group.search_key))
+        {
+            entry.warnings.push(warning.clone());
+        }
+    }
+}
+
+/// Annotates the reason of entries that are referenced as "UpperFilters" or "LowerFilters" of a device class.
+///
+/// Filter drivers are only loaded by the bootloader if they are boot drivers on their own, so this just documents
+/// why such a driver is part of the boot process.
+fn annotate_class_filters(
+    hive: &RegistryHive,
+    control_set_key_name: &str,
+    entries: &mut [NtLoadOrderEntry],
+) -> Result<()> {
+    let Ok(class_key_node) = hive.key_node(&format!("{control_set_key_name}\\Control\\Class"))
+    else {
+        debug!("No \"Control\\Class\" key found, skipping filter drivers");
+        return Ok(());
+    };
+
+    // Collect all references by lowercased service name.
+    let mut references = HashMap::<String, Vec<String>>::new();
+
+    for class in class_key_node.subkeys()? {
+        let class = class?;
+        let class_name = class
+            .value("Class")
+            .and_then(|value| value.sz_data())
+            .unwrap_or_else(|_| class.name().to_string());
+
+        for filters_value_name in ["UpperFilters", "LowerFilters"] {
+            let Ok(filters) = class
+                .value(filters_value_name)
+                .and_then(|value| value.multi_sz_data())
+            else {
+                continue;
+            };
+
+            for filter in filters {
+                references
+                    .entry(filter.trim().to_ascii_lowercase())
+                    .or_default()
+                    .push(format!(
+                        "\"{filters_value_name}\" of class \"{class_name}\""
+                    ));
+            }
+        }
+    }
+
+    for entry in entries {
+        if let Some(references) = references.get(&entry.name.to_ascii_lowercase()) {
+            trace!("\"{}\" is a filter driver", entry.name);
+            entry.reason = format!(
+                "{}; filter driver in {}",
+                entry.reason,
+                references.join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps only the entries whose service name or group is listed under the "SafeBoot" subkey of the given mode,
+/// like the bootloader does in Safe Mode.
+fn filter_safe_boot(
+    hive: &RegistryHive,
+    control_set_key_name: &str,
+    safe_boot: SafeBootMode,
+    entries: &mut Vec<NtLoadOrderEntry>,
+) -> Result<()> {
+    let subkey_name = match safe_boot {
+        SafeBootMode::Minimal => "Minimal",
+        SafeBootMode::Network => "Network",
+    };
+    let safe_boot_key_node = hive.key_node(&format!(
+        "{control_set_key_name}\\Control\\SafeBoot\\{subkey_name}"
+    ))?;
+
+    // Subkeys are named after services or groups, so compare them like group names.
+    let mut listed_names = HashSet::new();
+    for subkey in safe_boot_key_node.subkeys()? {
+        listed_names.insert(group_search_key(subkey?.name()));
+    }
+
+    entries.retain_mut(|entry| {
+        let group_name = entry.group.as_ref().map(|group| &group.display_name);
+        let listed_name = [Some(&entry.name), group_name]
+            .into_iter()
+            .flatten()
+            .find(|name| listed_names.contains(&group_search_key(name)));
+
+        match listed_name {
+            Some(listed_name) => {
+                entry.reason = format!(
+                    "{}; kept in Safe Mode via \"SafeBoot\\{subkey_name}\\{}\"",
+                    entry.reason,
+                    listed_name.trim()
+                );
+                true
+            }
+            None => {
+                debug!(
+                    "Dropping \"{}\", which is not listed under \"SafeBoot\\{subkey_name}\"",
+                    entry.name
+                );
+                false
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Returns the tags of a "GroupOrderList" value, along with a warning if the value is truncated.
+fn get_group_set(group: &RegistryKeyValue) -> Result<(IndexSet<u32>, Option<String>)> {
+    const U32_SIZE: usize = mem::size_of::<u32>();
+
     let data = group.binary_data()?;
-    let mut set = IndexSet::new();
 
-    if data.len() >= 2 * mem::size_of::<u32>() {
-        const U32_SIZE: usize = mem::size_of::<u32>();
+    // A group without any tags (zero count) still gets an empty set here.
+    // This is important, because its tagged services must then be sorted like unlisted tags
+    // instead of using the tags as indexes (see `get_tag_index`).
+    let mut set = IndexSet::new();
+    let mut warning = None;
+
+    if let Some(count) = data.get(..U32_SIZE) {
+        let count = u32::from_le_bytes(count.try_into().unwrap()) as usize;
+
+        // `chunks_exact` skips a trailing remainder of less than 4 bytes, which cannot be a tag.
+        let tags = data[U32_SIZE..].chunks_exact(U32_SIZE);
+        if count > tags.len() {
+            warning = Some(format!(
+                "\"GroupOrderList\" value \"{}\" specifies {count} tags, but only contains {}",
+                group.name(),
+                tags.len()
+            ));
+        }
 
-        let count = u32::from_le_bytes(data[..U32_SIZE].try_into().unwrap());
-        set = data[U32_SIZE..]
-            .chunks(U32_SIZE)
-            .take(count as usize)
+        set = tags
+            .take(count)
             .map(|x| u32::from_le_bytes(x.try_into().unwrap()))
             .collect::<IndexSet<u32>>();
     }
 
-    Ok(set)
+    Ok((set, warning))
 }
 
 fn add_service(
     entries: &mut Vec<NtLoadOrderEntry>,
     service: &RegistryKeyNode,
-    reason: String,
+    mut reason: String,
+    kind: NtLoadOrderEntryKind,
+    strict: bool,
 ) -> Result<()> {
+    const SERVICE_ERROR_CRITICAL: u32 = 3;
+
     let name = service.name().to_string();
-    let image_path = service_image_path(service);
+    let mut warnings = Vec::new();
+    let mut image_path = service_image_path(service, &mut warnings);
+
+    if let Some(service_dll) = service_dll(service) {
+        debug!("Using \"{service_dll}\" as the image of svchost-hosted service \"{name}\"");
+        reason = format!("{reason}, hosted by \"{image_path}\" via \"ServiceDll\"");
+        image_path = service_dll;
+    }
 
     let mut group = None;
     if let Ok(value) = service.value("Group") {
-        if let Ok(display_name) = value.sz_data() {
-            let search_key = display_name.to_ascii_lowercase();
+        let display_name = match value.sz_data() {
+            Ok(display_name) => Some(display_name),
+            Err(_) => {
+                // Some third-party drivers store their group as REG_MULTI_SZ.
+                // Use the first string like the bootloader does for a REG_SZ value.
+                let display_name = value
+                    .multi_sz_data()
+                    .ok()
+                    .and_then(|strings| strings.into_iter().next());
+
+                if display_name.is_some() {
+                    debug!(
+                        "\"Group\" of service \"{}\" is a REG_MULTI_SZ value",
+                        service.name()
+                    );
+                    warnings.push(
+                        "\"Group\" is a REG_MULTI_SZ value, using its first string".to_string(),
+                    );
+                } else {
+                    warnings.push("\"Group\" is not a string value, ignoring it".to_string());
+                }
+
+                display_name
+            }
+        };
+
+        if let Some(display_name) = display_name {
+            let search_key = group_search_key(&display_name);
 
             group = Some(NtLoadOrderEntryGroup {
                 display_name,
@@ -143,30 +573,160 @@ fn add_service(
         }
     }
 
-    let mut tag = None;
-    if let Ok(value) = service.value("Tag") {
-        if let Ok(dword) = value.dword_data() {
-            tag = Some(dword);
+    let tag = optional_dword(service, "Tag", strict)?;
+
+    let service_type = service
+        .value("Type")
+        .and_then(|value| value.dword_data())
+        .ok();
+
+    let error_control = service
+        .value("ErrorControl")
+        .and_then(|value| value.dword_data())
+        .ok();
+    let critical = error_control == Some(SERVICE_ERROR_CRITICAL);
+
+    let depend_on_service = optional_string_list(service, "DependOnService");
+    let depend_on_group = optional_string_list(service, "DependOnGroup");
+
+    for value_name in ["ImagePath", "Group"] {
+        if let Ok(value) = service.value(value_name) {
+            warnings.extend(sz_data_warning(&value));
+        }
+    }
+
+    if strict {
+        if let Some(warning) = warnings.first() {
+            bail!("Service \"{name}\" is malformed: {warning}");
         }
     }
 
     entries.push(NtLoadOrderEntry {
-        name,
-        image_path,
         group,
         tag,
-        reason,
-        is_kernel_binary: false,
+        service_type,
+        error_control,
+        critical,
+        depend_on_service,
+        depend_on_group,
+        warnings,
+        ..NtLoadOrderEntry::new(name, image_path, reason, kind)
     });
 
     Ok(())
 }
 
-fn service_image_path(service: &RegistryKeyNode) -> String {
-    // If there is an "ImagePath" value, use that.
+/// Returns the strings of an optional REG_MULTI_SZ value, also accepting a single REG_SZ string.
+fn optional_string_list(service: &RegistryKeyNode, value_name: &str) -> Vec<String> {
+    let Ok(value) = service.value(value_name) else {
+        return Vec::new();
+    };
+
+    let strings = value
+        .multi_sz_data()
+        .or_else(|_| value.sz_data().map(|string| vec![string]))
+        .unwrap_or_default();
+
+    strings
+        .into_iter()
+        .filter(|string| !string.is_empty())
+        .collect()
+}
+
+/// Checks the raw data of a string value for anything that is lost when reading it as a string.
+fn sz_data_warning(value: &RegistryKeyValue) -> Option<String> {
+    let raw_data = value.raw_sz_data().ok()?;
+
+    // Trailing NUL characters just terminate the string.
+    let length = raw_data
+        .iter()
+        .rposition(|code_unit| *code_unit != 0)
+        .map_or(0, |index| index + 1);
+    let raw_data = &raw_data[..length];
+
+    if raw_data.contains(&0) {
+        Some(format!(
+            "\"{}\" contains an embedded NUL character, ignoring everything after it",
+            value.name()
+        ))
+    } else if String::from_utf16(raw_data).is_err() {
+        Some(format!(
+            "\"{}\" is not valid UTF-16, replacing undecodable characters",
+            value.name()
+        ))
+    } else {
+        None
+    }
+}
+
+/// Returns the data of a DWORD value, or `None` if it doesn't exist.
+///
+/// A value of another type is ignored as well, unless `strict` is set.
+fn optional_dword(
+    key_node: &RegistryKeyNode,
+    value_name: &str,
+    strict: bool,
+) -> Result<Option<u32>> {
+    let Ok(value) = key_node.value(value_name) else {
+        return Ok(None);
+    };
+
+    match value.dword_data() {
+        Ok(dword) => Ok(Some(dword)),
+        Err(e) if strict => Err(e.context(format!(
+            "\"{value_name}\" of \"{}\" is not a valid REG_DWORD value",
+            key_node.name()
+        ))),
+        Err(_) => {
+            trace!(
+                "Ignoring \"{value_name}\" of \"{}\", which is not a valid REG_DWORD value",
+                key_node.name()
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Returns the "ServiceDll" value of the "Parameters" subkey, if any.
+///
+/// Services hosted by svchost.exe have an "ImagePath" like "%SystemRoot%\System32\svchost.exe -k netsvcs",
+/// and their actual image is this DLL (usually given as "%SystemRoot%\System32\xyz.dll").
+/// Boot-start services are drivers and never hosted by svchost.exe, but a misconfigured service may be.
+fn service_dll(service: &RegistryKeyNode) -> Option<String> {
+    const SYSTEM_ROOT_VARIABLES: [&str; 2] = ["%SystemRoot%\\", "%windir%\\"];
+
+    let service_dll = service
+        .subkey("Parameters")
+        .ok()?
+        .value("ServiceDll")
+        .ok()?
+        .sz_data()
+        .ok()
+        .filter(|service_dll| !service_dll.is_empty())?;
+
+    // Make the path relative to the system root like most "ImagePath" values.
+    for variable in SYSTEM_ROOT_VARIABLES {
+        if let Some(prefix) = service_dll.get(..variable.len()) {
+            if prefix.eq_ignore_ascii_case(variable) {
+                return Some(service_dll[variable.len()..].to_string());
+            }
+        }
+    }
+
+    Some(service_dll)
+}
+
+fn service_image_path(service: &RegistryKeyNode, warnings: &mut Vec<String>) -> String {
+    // If there is a non-empty "ImagePath" value, use that.
     if let Ok(value) = service.value("ImagePath") {
-        if let Ok(string) = value.sz_data() {
-            return string;
+        match value.sz_data() {
+            // An empty "ImagePath" is treated like a missing one.
+            Ok(string) if string.is_empty() => (),
+            Ok(string) => return string,
+            Err(_) => warnings.push(
+                "\"ImagePath\" is not a string value, deriving it from the service name"
+                    .to_string(),
+            ),
         }
     }
 
@@ -0,0 +1,43 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use anyhow::{bail, Result};
+use dlv_list::VecList;
+use log::debug;
+
+use crate::NtLoadOrderEntry;
+
+/// Moves the services with the given names to the front of the sorted `entries` in the given order,
+/// and marks them as pinned.
+///
+/// Names are compared case-insensitively.
+pub fn pin_services(entries: &mut VecList<NtLoadOrderEntry>, names: &[String]) -> Result<()> {
+    // Go through the names from back to front, so that the first one ends up at the very front.
+    for name in names.iter().rev() {
+        let mut index = entries.front_index();
+        while let Some(i) = index {
+            if entries.get(i).unwrap().name.eq_ignore_ascii_case(name) {
+                break;
+            }
+            index = entries.get_next_index(i);
+        }
+
+        let Some(index) = index else {
+            bail!("Cannot pin service \"{name}\", because it is not part of the load order");
+        };
+
+        let entry = entries.remove(index).unwrap();
+        if entry.pinned {
+            bail!("Service \"{name}\" is pinned more than once");
+        }
+
+        debug!("Pinning service \"{}\"", entry.name);
+        entries.push_front(NtLoadOrderEntry {
+            reason: format!("{}, pinned after the kernel binaries", entry.reason),
+            pinned: true,
+            ..entry
+        });
+    }
+
+    Ok(())
+}
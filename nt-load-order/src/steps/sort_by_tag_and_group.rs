@@ -1,23 +1,35 @@
 // Copyright 2025 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::mem;
 
 use dlv_list::VecList;
 use indexmap::IndexSet;
+use log::{debug, trace};
 
-use crate::steps::load_from_registry::RegistryInfo;
-use crate::NtLoadOrderEntry;
+use crate::steps::RegistryInfo;
+use crate::{group_search_key, NtLoadOrderEntry};
 
 use super::move_matching_elements_to_front;
 
-pub fn sort_by_tag_and_group(registry_info: RegistryInfo) -> VecList<NtLoadOrderEntry> {
-    let mut entries = registry_info
-        .entries
-        .into_iter()
-        .rev()
-        .collect::<VecList<NtLoadOrderEntry>>();
+/// Sorts the entries by their tags and groups like the bootloader does.
+///
+/// While enumerating the service keys, the bootloader inserts every boot driver at the head of its list,
+/// so the list is in reverse order of the service keys before sorting.
+/// Entries with equal tags and groups mostly keep their relative order, so the reversal is needed to get the same result.
+/// `reverse_registry_order` only exists to isolate ordering bugs during debugging and should be `true` otherwise.
+pub fn sort_by_tag_and_group(
+    mut registry_info: RegistryInfo,
+    reverse_registry_order: bool,
+) -> VecList<NtLoadOrderEntry> {
+    warn_about_tag_collisions(&mut registry_info.entries);
+
+    let mut entries = if reverse_registry_order {
+        registry_info.entries.into_iter().rev().collect()
+    } else {
+        registry_info.entries.into_iter().collect::<VecList<_>>()
+    };
 
     sort_list_by_tag(&mut entries, registry_info.groups);
     sort_list_by_group(&mut entries, registry_info.service_group_order);
@@ -25,45 +37,87 @@ pub fn sort_by_tag_and_group(registry_info: RegistryInfo) -> VecList<NtLoadOrder
     entries
 }
 
-fn sort_list_by_tag(
-    entries: &mut VecList<NtLoadOrderEntry>,
-    groups: HashMap<String, IndexSet<u32>>,
-) {
-    let start = entries.front_index();
-    let end = entries.back_index();
+/// Adds a warning to all entries sharing the same tag in the same group.
+/// Their relative order then depends on the order of their service keys.
+fn warn_about_tag_collisions(entries: &mut [NtLoadOrderEntry]) {
+    let mut indexes_by_group_and_tag = HashMap::<(&str, u32), Vec<usize>>::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if let (Some(group), Some(tag)) = (&entry.group, entry.tag) {
+            indexes_by_group_and_tag
+                .entry((&group.search_key, tag))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for (&(_, tag), indexes) in &indexes_by_group_and_tag {
+        if indexes.len() < 2 {
+            continue;
+        }
 
-    if start == end {
-        return;
+        for &index in indexes {
+            let entry = &entries[index];
+            let others = indexes
+                .iter()
+                .filter(|&&other| other != index)
+                .map(|&other| format!("\"{}\"", entries[other].name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            debug!("\"{}\" shares its tag {tag} with {others}", entry.name);
+
+            let group_name = entry.group.as_ref().map_or("", |group| &group.display_name);
+            warnings.push((
+                index,
+                format!("Tag {tag} in group \"{group_name}\" is shared with {others}"),
+            ));
+        }
     }
 
-    let start = start.unwrap();
-    let end = end.unwrap();
-    let mut current = start;
+    for (index, warning) in warnings {
+        entries[index].warnings.push(warning);
+    }
+}
 
-    while current != end {
-        let next = entries.get_next_index(current).unwrap();
-        let current_entry = entries.get(current).unwrap();
-        let next_entry = entries.get(next).unwrap();
+fn sort_list_by_tag(
+    entries: &mut VecList<NtLoadOrderEntry>,
+    groups: HashMap<String, IndexSet<u32>>,
+) {
+    // The bootloader performs an insertion sort: An entry that is not smaller than all entries before it
+    // stays where it is. Any other entry is moved before the first entry that is not smaller than itself.
+    // This means that equal entries don't necessarily keep their relative order.
+    //
+    // We get the exact same result in O(n log n) by additionally assigning each entry a position:
+    // Entries that stay at the end get increasing positions, moved entries get decreasing negative ones.
+    let mut max_key = None;
+    let mut next_back_position = 0i64;
+    let mut next_front_position = 0i64;
 
-        if compare_tags(current_entry, next_entry, &groups) == Ordering::Greater {
-            // This entry needs to move.
-            // Find the right spot to insert it among the entries before this one.
-            let mut target = entries.front_index().unwrap();
+    let mut keyed_entries = mem::take(entries)
+        .into_iter()
+        .map(|entry| {
+            let key = get_sort_key(&entry, &groups);
+            trace!("Tag sort key of \"{}\" is {key:?}", entry.name);
 
-            while target != current {
-                let target_entry = entries.get(target).unwrap();
+            let position = if max_key.is_some_and(|max_key| key < max_key) {
+                next_front_position -= 1;
+                next_front_position
+            } else {
+                max_key = Some(key);
+                next_back_position += 1;
+                next_back_position
+            };
 
-                match compare_tags(next_entry, target_entry, &groups) {
-                    Ordering::Less | Ordering::Equal => break,
-                    Ordering::Greater => target = entries.get_next_index(target).unwrap(),
-                }
-            }
+            (key, position, entry)
+        })
+        .collect::<Vec<_>>();
 
-            entries.move_before(next, target);
-        }
+    keyed_entries.sort_unstable_by_key(|(key, position, _)| (*key, *position));
 
-        current = next;
-    }
+    *entries = keyed_entries
+        .into_iter()
+        .map(|(_, _, entry)| entry)
+        .collect();
 }
 
 fn sort_list_by_group(entries: &mut VecList<NtLoadOrderEntry>, service_group_order: Vec<String>) {
@@ -73,40 +127,43 @@ fn sort_list_by_group(entries: &mut VecList<NtLoadOrderEntry>, service_group_ord
     let mut first_moved = None;
 
     for group_name in service_group_order.iter().rev() {
+        // Group names sometimes differ in surrounding whitespace, which is ignored like the case.
+        let group_name = group_name.trim();
+        let search_key = group_search_key(group_name);
+
         move_matching_elements_to_front(entries, &mut first_moved, |entry| {
             let Some(entry_group) = &entry.group else {
                 return false;
             };
-            entry_group.search_key.eq_ignore_ascii_case(group_name)
+            let matches = entry_group.search_key == search_key;
+            if matches {
+                trace!("Sorting \"{}\" into group \"{group_name}\"", entry.name);
+            }
+
+            matches
         });
     }
 }
 
-fn compare_tags(
-    a: &NtLoadOrderEntry,
-    b: &NtLoadOrderEntry,
-    groups: &HashMap<String, IndexSet<u32>>,
-) -> Ordering {
-    let (a_tag, b_tag) = match (a.tag, b.tag) {
-        (None, None) => return Ordering::Equal,
-        (None, Some(_)) => return Ordering::Greater,
-        (Some(_), None) => return Ordering::Less,
-        (Some(a_tag), Some(b_tag)) => (a_tag, b_tag),
-    };
-
-    let (a_group, b_group) = match (&a.group, &b.group) {
-        (None, None) => return Ordering::Equal,
-        (None, Some(_)) => return Ordering::Greater,
-        (Some(_), None) => return Ordering::Less,
-        (Some(a_group), Some(b_group)) => (a_group, b_group),
-    };
-
-    let a_index = get_tag_index(a_tag, &a_group.search_key, groups);
-    let b_index = get_tag_index(b_tag, &b_group.search_key, groups);
-
-    a_index.cmp(&b_index)
+/// Returns the key to sort the given entry by its tag.
+///
+/// Tagged entries of a group come first, followed by tagged entries without a group,
+/// followed by all untagged entries.
+fn get_sort_key(entry: &NtLoadOrderEntry, groups: &HashMap<String, IndexSet<u32>>) -> (u8, usize) {
+    match (entry.tag, &entry.group) {
+        (Some(tag), Some(group)) => (0, get_tag_index(tag, &group.search_key, groups)),
+        (Some(_), None) => (1, 0),
+        (None, _) => (2, 0),
+    }
 }
 
+/// Returns the index of the given tag within its group.
+///
+/// For a group with a GroupOrderList entry, this is the 1-based position of the tag in that entry.
+/// Tags missing from the entry are sorted second to last.
+///
+/// A group without a GroupOrderList entry uses its raw tags as indexes.
+/// This is not limited to specific group names, but in practice it is only seen for the "Core" group.
 fn get_tag_index(
     tag: u32,
     group_search_key: &str,
@@ -123,8 +180,139 @@ fn get_tag_index(
             0xffff_fffe
         }
     } else {
-        // The "Core" group has tags, but no entry in GroupOrderList.
-        // Treat the tag as the index.
+        // E.g. the "Core" group has tags, but no entry in GroupOrderList.
         tag as usize
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::*;
+    use crate::{NtLoadOrderEntryGroup, NtLoadOrderEntryKind};
+
+    /// The previous insertion sort implementation of `sort_list_by_tag`, serving as a reference.
+    fn insertion_sort_list_by_tag(
+        entries: &mut VecList<NtLoadOrderEntry>,
+        groups: &HashMap<String, IndexSet<u32>>,
+    ) {
+        let compare = |a: &NtLoadOrderEntry, b: &NtLoadOrderEntry| {
+            get_sort_key(a, groups).cmp(&get_sort_key(b, groups))
+        };
+
+        let (Some(start), Some(end)) = (entries.front_index(), entries.back_index()) else {
+            return;
+        };
+        let mut current = start;
+
+        while current != end {
+            let next = entries.get_next_index(current).unwrap();
+            let next_entry = entries.get(next).unwrap();
+
+            if compare(entries.get(current).unwrap(), next_entry) == Ordering::Greater {
+                let mut target = entries.front_index().unwrap();
+
+                while target != current {
+                    match compare(next_entry, entries.get(target).unwrap()) {
+                        Ordering::Less | Ordering::Equal => break,
+                        Ordering::Greater => target = entries.get_next_index(target).unwrap(),
+                    }
+                }
+
+                entries.move_before(next, target);
+            }
+
+            current = next;
+        }
+    }
+
+    fn entries(seed: u64, count: usize) -> Vec<NtLoadOrderEntry> {
+        let mut state = seed;
+        let mut random = |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+
+        (0..count)
+            .map(|i| {
+                let group = (random(4) != 0).then(|| {
+                    let search_key = format!("group {}", random(3));
+                    NtLoadOrderEntryGroup {
+                        display_name: search_key.clone(),
+                        search_key,
+                    }
+                });
+
+                NtLoadOrderEntry {
+                    group,
+                    tag: (random(4) != 0).then(|| random(6) as u32),
+                    ..NtLoadOrderEntry::new(
+                        format!("Service{i}"),
+                        String::new(),
+                        String::new(),
+                        NtLoadOrderEntryKind::Service,
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn tagged_entry(name: &str, group: &str, tag: u32) -> NtLoadOrderEntry {
+        NtLoadOrderEntry {
+            name: name.to_string(),
+            group: Some(NtLoadOrderEntryGroup {
+                display_name: group.to_string(),
+                search_key: group_search_key(group),
+            }),
+            tag: Some(tag),
+            ..entries(0, 1).remove(0)
+        }
+    }
+
+    #[test]
+    fn test_sort_list_by_tag_without_group_order_list() {
+        // Like "Core", this group has tags, but no GroupOrderList entry, so it is sorted by the raw tags.
+        // Their indexes are compared with the 1-based indexes of another group.
+        let groups = HashMap::from([("ordered".to_string(), IndexSet::from([7, 5, 6]))]);
+        let input = [
+            tagged_entry("Core4", "Core", 4),
+            tagged_entry("Ordered6", "Ordered", 6),
+            tagged_entry("Core2", "Core", 2),
+            tagged_entry("Ordered7", "Ordered", 7),
+        ];
+
+        let mut entries = input.into_iter().collect::<VecList<_>>();
+        sort_list_by_tag(&mut entries, groups);
+        let names = entries.into_iter().map(|e| e.name).collect::<Vec<_>>();
+
+        assert_eq!(names, ["Ordered7", "Core2", "Ordered6", "Core4"]);
+    }
+
+    #[test]
+    fn test_sort_list_by_tag_matches_insertion_sort() {
+        // "group 2" has no GroupOrderList entry and uses its tags as indexes.
+        let groups = HashMap::from([
+            ("group 0".to_string(), IndexSet::from([3, 1, 4])),
+            ("group 1".to_string(), IndexSet::from([5, 2])),
+        ]);
+
+        for seed in 0..200 {
+            for count in [0, 1, 2, 5, 20, 100] {
+                let input = entries(seed, count);
+
+                let mut expected = input.iter().cloned().collect::<VecList<_>>();
+                insertion_sort_list_by_tag(&mut expected, &groups);
+                let expected = expected.into_iter().map(|e| e.name).collect::<Vec<_>>();
+
+                let mut actual = input.into_iter().collect::<VecList<_>>();
+                sort_list_by_tag(&mut actual, groups.clone());
+                let actual = actual.into_iter().map(|e| e.name).collect::<Vec<_>>();
+
+                assert_eq!(actual, expected, "seed {seed}, count {count}");
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::collections::HashMap;
+
+use crate::{LoadOrderNode, NtLoadOrderEntry};
+
+/// Arranges the entries in a tree, where every entry owns the entries it has imported.
+/// Entries on the same level keep their order.
+pub(crate) fn build_tree(entries: Vec<NtLoadOrderEntry>) -> Vec<LoadOrderNode> {
+    let indexes_by_image_path = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| (entry.image_path.to_ascii_lowercase(), index))
+        .collect::<HashMap<_, _>>();
+
+    // Determine the children of every entry.
+    // Imports are loaded before their importers, so this can't be done in a single pass over the entries.
+    let mut root_indexes = Vec::new();
+    let mut child_indexes = vec![Vec::new(); entries.len()];
+
+    for (index, entry) in entries.iter().enumerate() {
+        let parent_index = entry.imported_by.as_ref().and_then(|imported_by| {
+            indexes_by_image_path
+                .get(&imported_by.to_ascii_lowercase())
+                .copied()
+        });
+
+        match parent_index {
+            Some(parent_index) => child_indexes[parent_index].push(index),
+            None => root_indexes.push(index),
+        }
+    }
+
+    let mut entries = entries.into_iter().map(Some).collect::<Vec<_>>();
+    root_indexes
+        .into_iter()
+        .map(|index| take_node(index, &mut entries, &child_indexes))
+        .collect()
+}
+
+fn take_node(
+    index: usize,
+    entries: &mut [Option<NtLoadOrderEntry>],
+    child_indexes: &[Vec<usize>],
+) -> LoadOrderNode {
+    let entry = entries[index].take().unwrap();
+    let children = child_indexes[index]
+        .iter()
+        .map(|&child_index| take_node(child_index, entries, child_indexes))
+        .collect();
+
+    LoadOrderNode { entry, children }
+}
+
+/// Iterator over a [`LoadOrderNode`] and its descendants, returned by [`LoadOrderNode::iter`].
+pub struct LoadOrderNodes<'a> {
+    pub(crate) stack: Vec<&'a LoadOrderNode>,
+}
+
+impl<'a> Iterator for LoadOrderNodes<'a> {
+    type Item = &'a LoadOrderNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children.iter().rev());
+        Some(node)
+    }
+}
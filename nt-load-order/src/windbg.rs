@@ -0,0 +1,80 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fmt::Write;
+
+use crate::{NtLoadOrderEntry, NtLoadOrderEntryKind};
+
+/// Width of the module name column, as used by WinDbg.
+const MODULE_NAME_WIDTH: usize = 8;
+
+/// Formats the entries like WinDbg's `lm f` command lists the loaded kernel modules, for a direct comparison
+/// with a kernel debugging session.
+///
+/// The output consists of a "module name" header line followed by one line per entry, in load order:
+///
+/// ```text
+/// module name
+/// nt       \SystemRoot\System32\ntoskrnl.exe
+/// hal      \SystemRoot\System32\hal.dll
+/// pci      \SystemRoot\System32\drivers\pci.sys
+/// ```
+///
+/// As the image base addresses are only known at runtime, the "start" and "end" columns of `lm` are omitted.
+/// `lm` sorts the modules by address, so compare the output with the debugger's module list in load order,
+/// e.g. by walking `nt!PsLoadedModuleList`.
+///
+/// The module name is the file name without extension, except for the kernel, which WinDbg always calls "nt".
+/// Relative image paths are prefixed with `\SystemRoot\` and drive-letter paths with `\??\`, like in the
+/// `FullDllName` of a loaded module.
+/// Unresolved imports are skipped, because WinDbg only lists modules that have been loaded.
+pub fn to_windbg_modules(entries: &[NtLoadOrderEntry]) -> String {
+    let mut output = "module name\n".to_string();
+
+    for entry in entries {
+        if entry.kind == NtLoadOrderEntryKind::UnresolvedImport {
+            continue;
+        }
+
+        let module_name = windbg_module_name(entry);
+        let full_path = windbg_full_path(&entry.image_path);
+        writeln!(output, "{module_name:<MODULE_NAME_WIDTH$} {full_path}").unwrap();
+    }
+
+    output
+}
+
+fn windbg_module_name(entry: &NtLoadOrderEntry) -> String {
+    if entry.kind == NtLoadOrderEntryKind::KernelBinary && entry.name == "ntoskrnl" {
+        return "nt".to_string();
+    }
+
+    let file_name = entry
+        .image_path
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or_default();
+    let stem = match file_name.rsplit_once('.') {
+        Some((stem, _extension)) if !stem.is_empty() => stem,
+        _ => file_name,
+    };
+
+    // WinDbg replaces all characters that are invalid in symbol names.
+    stem.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn windbg_full_path(image_path: &str) -> String {
+    let bytes = image_path.as_bytes();
+    let is_absolute =
+        bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\';
+
+    if image_path.starts_with('\\') {
+        image_path.to_string()
+    } else if is_absolute {
+        format!("\\??\\{image_path}")
+    } else {
+        format!("\\SystemRoot\\{image_path}")
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_alternative_extension() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "NoExtension", Some("Boot Bus Extender"), Some(1));
+    hive.set_sz(
+        &service_key("NoExtension"),
+        "ImagePath",
+        "System32\\drivers\\noextension",
+    );
+    add_boot_service(
+        &mut hive,
+        "WrongExtension",
+        Some("Boot Bus Extender"),
+        Some(2),
+    );
+    hive.set_sz(
+        &service_key("WrongExtension"),
+        "ImagePath",
+        "System32\\drivers\\wrongextension.dll",
+    );
+    let system_root = TestSystemRoot::new(&hive);
+
+    for path in [
+        "System32\\drivers\\noextension.sys",
+        "System32\\drivers\\wrongextension.sys",
+        "System32\\drivers\\ntfs.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .get()
+        .unwrap();
+
+    assert_eq!(entries[0].image_path, "System32\\drivers\\noextension.sys");
+    assert!(
+        entries[0].reason.ends_with(
+            ", using \"System32\\drivers\\noextension.sys\" instead of the missing \
+             \"System32\\drivers\\noextension\""
+        ),
+        "{}",
+        entries[0].reason
+    );
+
+    assert_eq!(
+        entries[1].image_path,
+        "System32\\drivers\\wrongextension.sys"
+    );
+    assert_eq!(entries[1].machine, Some(0x8664));
+}
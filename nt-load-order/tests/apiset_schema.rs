@@ -0,0 +1,212 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::path::Path;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_apiset_schema_info() {
+    let system_root = TestSystemRoot::new(&system_hive());
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+
+    let info = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .apiset_schema_info()
+        .unwrap();
+
+    let expected_path = Path::new(&system_root.path())
+        .join("System32")
+        .join("apisetschema.dll");
+    assert_eq!(info.path, expected_path);
+    assert_eq!(info.version, 6);
+}
+
+#[test]
+fn test_apiset_schema_info_without_apiset_section() {
+    let system_root = TestSystemRoot::new(&system_hive());
+    system_root.write_file("System32\\apisetschema.dll", &PeBuilder::new().to_bytes());
+
+    let result = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .apiset_schema_info();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_import_via_api_set() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Acpi", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-wmi-l1-1-0", "wmilib.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+        "System32\\drivers\\wmilib.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\acpi.sys",
+        &PeBuilder::new()
+            .import("api-ms-win-core-wmi-l1-1-0.dll")
+            .import("pcw.sys")
+            .to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .get()
+        .unwrap();
+
+    let wmilib = entries
+        .iter()
+        .find(|entry| entry.name == "wmilib.sys")
+        .unwrap();
+    assert_eq!(
+        wmilib.via_api_set.as_deref(),
+        Some("api-ms-win-core-wmi-l1-1-0")
+    );
+    assert_eq!(
+        wmilib.reason,
+        "Import of \"System32\\drivers\\acpi.sys\" via api-set \"api-ms-win-core-wmi-l1-1-0\""
+    );
+
+    let pcw = entries
+        .iter()
+        .find(|entry| entry.name == "pcw.sys")
+        .unwrap();
+    assert_eq!(pcw.via_api_set, None);
+}
+
+#[test]
+fn test_import_via_multi_value_api_set() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Acpi", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    // The default entry is preferred regardless of its position,
+    // otherwise the lexicographically smallest host is taken.
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry_with_values(
+                "api-ms-win-core-wmi-l1-1-0",
+                &[("acpi.sys", "wmilib2.sys"), ("", "wmilib.sys")],
+            )
+            .apiset_entry_with_values(
+                "api-ms-win-core-foo-l1-1-0",
+                &[("pci.sys", "foo2.sys"), ("other.sys", "foo.sys")],
+            )
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\wmilib.sys",
+        "System32\\drivers\\wmilib2.sys",
+        "System32\\drivers\\foo.sys",
+        "System32\\drivers\\foo2.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\acpi.sys",
+        &PeBuilder::new()
+            .import("api-ms-win-core-wmi-l1-1-0.dll")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new()
+            .import("api-ms-win-core-foo-l1-1-0.dll")
+            .to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .get()
+        .unwrap();
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+
+    assert!(names.contains(&"wmilib.sys"), "{names:?}");
+    assert!(names.contains(&"foo.sys"), "{names:?}");
+    assert!(!names.contains(&"wmilib2.sys"), "{names:?}");
+    assert!(!names.contains(&"foo2.sys"), "{names:?}");
+}
+
+#[test]
+fn test_missing_apiset_schema() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new()
+            .import("pcw.sys")
+            .import("api-ms-win-core-wmi-l1-1-0.dll")
+            .to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .strict_imports(false)
+        .get()
+        .unwrap();
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+
+    // Without an API Set Map, the API Set import is looked up by its file name.
+    assert_eq!(
+        names,
+        [
+            "ntoskrnl",
+            "hal",
+            "Pci",
+            "pcw.sys",
+            "api-ms-win-core-wmi-l1-1-0.dll",
+            "ntfs"
+        ]
+    );
+    assert_eq!(
+        entries[2].warnings,
+        ["Cannot resolve API Set import \"api-ms-win-core-wmi-l1-1-0.dll\" without an \"apisetschema.dll\""]
+    );
+}
@@ -0,0 +1,39 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#![cfg(all(
+    feature = "autodetect-cpu-vendor",
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+
+use nt_load_order::NtLoadOrder;
+
+fn mcupdate_image_path(nt_load_order: &NtLoadOrder) -> Option<String> {
+    nt_load_order
+        .kernel_binaries()
+        .into_iter()
+        .find(|entry| entry.name == "mcupdate")
+        .map(|entry| entry.image_path)
+}
+
+#[test]
+fn test_autodetect_cpu_vendor() {
+    let nt_load_order = NtLoadOrder::new().autodetect_cpu_vendor(true);
+    let image_path = mcupdate_image_path(&nt_load_order).unwrap();
+    assert!(image_path.starts_with("System32\\mcupdate_"));
+
+    // An explicitly set CPU vendor takes precedence.
+    let nt_load_order = nt_load_order.cpu_vendor(Some("AuthenticAMD".to_string()));
+    assert_eq!(
+        mcupdate_image_path(&nt_load_order).unwrap(),
+        "System32\\mcupdate_AuthenticAMD.dll"
+    );
+}
+
+#[test]
+fn test_autodetect_cpu_vendor_ignored_for_target_system_root() {
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some("/nonexistent".to_string()))
+        .autodetect_cpu_vendor(true);
+    assert_eq!(mcupdate_image_path(&nt_load_order), None);
+}
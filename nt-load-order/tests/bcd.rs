@@ -0,0 +1,119 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::path::PathBuf;
+
+use nt_load_order::{BcdBootEntry, NtLoadOrder, SafeBootMode};
+
+use crate::common::{system_hive, HiveBuilder, TestSystemRoot};
+
+const BOOTMGR: &str = "Objects\\{9dea862c-5cdd-4e70-acc1-f32b344d4795}\\Elements";
+const DBGSETTINGS: &str = "Objects\\{4636856e-540f-4170-a130-a84776f4c654}\\Elements";
+const OS_LOADER_GUID: &str = "{b2721d73-1db4-4c62-bf78-c548a880142d}";
+
+fn bcd_hive() -> HiveBuilder {
+    let mut hive = HiveBuilder::new();
+    hive.set_sz(&format!("{BOOTMGR}\\23000003"), "Element", OS_LOADER_GUID);
+    hive.set_sz(&os_loader_element("12000004"), "Element", "Windows 10");
+    hive.set_sz(&os_loader_element("22000002"), "Element", "\\Windows");
+    hive
+}
+
+fn os_loader_element(element_type: &str) -> String {
+    format!("Objects\\{OS_LOADER_GUID}\\Elements\\{element_type}")
+}
+
+/// Writes the BCD store next to the SYSTEM hive and returns its path.
+fn write_bcd(system_root: &TestSystemRoot, bcd: &HiveBuilder) -> PathBuf {
+    system_root.write_file("Boot\\BCD", &bcd.to_bytes());
+    PathBuf::from(system_root.path()).join("Boot").join("BCD")
+}
+
+#[test]
+fn test_default_boot_entry() {
+    let system_root = TestSystemRoot::new(&system_hive());
+    let bcd_path = write_bcd(&system_root, &bcd_hive());
+
+    let entry = BcdBootEntry::from_bcd_store(bcd_path).unwrap();
+    assert_eq!(entry.guid, OS_LOADER_GUID);
+    assert_eq!(entry.description.as_deref(), Some("Windows 10"));
+    assert_eq!(entry.system_root.as_deref(), Some("\\Windows"));
+    assert_eq!(entry.safe_boot, None);
+    assert_eq!(entry.kd_driver, None);
+    assert!(!entry.winpe);
+}
+
+#[test]
+fn test_safe_boot() {
+    let system_root = TestSystemRoot::new(&system_hive());
+
+    for (value, mode) in [
+        (0u64, SafeBootMode::Minimal),
+        (1, SafeBootMode::Network),
+        (2, SafeBootMode::Minimal),
+    ] {
+        let mut bcd = bcd_hive();
+        bcd.set_binary(
+            &os_loader_element("25000080"),
+            "Element",
+            &value.to_le_bytes(),
+        );
+        let bcd_path = write_bcd(&system_root, &bcd);
+
+        let entry = BcdBootEntry::from_bcd_store(bcd_path).unwrap();
+        assert_eq!(entry.safe_boot, Some(mode));
+    }
+}
+
+#[test]
+fn test_debugger_settings() {
+    let mut bcd = bcd_hive();
+    bcd.set_binary(&os_loader_element("260000A0"), "Element", &[1]);
+    bcd.set_binary(
+        &format!("{DBGSETTINGS}\\15000011"),
+        "Element",
+        &3u64.to_le_bytes(),
+    );
+
+    let system_root = TestSystemRoot::new(&system_hive());
+    let bcd_path = write_bcd(&system_root, &bcd);
+
+    let entry = BcdBootEntry::from_bcd_store(bcd_path).unwrap();
+    assert_eq!(entry.kd_driver.as_deref(), Some("kdnet"));
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .bcd_boot_entry(&entry)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    assert!(entries.iter().any(|entry| entry.name == "kdnet"));
+    assert!(!entries.iter().any(|entry| entry.name == "kdcom"));
+
+    // A debugger type on the boot entry itself takes precedence.
+    bcd.set_binary(
+        &os_loader_element("15000011"),
+        "Element",
+        &0u64.to_le_bytes(),
+    );
+    let bcd_path = write_bcd(&system_root, &bcd);
+    let entry = BcdBootEntry::from_bcd_store(bcd_path).unwrap();
+    assert_eq!(entry.kd_driver.as_deref(), Some("kdcom"));
+}
+
+#[test]
+fn test_missing_default_boot_entry() {
+    let mut bcd = HiveBuilder::new();
+    bcd.create_key(BOOTMGR);
+
+    let system_root = TestSystemRoot::new(&system_hive());
+    let bcd_path = write_bcd(&system_root, &bcd);
+
+    let error = BcdBootEntry::from_bcd_store(bcd_path).err().unwrap();
+    assert!(
+        format!("{error:#}").contains("no default boot entry"),
+        "{error:#}"
+    );
+}
@@ -0,0 +1,91 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntryKind};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot, CONTROL_SET};
+
+#[test]
+fn test_boot_execute() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Beep", None, None);
+    hive.set_multi_sz(
+        &format!("{CONTROL_SET}\\Control\\Session Manager"),
+        "BootExecute",
+        &["autocheck autochk *", "sdnclean64.exe /quiet", ""],
+    );
+    let system_root = TestSystemRoot::new(&hive);
+
+    let get_entries = |include_boot_execute| {
+        NtLoadOrder::new()
+            .system_root(Some(system_root.path()))
+            .add_kernel_binaries(false)
+            .add_imports(false)
+            .include_boot_execute(include_boot_execute)
+            .get()
+            .unwrap()
+    };
+
+    assert_eq!(get_entries(false).len(), 2);
+
+    // The applications are appended after all drivers.
+    let entries = get_entries(true);
+    let boot_execute = entries
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.image_path.as_str(), entry.kind))
+        .skip(2)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        boot_execute,
+        [
+            (
+                "autochk",
+                "System32\\autochk.exe",
+                NtLoadOrderEntryKind::BootExecute
+            ),
+            (
+                "sdnclean64.exe",
+                "System32\\sdnclean64.exe",
+                NtLoadOrderEntryKind::BootExecute
+            ),
+        ]
+    );
+    assert_eq!(
+        entries[2].reason,
+        "Boot Execute via \"autocheck autochk *\""
+    );
+}
+
+#[test]
+fn test_boot_execute_without_imports() {
+    let mut hive = system_hive();
+    hive.set_multi_sz(
+        &format!("{CONTROL_SET}\\Control\\Session Manager"),
+        "BootExecute",
+        &["autocheck autochk *"],
+    );
+    let system_root = TestSystemRoot::new(&hive);
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    system_root.write_file("System32\\drivers\\ntfs.sys", &PeBuilder::new().to_bytes());
+
+    // The missing "autochk.exe" is not opened for adding imports.
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .include_boot_execute(true)
+        .get()
+        .unwrap();
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["ntfs", "autochk"]);
+}
@@ -0,0 +1,88 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{reconcile_with_boot_log, NtLoadOrder, NtLoadOrderEntry};
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+fn get_entries() -> Vec<NtLoadOrderEntry> {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "ACPI", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "pci", Some("Boot Bus Extender"), Some(2));
+    add_boot_service(&mut hive, "Disk", Some("Boot Bus Extender"), Some(3));
+    add_boot_service(&mut hive, "Beep", None, None);
+
+    let system_root = TestSystemRoot::new(&hive);
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap()
+}
+
+fn names(entries: &[NtLoadOrderEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.name.as_str()).collect()
+}
+
+#[test]
+fn test_reconcile_consistent() {
+    let entries = get_entries();
+    assert_eq!(names(&entries), ["ACPI", "pci", "Disk", "ntfs", "Beep"]);
+
+    let boot_log = "\
+Microsoft (R) Windows (R) Version 10.0 (Build 19041)
+ 1 26 2025 14:20:35.500
+Loaded driver \\SystemRoot\\system32\\drivers\\acpi.sys
+Loaded driver \\SystemRoot\\System32\\drivers\\pci.sys
+Loaded driver \\SystemRoot\\System32\\drivers\\disk.sys
+Loaded driver \\SystemRoot\\System32\\Drivers\\Ntfs.sys
+Loaded driver \\SystemRoot\\System32\\drivers\\beep.sys
+Loaded driver \\SystemRoot\\System32\\drivers\\afd.sys
+";
+    let report = reconcile_with_boot_log(&entries, boot_log);
+    assert!(report.is_consistent(), "{report:?}");
+}
+
+#[test]
+fn test_reconcile_discrepancies() {
+    let entries = get_entries();
+
+    // An earlier boot is ignored, only the last one counts.
+    let boot_log = "\
+Microsoft (R) Windows (R) Version 10.0 (Build 19041)
+ 1 25 2025 09:00:00.500
+Did not load driver \\SystemRoot\\System32\\drivers\\acpi.sys
+Microsoft (R) Windows (R) Version 10.0 (Build 19041)
+ 1 26 2025 14:20:35.500
+Loaded driver \\SystemRoot\\system32\\drivers\\acpi.sys
+Loaded driver \\SystemRoot\\System32\\drivers\\disk.sys
+Loaded driver \\SystemRoot\\System32\\drivers\\wd\\WdBoot.sys
+Loaded driver \\SystemRoot\\System32\\drivers\\pci.sys
+Did not load driver \\SystemRoot\\System32\\drivers\\beep.sys
+Loaded driver \\SystemRoot\\System32\\drivers\\afd.sys
+";
+    let report = reconcile_with_boot_log(&entries, boot_log);
+
+    let out_of_order = report
+        .out_of_order
+        .iter()
+        .map(|mismatch| {
+            (
+                mismatch.entry.name.as_str(),
+                mismatch.predicted_index,
+                mismatch.actual_index,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(out_of_order, [("pci", 1, 3)]);
+    assert_eq!(names(&report.not_loaded), ["Beep"]);
+    assert_eq!(names(&report.missing), ["ntfs"]);
+    assert_eq!(
+        report.unexpected,
+        ["\\SystemRoot\\System32\\drivers\\wd\\WdBoot.sys"]
+    );
+    assert!(!report.is_consistent());
+}
@@ -0,0 +1,52 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot, CONTROL_SET};
+
+const DISK_DRIVE_CLASS: &str = "{4d36e967-e325-11ce-bfc1-08002be10318}";
+
+#[test]
+fn test_class_filters() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "disk", Some("SCSI Class"), None);
+    add_boot_service(&mut hive, "partmgr", Some("System Bus Extender"), None);
+    add_boot_service(&mut hive, "EhStorClass", Some("System Bus Extender"), None);
+
+    // A filter driver that is not boot-start is not loaded by the bootloader.
+    add_boot_service(&mut hive, "fvevol", Some("Filter"), None);
+    hive.set_dword(&service_key("fvevol"), "Start", 3);
+
+    let class = format!("{CONTROL_SET}\\Control\\Class\\{DISK_DRIVE_CLASS}");
+    hive.set_sz(&class, "Class", "DiskDrive");
+    hive.set_multi_sz(&class, "UpperFilters", &["partmgr", "fvevol"]);
+    hive.set_multi_sz(&class, "LowerFilters", &["EhStorClass"]);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let reason = |name: &str| {
+        entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.reason.as_str())
+    };
+
+    assert_eq!(
+        reason("partmgr"),
+        Some("Boot Driver via its \"Start\" value; filter driver in \"UpperFilters\" of class \"DiskDrive\"")
+    );
+    assert_eq!(
+        reason("EhStorClass"),
+        Some("Boot Driver via its \"Start\" value; filter driver in \"LowerFilters\" of class \"DiskDrive\"")
+    );
+    assert_eq!(reason("disk"), Some("Boot Driver via its \"Start\" value"));
+    assert_eq!(reason("fvevol"), None);
+}
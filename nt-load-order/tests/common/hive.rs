@@ -0,0 +1,332 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A minimal writer for registry hive files, just capable enough to create SYSTEM hives for the tests.
+
+const REG_SZ: u32 = 1;
+const REG_BINARY: u32 = 3;
+const REG_DWORD: u32 = 4;
+const REG_MULTI_SZ: u32 = 7;
+const REG_QWORD: u32 = 11;
+
+const BASE_BLOCK_SIZE: usize = 4096;
+/// Maximum data size of a single cell, beyond which data is split into Big Data segments.
+const BIG_DATA_SEGMENT_SIZE: usize = 16344;
+const HBIN_HEADER_SIZE: usize = 32;
+
+const KEY_HIVE_ENTRY: u16 = 0x0004;
+const KEY_COMP_NAME: u16 = 0x0020;
+const VALUE_COMP_NAME: u16 = 0x0001;
+
+#[derive(Default)]
+pub struct HiveBuilder {
+    root: KeyBuilder,
+}
+
+#[derive(Default)]
+struct KeyBuilder {
+    name: String,
+    subkeys: Vec<KeyBuilder>,
+    values: Vec<ValueBuilder>,
+}
+
+struct ValueBuilder {
+    name: String,
+    data_type: u32,
+    data: Vec<u8>,
+}
+
+impl HiveBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the key at the given backslash-separated path, including all parent keys.
+    pub fn create_key(&mut self, path: &str) {
+        self.key_mut(path);
+    }
+
+    /// Removes the key at the given path along with all of its subkeys and values.
+    pub fn remove_key(&mut self, path: &str) {
+        let (parent_path, name) = path.rsplit_once('\\').unwrap_or(("", path));
+        let parent = self.key_mut(parent_path);
+        parent
+            .subkeys
+            .retain(|subkey| !subkey.name.eq_ignore_ascii_case(name));
+    }
+
+    pub fn set_binary(&mut self, path: &str, name: &str, data: &[u8]) {
+        self.set_value(path, name, REG_BINARY, data.to_vec());
+    }
+
+    pub fn set_dword(&mut self, path: &str, name: &str, data: u32) {
+        self.set_value(path, name, REG_DWORD, data.to_le_bytes().to_vec());
+    }
+
+    pub fn set_multi_sz(&mut self, path: &str, name: &str, data: &[&str]) {
+        let mut bytes = Vec::new();
+        for string in data {
+            bytes.extend(utf16_nul_terminated(string));
+        }
+        bytes.extend([0, 0]);
+
+        self.set_value(path, name, REG_MULTI_SZ, bytes);
+    }
+
+    pub fn set_qword(&mut self, path: &str, name: &str, data: u64) {
+        self.set_value(path, name, REG_QWORD, data.to_le_bytes().to_vec());
+    }
+
+    pub fn set_sz(&mut self, path: &str, name: &str, data: &str) {
+        self.set_value(path, name, REG_SZ, utf16_nul_terminated(data));
+    }
+
+    /// Sets a value with an arbitrary data type and raw data.
+    pub fn set_value(&mut self, path: &str, name: &str, data_type: u32, data: Vec<u8>) {
+        let key = self.key_mut(path);
+        key.values
+            .retain(|value| !value.name.eq_ignore_ascii_case(name));
+        key.values.push(ValueBuilder {
+            name: name.to_string(),
+            data_type,
+            data,
+        });
+    }
+
+    /// Serializes the hive into the bytes of a regf file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut cells = CellWriter::default();
+        let root_cell_offset = cells.write_key(&self.root, true);
+        let data = cells.finish();
+
+        let mut base_block = vec![0u8; BASE_BLOCK_SIZE];
+        base_block[0..4].copy_from_slice(b"regf");
+        put_u32(&mut base_block, 4, 1); // primary sequence number
+        put_u32(&mut base_block, 8, 1); // secondary sequence number
+        put_u32(&mut base_block, 20, 1); // major version
+        put_u32(&mut base_block, 24, 5); // minor version
+        put_u32(&mut base_block, 28, 0); // file type (primary)
+        put_u32(&mut base_block, 32, 1); // file format (memory)
+        put_u32(&mut base_block, 36, root_cell_offset);
+        put_u32(&mut base_block, 40, data.len() as u32);
+        put_u32(&mut base_block, 44, 1); // clustering factor
+
+        let mut checksum = base_block[..508].chunks(4).fold(0u32, |acc, dword| {
+            acc ^ u32::from_le_bytes(dword.try_into().unwrap())
+        });
+        if checksum == 0 {
+            checksum = 1;
+        } else if checksum == u32::MAX {
+            checksum = u32::MAX - 1;
+        }
+        put_u32(&mut base_block, 508, checksum);
+
+        base_block.extend(data);
+        base_block
+    }
+
+    fn key_mut(&mut self, path: &str) -> &mut KeyBuilder {
+        let mut key = &mut self.root;
+
+        for component in path.split('\\').filter(|c| !c.is_empty()) {
+            let index = match key
+                .subkeys
+                .iter()
+                .position(|subkey| subkey.name.eq_ignore_ascii_case(component))
+            {
+                Some(index) => index,
+                None => {
+                    key.subkeys.push(KeyBuilder {
+                        name: component.to_string(),
+                        ..Default::default()
+                    });
+                    key.subkeys.len() - 1
+                }
+            };
+
+            key = &mut key.subkeys[index];
+        }
+
+        key
+    }
+}
+
+/// Sequentially allocates cells in a single hive bin.
+struct CellWriter {
+    data: Vec<u8>,
+}
+
+impl Default for CellWriter {
+    fn default() -> Self {
+        let mut data = vec![0u8; HBIN_HEADER_SIZE];
+        data[0..4].copy_from_slice(b"hbin");
+        Self { data }
+    }
+}
+
+impl CellWriter {
+    fn allocate(&mut self, content: &[u8]) -> u32 {
+        let offset = self.data.len() as u32;
+        let size = (4 + content.len()).next_multiple_of(8);
+
+        self.data.extend((-(size as i32)).to_le_bytes());
+        self.data.extend(content);
+        self.data.resize(offset as usize + size, 0);
+
+        offset
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let size = self.data.len().next_multiple_of(BASE_BLOCK_SIZE);
+
+        // Mark the remaining space of the hive bin as a free cell.
+        let remaining = size - self.data.len();
+        if remaining > 0 {
+            self.data.extend((remaining as i32).to_le_bytes());
+            self.data.resize(size, 0);
+        }
+
+        put_u32(&mut self.data, 8, size as u32);
+        self.data
+    }
+
+    fn write_key(&mut self, key: &KeyBuilder, is_root: bool) -> u32 {
+        // Subkeys must be sorted by their uppercased names for the binary search performed by readers.
+        let mut subkeys = key.subkeys.iter().collect::<Vec<_>>();
+        subkeys.sort_by_key(|subkey| {
+            subkey
+                .name
+                .to_uppercase()
+                .encode_utf16()
+                .collect::<Vec<_>>()
+        });
+
+        let subkey_offsets = subkeys
+            .iter()
+            .map(|subkey| self.write_key(subkey, false))
+            .collect::<Vec<_>>();
+        let subkeys_list_offset = if subkey_offsets.is_empty() {
+            u32::MAX
+        } else {
+            let mut leaf = b"li".to_vec();
+            leaf.extend((subkey_offsets.len() as u16).to_le_bytes());
+            for offset in &subkey_offsets {
+                leaf.extend(offset.to_le_bytes());
+            }
+            self.allocate(&leaf)
+        };
+
+        let value_offsets = key
+            .values
+            .iter()
+            .map(|value| self.write_value(value))
+            .collect::<Vec<_>>();
+        let key_values_list_offset = if value_offsets.is_empty() {
+            u32::MAX
+        } else {
+            let list = value_offsets
+                .iter()
+                .flat_map(|offset| offset.to_le_bytes())
+                .collect::<Vec<_>>();
+            self.allocate(&list)
+        };
+
+        let (name_bytes, comp_name) = encode_name(&key.name);
+        let mut flags = if comp_name { KEY_COMP_NAME } else { 0 };
+        if is_root {
+            flags |= KEY_HIVE_ENTRY;
+        }
+
+        let mut node = b"nk".to_vec();
+        node.extend(flags.to_le_bytes());
+        node.extend(0u64.to_le_bytes()); // timestamp
+        node.extend(0u32.to_le_bytes()); // spare
+        node.extend(0u32.to_le_bytes()); // parent
+        node.extend((subkey_offsets.len() as u32).to_le_bytes());
+        node.extend(0u32.to_le_bytes()); // volatile subkey count
+        node.extend(subkeys_list_offset.to_le_bytes());
+        node.extend(u32::MAX.to_le_bytes()); // volatile subkeys list offset
+        node.extend((value_offsets.len() as u32).to_le_bytes());
+        node.extend(key_values_list_offset.to_le_bytes());
+        node.extend(u32::MAX.to_le_bytes()); // key security offset
+        node.extend(u32::MAX.to_le_bytes()); // class name offset
+        node.extend([0u8; 20]); // max subkey name, max subkey class name, max value name, max value data, work var
+        node.extend((name_bytes.len() as u16).to_le_bytes());
+        node.extend(0u16.to_le_bytes()); // class name length
+        node.extend(name_bytes);
+
+        self.allocate(&node)
+    }
+
+    fn write_value(&mut self, value: &ValueBuilder) -> u32 {
+        let (data_size, data_offset) = if value.data.len() <= 4 {
+            // Small data is stored directly in the `data_offset` field.
+            let mut inline = [0u8; 4];
+            inline[..value.data.len()].copy_from_slice(&value.data);
+            (
+                value.data.len() as u32 | 0x8000_0000,
+                u32::from_le_bytes(inline),
+            )
+        } else if value.data.len() <= BIG_DATA_SEGMENT_SIZE {
+            (value.data.len() as u32, self.allocate(&value.data))
+        } else {
+            (value.data.len() as u32, self.write_big_data(&value.data))
+        };
+
+        let (name_bytes, comp_name) = encode_name(&value.name);
+        let flags = if comp_name { VALUE_COMP_NAME } else { 0 };
+
+        let mut vk = b"vk".to_vec();
+        vk.extend((name_bytes.len() as u16).to_le_bytes());
+        vk.extend(data_size.to_le_bytes());
+        vk.extend(data_offset.to_le_bytes());
+        vk.extend(value.data_type.to_le_bytes());
+        vk.extend(flags.to_le_bytes());
+        vk.extend(0u16.to_le_bytes()); // spare
+        vk.extend(name_bytes);
+
+        self.allocate(&vk)
+    }
+
+    /// Writes the data in segments, referenced by a segment list and a "db" header cell.
+    fn write_big_data(&mut self, data: &[u8]) -> u32 {
+        let segment_offsets = data
+            .chunks(BIG_DATA_SEGMENT_SIZE)
+            .map(|segment| self.allocate(segment))
+            .collect::<Vec<_>>();
+        let segment_list = segment_offsets
+            .iter()
+            .flat_map(|offset| offset.to_le_bytes())
+            .collect::<Vec<_>>();
+        let segment_list_offset = self.allocate(&segment_list);
+
+        let mut db = b"db".to_vec();
+        db.extend((segment_offsets.len() as u16).to_le_bytes());
+        db.extend(segment_list_offset.to_le_bytes());
+        self.allocate(&db)
+    }
+}
+
+/// Encodes a key or value name, preferring the compressed Latin1 representation when possible.
+fn encode_name(name: &str) -> (Vec<u8>, bool) {
+    if name.chars().all(|c| (c as u32) < 0x100) {
+        (name.chars().map(|c| c as u8).collect(), true)
+    } else {
+        (
+            name.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            false,
+        )
+    }
+}
+
+fn put_u32(buffer: &mut [u8], offset: usize, value: u32) {
+    buffer[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn utf16_nul_terminated(string: &str) -> Vec<u8> {
+    string
+        .encode_utf16()
+        .chain(Some(0))
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
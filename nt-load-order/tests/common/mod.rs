@@ -0,0 +1,115 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Helpers shared by the integration tests to set up target system roots.
+
+// Not every test uses every helper.
+#![allow(dead_code)]
+
+pub mod hive;
+pub mod pe;
+pub mod transaction_log;
+
+use std::fs;
+use std::path::PathBuf;
+
+use tempfile::TempDir;
+
+pub use self::hive::HiveBuilder;
+
+pub const CONTROL_SET: &str = "ControlSet001";
+
+/// Returns the path of the given service key, relative to the hive root.
+pub fn service_key(name: &str) -> String {
+    format!("{CONTROL_SET}\\Services\\{name}")
+}
+
+/// Creates a SYSTEM hive with all keys required for analyzing the load order,
+/// including the "Ntfs" boot file system driver.
+pub fn system_hive() -> HiveBuilder {
+    let mut hive = HiveBuilder::new();
+    hive.set_dword("HardwareConfig", "LastId", 0);
+    hive.set_dword("Select", "Current", 1);
+    hive.set_multi_sz(
+        &format!("{CONTROL_SET}\\Control\\ServiceGroupOrder"),
+        "List",
+        &[
+            "Early-Launch",
+            "Core Platform Extensions",
+            "Boot Bus Extender",
+            "System Bus Extender",
+            "Boot File System",
+            "Filter",
+        ],
+    );
+    hive.set_binary(
+        &format!("{CONTROL_SET}\\Control\\GroupOrderList"),
+        "Boot Bus Extender",
+        &group_order_list_data(&[1, 2, 3]),
+    );
+
+    let ntfs = service_key("Ntfs");
+    hive.set_dword(&ntfs, "Start", 3);
+    hive.set_sz(&ntfs, "Group", "Boot File System");
+    hive.set_sz(&ntfs, "ImagePath", "System32\\drivers\\ntfs.sys");
+
+    hive
+}
+
+/// Adds a boot-start service with an image path of "System32\drivers\<lowercase name>.sys".
+pub fn add_boot_service(hive: &mut HiveBuilder, name: &str, group: Option<&str>, tag: Option<u32>) {
+    let key = service_key(name);
+    hive.set_dword(&key, "Start", 0);
+    hive.set_sz(
+        &key,
+        "ImagePath",
+        &format!("System32\\drivers\\{}.sys", name.to_ascii_lowercase()),
+    );
+
+    if let Some(group) = group {
+        hive.set_sz(&key, "Group", group);
+    }
+
+    if let Some(tag) = tag {
+        hive.set_dword(&key, "Tag", tag);
+    }
+}
+
+/// Encodes the data of a "GroupOrderList" value, consisting of the tag count followed by the tags.
+pub fn group_order_list_data(tags: &[u32]) -> Vec<u8> {
+    let count = tags.len() as u32;
+    count
+        .to_le_bytes()
+        .into_iter()
+        .chain(tags.iter().flat_map(|tag| tag.to_le_bytes()))
+        .collect()
+}
+
+/// A temporary target system root, which is deleted when dropped.
+pub struct TestSystemRoot {
+    dir: TempDir,
+}
+
+impl TestSystemRoot {
+    /// Creates a system root containing nothing but the given SYSTEM hive.
+    pub fn new(hive: &HiveBuilder) -> Self {
+        let system_root = Self {
+            dir: TempDir::new().unwrap(),
+        };
+        system_root.write_file("System32\\config\\SYSTEM", &hive.to_bytes());
+        system_root
+    }
+
+    pub fn path(&self) -> String {
+        self.dir.path().to_str().unwrap().to_string()
+    }
+
+    /// Writes a file at the given backslash-separated path relative to the system root.
+    pub fn write_file(&self, relative_path: &str, data: &[u8]) {
+        let mut path = PathBuf::from(self.dir.path());
+        path.extend(relative_path.split('\\'));
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, data).unwrap();
+    }
+}
@@ -0,0 +1,426 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A minimal writer for PE32+ (and PE32) images, just capable enough to create drivers with imports and
+//! an apisetschema.dll.
+
+const FILE_ALIGNMENT: usize = 0x200;
+const SECTION_ALIGNMENT: usize = 0x1000;
+const HEADERS_SIZE: usize = 0x200;
+
+const PE_HEADER_OFFSET: usize = 0x40;
+const OPTIONAL_HEADER_SIZE: usize = 240;
+const OPTIONAL_HEADER32_SIZE: usize = 224;
+const SECTION_HEADER_SIZE: usize = 40;
+
+const IMAGE_BASE: u64 = 0x1_4000_0000;
+const IMAGE_BASE32: u32 = 0x1000_0000;
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+const IMAGE_FILE_MACHINE_I386: u16 = 0x14c;
+
+const IMAGE_DIRECTORY_ENTRY_IMPORT: usize = 1;
+const IMAGE_DIRECTORY_ENTRY_SECURITY: usize = 4;
+const IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG: usize = 10;
+const IMAGE_SCN_CNT_INITIALIZED_DATA: u32 = 0x0000_0040;
+const IMAGE_SCN_MEM_READ: u32 = 0x4000_0000;
+
+const APISET_VERSION_WINDOWS_10: u32 = 6;
+const APISET_HASH_FACTOR: u32 = 31;
+
+#[derive(Default)]
+pub struct PeBuilder {
+    imports: Vec<String>,
+    /// API Set names with their value entries, each consisting of an importing module name and a host DLL.
+    apiset_entries: Vec<(String, Vec<(String, String)>)>,
+    embedded_signature: bool,
+    machine: Option<u16>,
+    chpe_metadata: bool,
+    pe32: bool,
+    subsystem: Option<u16>,
+}
+
+struct Section {
+    name: &'static [u8],
+    data: Vec<u8>,
+}
+
+impl PeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an import of the given DLL (e.g. "hal.dll").
+    pub fn import(mut self, dll_name: &str) -> Self {
+        self.imports.push(dll_name.to_string());
+        self
+    }
+
+    /// Adds an entry to an ".apiset" section, mapping an API Set (e.g. "api-ms-win-core-foo-l1-1-0")
+    /// to a host DLL (e.g. "foo.dll").
+    /// An empty host DLL marks the API Set as unavailable.
+    pub fn apiset_entry(mut self, name: &str, host: &str) -> Self {
+        self.apiset_entries
+            .push((name.to_string(), vec![(String::new(), host.to_string())]));
+        self
+    }
+
+    /// Like [`Self::apiset_entry`], but with multiple value entries in the given order.
+    /// Each value entry consists of the name of an importing module (empty for the default entry) and a host DLL.
+    pub fn apiset_entry_with_values(mut self, name: &str, values: &[(&str, &str)]) -> Self {
+        let values = values
+            .iter()
+            .map(|(importing_name, host)| (importing_name.to_string(), host.to_string()))
+            .collect();
+        self.apiset_entries.push((name.to_string(), values));
+        self
+    }
+
+    /// Appends a (dummy) embedded Authenticode signature.
+    pub fn embedded_signature(mut self) -> Self {
+        self.embedded_signature = true;
+        self
+    }
+
+    /// Sets the machine type of the file header, which defaults to AMD64.
+    pub fn machine(mut self, machine: u16) -> Self {
+        self.machine = Some(machine);
+        self
+    }
+
+    /// Adds a load configuration with a CHPE metadata pointer, as found in ARM64X and ARM64EC images.
+    pub fn chpe_metadata(mut self) -> Self {
+        self.chpe_metadata = true;
+        self
+    }
+
+    /// Creates a 32-bit PE32 image instead of a PE32+ one, with a machine type defaulting to I386.
+    pub fn pe32(mut self) -> Self {
+        self.pe32 = true;
+        self
+    }
+
+    /// Sets the subsystem of the optional header, which defaults to native (1).
+    pub fn subsystem(mut self, subsystem: u16) -> Self {
+        self.subsystem = Some(subsystem);
+        self
+    }
+
+    /// Serializes the image into the bytes of a PE32+ (or PE32) file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut sections = Vec::new();
+        let mut import_directory = (0, 0);
+
+        if !self.imports.is_empty() {
+            let rva = section_rva(sections.len());
+            let data = self.import_section_data(rva);
+            import_directory = (rva, 20 * (self.imports.len() as u32 + 1));
+            sections.push(Section {
+                name: b".idata",
+                data,
+            });
+        }
+
+        if !self.apiset_entries.is_empty() {
+            sections.push(Section {
+                name: b".apiset",
+                data: self.apiset_section_data(),
+            });
+        }
+
+        let mut load_config_directory = (0, 0);
+
+        if self.chpe_metadata {
+            const LOAD_CONFIG_SIZE: usize = 0xd0;
+            const CHPE_METADATA_POINTER_OFFSET: usize = 0xc8;
+
+            let rva = section_rva(sections.len());
+            let mut data = vec![0u8; LOAD_CONFIG_SIZE];
+            put_u32(&mut data, 0, LOAD_CONFIG_SIZE as u32);
+
+            // The pointer only needs to be non-zero.
+            let pointer = IMAGE_BASE + rva as u64 + LOAD_CONFIG_SIZE as u64;
+            data[CHPE_METADATA_POINTER_OFFSET..].copy_from_slice(&pointer.to_le_bytes());
+
+            load_config_directory = (rva, LOAD_CONFIG_SIZE as u32);
+            sections.push(Section {
+                name: b".rdata",
+                data,
+            });
+        }
+
+        let size_of_image = section_rva(sections.len());
+
+        // The signature is not mapped into memory, but appended to the file.
+        // Its directory entry therefore contains a file offset instead of an RVA.
+        let certificate = self.embedded_signature.then(|| {
+            let mut certificate = 16u32.to_le_bytes().to_vec(); // length
+            certificate.extend(0x0200u16.to_le_bytes()); // revision
+            certificate.extend(0x0002u16.to_le_bytes()); // type (PKCS#7 SignedData)
+            certificate.extend([0u8; 8]);
+            certificate
+        });
+        let raw_data_size = sections
+            .iter()
+            .map(|section| section.data.len().next_multiple_of(FILE_ALIGNMENT))
+            .sum::<usize>();
+        let security_directory = match &certificate {
+            Some(certificate) => (
+                (HEADERS_SIZE + raw_data_size) as u32,
+                certificate.len() as u32,
+            ),
+            None => (0, 0),
+        };
+
+        let (default_machine, optional_header_size) = if self.pe32 {
+            (IMAGE_FILE_MACHINE_I386, OPTIONAL_HEADER32_SIZE)
+        } else {
+            (IMAGE_FILE_MACHINE_AMD64, OPTIONAL_HEADER_SIZE)
+        };
+
+        let mut file = vec![0u8; HEADERS_SIZE];
+
+        // DOS header
+        file[0..2].copy_from_slice(b"MZ");
+        put_u32(&mut file, 0x3c, PE_HEADER_OFFSET as u32);
+
+        // PE signature and COFF file header
+        let mut header = b"PE\0\0".to_vec();
+        header.extend(self.machine.unwrap_or(default_machine).to_le_bytes());
+        header.extend((sections.len() as u16).to_le_bytes());
+        header.extend(0u32.to_le_bytes()); // timestamp
+        header.extend(0u32.to_le_bytes()); // pointer to symbol table
+        header.extend(0u32.to_le_bytes()); // number of symbols
+        header.extend((optional_header_size as u16).to_le_bytes());
+        if self.pe32 {
+            header.extend(0x2102u16.to_le_bytes()); // executable, 32-bit machine, DLL
+        } else {
+            header.extend(0x2022u16.to_le_bytes()); // executable, large address aware, DLL
+        }
+
+        // Optional header
+        if self.pe32 {
+            header.extend(0x10bu16.to_le_bytes()); // magic (PE32)
+        } else {
+            header.extend(0x20bu16.to_le_bytes()); // magic (PE32+)
+        }
+        header.extend([14, 0]); // linker version
+        header.extend(0u32.to_le_bytes()); // size of code
+        header.extend(0u32.to_le_bytes()); // size of initialized data
+        header.extend(0u32.to_le_bytes()); // size of uninitialized data
+        header.extend(0u32.to_le_bytes()); // address of entry point
+        header.extend(0u32.to_le_bytes()); // base of code
+        if self.pe32 {
+            header.extend(0u32.to_le_bytes()); // base of data
+            header.extend(IMAGE_BASE32.to_le_bytes());
+        } else {
+            header.extend(IMAGE_BASE.to_le_bytes());
+        }
+        header.extend((SECTION_ALIGNMENT as u32).to_le_bytes());
+        header.extend((FILE_ALIGNMENT as u32).to_le_bytes());
+        header.extend([10, 0, 0, 0]); // operating system version
+        header.extend([10, 0, 0, 0]); // image version
+        header.extend([10, 0, 0, 0]); // subsystem version
+        header.extend(0u32.to_le_bytes()); // win32 version
+        header.extend(size_of_image.to_le_bytes());
+        header.extend((HEADERS_SIZE as u32).to_le_bytes());
+        header.extend(0u32.to_le_bytes()); // checksum
+        header.extend(self.subsystem.unwrap_or(1).to_le_bytes()); // subsystem
+        header.extend(0u16.to_le_bytes()); // DLL characteristics
+        for size in [0x40000u64, 0x1000, 0x100000, 0x1000] {
+            // sizes of stack reserve, stack commit, heap reserve, and heap commit
+            if self.pe32 {
+                header.extend((size as u32).to_le_bytes());
+            } else {
+                header.extend(size.to_le_bytes());
+            }
+        }
+        header.extend(0u32.to_le_bytes()); // loader flags
+        header.extend(16u32.to_le_bytes()); // number of data directories
+
+        for index in 0..16 {
+            let (rva, size) = match index {
+                IMAGE_DIRECTORY_ENTRY_IMPORT => import_directory,
+                IMAGE_DIRECTORY_ENTRY_SECURITY => security_directory,
+                IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG => load_config_directory,
+                _ => (0, 0),
+            };
+            header.extend(rva.to_le_bytes());
+            header.extend(size.to_le_bytes());
+        }
+
+        // Section headers
+        let mut raw_offset = HEADERS_SIZE;
+        for (index, section) in sections.iter().enumerate() {
+            assert!(section.data.len() <= SECTION_ALIGNMENT);
+            let raw_size = section.data.len().next_multiple_of(FILE_ALIGNMENT);
+
+            let mut name = [0u8; 8];
+            name[..section.name.len()].copy_from_slice(section.name);
+            header.extend(name);
+            header.extend((section.data.len() as u32).to_le_bytes()); // virtual size
+            header.extend(section_rva(index).to_le_bytes());
+            header.extend((raw_size as u32).to_le_bytes());
+            header.extend((raw_offset as u32).to_le_bytes());
+            header.extend([0u8; 12]); // relocations and line numbers
+            header.extend((IMAGE_SCN_CNT_INITIALIZED_DATA | IMAGE_SCN_MEM_READ).to_le_bytes());
+
+            raw_offset += raw_size;
+        }
+
+        debug_assert!(
+            PE_HEADER_OFFSET + 24 + optional_header_size + SECTION_HEADER_SIZE * sections.len()
+                == PE_HEADER_OFFSET + header.len()
+        );
+        file[PE_HEADER_OFFSET..PE_HEADER_OFFSET + header.len()].copy_from_slice(&header);
+
+        // Section data
+        for section in sections {
+            let raw_size = section.data.len().next_multiple_of(FILE_ALIGNMENT);
+            let start = file.len();
+            file.extend(section.data);
+            file.resize(start + raw_size, 0);
+        }
+
+        if let Some(certificate) = certificate {
+            file.extend(certificate);
+        }
+
+        file
+    }
+
+    /// Builds an import section at the given RVA, importing ordinal 1 of every DLL.
+    fn import_section_data(&self, rva: u32) -> Vec<u8> {
+        // The thunks following the descriptors must be 8-byte aligned.
+        let descriptors_size = (20 * (self.imports.len() + 1)).next_multiple_of(8);
+        let mut descriptors = Vec::new();
+        let mut data = Vec::new();
+
+        for dll_name in &self.imports {
+            let thunks_rva = rva + (descriptors_size + data.len()) as u32;
+            if self.pe32 {
+                data.extend((1u32 << 31 | 1).to_le_bytes());
+                data.extend(0u32.to_le_bytes());
+            } else {
+                data.extend((1u64 << 63 | 1).to_le_bytes());
+                data.extend(0u64.to_le_bytes());
+            }
+
+            let name_rva = rva + (descriptors_size + data.len()) as u32;
+            data.extend(dll_name.as_bytes());
+            data.push(0);
+            data.resize(data.len().next_multiple_of(8), 0);
+
+            descriptors.extend(thunks_rva.to_le_bytes()); // import lookup table
+            descriptors.extend(0u32.to_le_bytes()); // timestamp
+            descriptors.extend(0u32.to_le_bytes()); // forwarder chain
+            descriptors.extend(name_rva.to_le_bytes());
+            descriptors.extend(thunks_rva.to_le_bytes()); // import address table
+        }
+
+        descriptors.resize(descriptors_size, 0);
+        descriptors.extend(data);
+        descriptors
+    }
+
+    /// Builds a version 6 (Windows 10) API Set Map.
+    fn apiset_section_data(&self) -> Vec<u8> {
+        const HEADER_SIZE: usize = 28;
+        const NAMESPACE_ENTRY_SIZE: usize = 24;
+        const VALUE_ENTRY_SIZE: usize = 20;
+        const HASH_ENTRY_SIZE: usize = 8;
+
+        // Namespace entries must be sorted by name.
+        let mut entries = self.apiset_entries.iter().collect::<Vec<_>>();
+        entries.sort();
+
+        let count = entries.len();
+        let value_count = entries
+            .iter()
+            .map(|(_, values)| values.len())
+            .sum::<usize>();
+        let namespace_entry_offset = HEADER_SIZE;
+        let value_entry_offset = namespace_entry_offset + NAMESPACE_ENTRY_SIZE * count;
+        let hash_entry_offset = value_entry_offset + VALUE_ENTRY_SIZE * value_count;
+        let mut strings_offset = hash_entry_offset + HASH_ENTRY_SIZE * count;
+
+        let mut namespace_entries = Vec::new();
+        let mut value_entries = Vec::new();
+        let mut hash_entries = Vec::new();
+        let mut strings = Vec::<u8>::new();
+        let mut value_index = 0;
+
+        let mut add_string = |string: &str| {
+            let bytes = utf16(string);
+            let offset = if bytes.is_empty() { 0 } else { strings_offset };
+            strings_offset += bytes.len();
+            strings.extend(&bytes);
+            (offset as u32, bytes.len() as u32)
+        };
+
+        for (index, (name, values)) in entries.into_iter().enumerate() {
+            let hashed_name = name
+                .rsplit_once('-')
+                .map_or(name.as_str(), |(hashed, _)| hashed);
+            let (name_offset, name_length) = add_string(name);
+
+            namespace_entries.extend(0u32.to_le_bytes()); // flags
+            namespace_entries.extend(name_offset.to_le_bytes());
+            namespace_entries.extend(name_length.to_le_bytes());
+            namespace_entries.extend((hashed_name.len() as u32 * 2).to_le_bytes());
+            namespace_entries.extend(
+                ((value_entry_offset + VALUE_ENTRY_SIZE * value_index) as u32).to_le_bytes(),
+            );
+            namespace_entries.extend((values.len() as u32).to_le_bytes());
+            value_index += values.len();
+
+            for (importing_name, host) in values {
+                let (importing_name_offset, importing_name_length) = add_string(importing_name);
+                let (host_offset, host_length) = add_string(host);
+
+                value_entries.extend(0u32.to_le_bytes()); // flags
+                value_entries.extend(importing_name_offset.to_le_bytes());
+                value_entries.extend(importing_name_length.to_le_bytes());
+                value_entries.extend(host_offset.to_le_bytes());
+                value_entries.extend(host_length.to_le_bytes());
+            }
+
+            let hash = hashed_name.chars().fold(0u32, |acc, c| {
+                acc.wrapping_mul(APISET_HASH_FACTOR).wrapping_add(c as u32)
+            });
+            hash_entries.push((hash, index as u32));
+        }
+
+        // Hash entries must be sorted by hash.
+        hash_entries.sort();
+
+        let mut data = Vec::new();
+        data.extend(APISET_VERSION_WINDOWS_10.to_le_bytes());
+        data.extend((strings_offset as u32).to_le_bytes()); // size
+        data.extend(0u32.to_le_bytes()); // flags
+        data.extend((count as u32).to_le_bytes());
+        data.extend((namespace_entry_offset as u32).to_le_bytes());
+        data.extend((hash_entry_offset as u32).to_le_bytes());
+        data.extend(APISET_HASH_FACTOR.to_le_bytes());
+        data.extend(namespace_entries);
+        data.extend(value_entries);
+        for (hash, index) in hash_entries {
+            data.extend(hash.to_le_bytes());
+            data.extend(index.to_le_bytes());
+        }
+        data.extend(strings);
+        data
+    }
+}
+
+/// Returns the RVA of the section with the given index, with every section occupying a single page.
+fn section_rva(index: usize) -> u32 {
+    (SECTION_ALIGNMENT * (index + 1)) as u32
+}
+
+fn put_u32(buffer: &mut [u8], offset: usize, value: u32) {
+    buffer[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn utf16(string: &str) -> Vec<u8> {
+    string.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
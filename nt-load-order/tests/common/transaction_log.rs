@@ -0,0 +1,93 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A minimal writer for transaction logs in the Windows 8.1 format, to test their replay.
+
+const BASE_BLOCK_SIZE: usize = 4096;
+const LOG_BASE_BLOCK_SIZE: usize = 512;
+const MARVIN32_SEED: u64 = 0x82ef4d887a4e55c5;
+
+/// Turns the bytes of a flushed hive and of its current state into a dirty hive and a transaction log,
+/// whose single entry contains all hive bins of the current state.
+pub fn dirty_hive_with_log(flushed: &[u8], current: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut dirty_hive = flushed.to_vec();
+    put_u32(&mut dirty_hive, 4, 2); // primary sequence number
+    put_u32(&mut dirty_hive, 8, 1); // secondary sequence number
+    update_checksum(&mut dirty_hive);
+
+    let mut log = current[..LOG_BASE_BLOCK_SIZE].to_vec();
+    put_u32(&mut log, 4, 1); // sequence number of the first entry
+    put_u32(&mut log, 8, 1);
+    put_u32(&mut log, 28, 6); // file type (log in the new format)
+    update_checksum(&mut log);
+
+    let hive_bins = &current[BASE_BLOCK_SIZE..];
+    let mut entry = vec![0u8; 48];
+    entry[0..4].copy_from_slice(b"HvLE");
+    put_u32(&mut entry, 12, 1); // sequence number
+    put_u32(&mut entry, 16, hive_bins.len() as u32);
+    put_u32(&mut entry, 20, 1); // dirty pages count
+    put_u32(&mut entry, 40, 0); // offset of the dirty page
+    put_u32(&mut entry, 44, hive_bins.len() as u32);
+    entry.extend(hive_bins);
+    entry.resize(entry.len().next_multiple_of(512), 0);
+
+    let size = entry.len() as u32;
+    put_u32(&mut entry, 4, size);
+    let hash_1 = marvin32(&entry[40..]);
+    entry[24..32].copy_from_slice(&hash_1.to_le_bytes());
+    let hash_2 = marvin32(&entry[..32]);
+    entry[32..40].copy_from_slice(&hash_2.to_le_bytes());
+
+    log.extend(entry);
+    (dirty_hive, log)
+}
+
+fn update_checksum(base_block: &mut [u8]) {
+    let mut checksum = base_block[..508].chunks(4).fold(0u32, |acc, dword| {
+        acc ^ u32::from_le_bytes(dword.try_into().unwrap())
+    });
+    if checksum == 0 {
+        checksum = 1;
+    } else if checksum == u32::MAX {
+        checksum = u32::MAX - 1;
+    }
+    put_u32(base_block, 508, checksum);
+}
+
+fn marvin32(data: &[u8]) -> u64 {
+    fn block(p0: &mut u32, p1: &mut u32) {
+        *p1 ^= *p0;
+        *p0 = p0.rotate_left(20);
+        *p0 = p0.wrapping_add(*p1);
+        *p1 = p1.rotate_left(9);
+        *p1 ^= *p0;
+        *p0 = p0.rotate_left(27);
+        *p0 = p0.wrapping_add(*p1);
+        *p1 = p1.rotate_left(19);
+    }
+
+    let mut p0 = MARVIN32_SEED as u32;
+    let mut p1 = (MARVIN32_SEED >> 32) as u32;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        p0 = p0.wrapping_add(u32::from_le_bytes(chunk.try_into().unwrap()));
+        block(&mut p0, &mut p1);
+    }
+
+    let remainder = chunks.remainder();
+    let mut last = 0x80u32 << (8 * remainder.len());
+    for (i, byte) in remainder.iter().enumerate() {
+        last |= u32::from(*byte) << (8 * i);
+    }
+    p0 = p0.wrapping_add(last);
+    block(&mut p0, &mut p1);
+    block(&mut p0, &mut p1);
+
+    (u64::from(p1) << 32) | u64::from(p0)
+}
+
+fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
@@ -0,0 +1,93 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{
+    add_boot_service, group_order_list_data, system_hive, HiveBuilder, TestSystemRoot,
+};
+
+/// Returns a hive with a stale "ControlSet001" and a current "ControlSet002" with different boot services.
+fn two_control_sets_hive() -> HiveBuilder {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Old", None, None);
+
+    hive.set_dword("Select", "Current", 2);
+    hive.set_multi_sz(
+        "ControlSet002\\Control\\ServiceGroupOrder",
+        "List",
+        &["Boot Bus Extender", "Boot File System"],
+    );
+    hive.set_binary(
+        "ControlSet002\\Control\\GroupOrderList",
+        "Boot Bus Extender",
+        &group_order_list_data(&[1]),
+    );
+
+    let ntfs = "ControlSet002\\Services\\Ntfs";
+    hive.set_dword(ntfs, "Start", 3);
+    hive.set_sz(ntfs, "Group", "Boot File System");
+    hive.set_sz(ntfs, "ImagePath", "System32\\drivers\\ntfs.sys");
+
+    let new = "ControlSet002\\Services\\New";
+    hive.set_dword(new, "Start", 0);
+    hive.set_sz(new, "ImagePath", "System32\\drivers\\new.sys");
+
+    hive
+}
+
+fn service_names(nt_load_order: NtLoadOrder) -> Vec<String> {
+    nt_load_order
+        .add_imports(false)
+        .add_kernel_binaries(false)
+        .get()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect()
+}
+
+#[test]
+fn test_current_control_set() {
+    let system_root = TestSystemRoot::new(&two_control_sets_hive());
+
+    let names = service_names(NtLoadOrder::new().system_root(Some(system_root.path())));
+    assert_eq!(names, ["ntfs", "Old"]);
+
+    let names = service_names(
+        NtLoadOrder::new()
+            .system_root(Some(system_root.path()))
+            .current_control_set(true),
+    );
+    assert_eq!(names, ["ntfs", "New"]);
+
+    let services = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .current_control_set(true)
+        .get_raw_services()
+        .unwrap();
+    assert!(services.iter().any(|service| service.name == "New"));
+    assert!(!services.iter().any(|service| service.name == "Old"));
+}
+
+#[test]
+fn test_current_control_set_without_current_value() {
+    let mut hive = system_hive();
+    hive.remove_key("Select");
+    hive.create_key("Select");
+    let system_root = TestSystemRoot::new(&hive);
+
+    let error = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .current_control_set(true)
+        .get()
+        .err()
+        .unwrap();
+
+    assert!(
+        format!("{error:#}").contains("Could not determine the current control set"),
+        "{error:#}"
+    );
+}
@@ -0,0 +1,46 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_dependencies() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Disk", None, None);
+    add_boot_service(&mut hive, "Volmgr", None, None);
+    hive.set_multi_sz(&service_key("Disk"), "DependOnService", &["Pci", "Volmgr"]);
+    hive.set_multi_sz(
+        &service_key("Disk"),
+        "DependOnGroup",
+        &["Boot Bus Extender"],
+    );
+
+    // Some services store a single dependency as REG_SZ.
+    hive.set_sz(&service_key("Volmgr"), "DependOnService", "Pci");
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let find = |name: &str| entries.iter().find(|entry| entry.name == name).unwrap();
+
+    let disk = find("Disk");
+    assert_eq!(disk.depend_on_service, ["Pci", "Volmgr"]);
+    assert_eq!(disk.depend_on_group, ["Boot Bus Extender"]);
+
+    let volmgr = find("Volmgr");
+    assert_eq!(volmgr.depend_on_service, ["Pci"]);
+    assert!(volmgr.depend_on_group.is_empty());
+
+    let pci = find("Pci");
+    assert!(pci.depend_on_service.is_empty());
+    assert!(pci.depend_on_group.is_empty());
+}
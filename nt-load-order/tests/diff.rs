@@ -0,0 +1,73 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{diff_load_orders, LoadOrderChange, NtLoadOrder, NtLoadOrderEntry};
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+fn get_entries(services: &[(&str, u32)]) -> Vec<NtLoadOrderEntry> {
+    let mut hive = system_hive();
+    for (name, tag) in services {
+        add_boot_service(&mut hive, name, Some("Boot Bus Extender"), Some(*tag));
+    }
+
+    let system_root = TestSystemRoot::new(&hive);
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap()
+}
+
+#[test]
+fn test_diff_load_orders() {
+    let old = get_entries(&[("A", 1), ("B", 2), ("C", 3)]);
+    let new = get_entries(&[("C", 1), ("a", 2), ("D", 3)]);
+
+    let diff = diff_load_orders(&old, &new)
+        .into_iter()
+        .map(|diff_entry| {
+            (
+                diff_entry.entry.name,
+                diff_entry.change,
+                diff_entry.old_index,
+                diff_entry.new_index,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        diff,
+        [
+            ("C".to_string(), LoadOrderChange::Moved, Some(2), Some(0)),
+            (
+                "a".to_string(),
+                LoadOrderChange::Unchanged,
+                Some(0),
+                Some(1)
+            ),
+            ("B".to_string(), LoadOrderChange::Removed, Some(1), None),
+            ("D".to_string(), LoadOrderChange::Added, None, Some(2)),
+            (
+                "ntfs".to_string(),
+                LoadOrderChange::Unchanged,
+                Some(3),
+                Some(3)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_diff_identical_load_orders() {
+    let entries = get_entries(&[("A", 1), ("B", 2)]);
+    let diff = diff_load_orders(&entries, &entries);
+
+    assert_eq!(diff.len(), entries.len());
+    assert!(diff
+        .iter()
+        .all(|diff_entry| diff_entry.change == LoadOrderChange::Unchanged));
+}
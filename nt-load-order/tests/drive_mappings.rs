@@ -0,0 +1,93 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::collections::HashMap;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntry};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{service_key, system_hive, TestSystemRoot};
+
+/// Returns the entries of a system root with an "Outside" boot driver at the given image path.
+///
+/// A separate directory, which comes with its own (unused) hive, is mapped to the given drive letter.
+/// It holds "Drivers\x.sys", which imports "pcw.sys" from the system root.
+fn entries(image_path: &str, mapped_drive: char) -> Vec<NtLoadOrderEntry> {
+    let mut hive = system_hive();
+    let key = service_key("Outside");
+    hive.set_dword(&key, "Start", 0);
+    hive.set_sz(&key, "Group", "Boot Bus Extender");
+    hive.set_sz(&key, "ImagePath", image_path);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+
+    let volume = TestSystemRoot::new(&system_hive());
+    volume.write_file(
+        "Drivers\\x.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .drive_mappings(HashMap::from([(mapped_drive, volume.path())]))
+        .get()
+        .unwrap()
+}
+
+fn names(entries: &[NtLoadOrderEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.name.as_str()).collect()
+}
+
+#[test]
+fn test_drive_mapping() {
+    let entries = entries("D:\\Drivers\\x.sys", 'd');
+    assert_eq!(
+        names(&entries),
+        ["ntoskrnl", "hal", "Outside", "pcw.sys", "ntfs"]
+    );
+
+    let outside = &entries[2];
+    assert_eq!(outside.reason, "Boot Driver via its \"Start\" value");
+    assert!(outside.signed_embedded.is_some());
+}
+
+#[test]
+fn test_unmapped_drive() {
+    let entries = entries("E:\\Drivers\\x.sys", 'D');
+    assert_eq!(names(&entries), ["ntoskrnl", "hal", "Outside", "ntfs"]);
+
+    // The entry is kept, but its imports can't be analyzed.
+    assert_eq!(
+        entries[2].reason,
+        "Boot Driver via its \"Start\" value, located outside of the system root"
+    );
+}
+
+#[test]
+fn test_rooted_path() {
+    let entries = entries("\\Drivers\\x.sys", 'D');
+    assert_eq!(names(&entries), ["ntoskrnl", "hal", "Outside", "ntfs"]);
+
+    // A rooted path refers to the boot volume, which is unknown for a target system.
+    assert_eq!(
+        entries[2].reason,
+        "Boot Driver via its \"Start\" value, located outside of the system root"
+    );
+}
@@ -0,0 +1,93 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntry};
+
+use crate::common::{add_boot_service, system_hive, HiveBuilder, TestSystemRoot};
+
+fn driver_verifier_hive(verify_drivers: Option<&str>) -> HiveBuilder {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "ACPI", Some("Core"), None);
+    add_boot_service(&mut hive, "pci", Some("Boot Bus Extender"), None);
+
+    if let Some(verify_drivers) = verify_drivers {
+        hive.set_sz(
+            &format!(
+                "{}\\Control\\Session Manager\\Memory Management",
+                common::CONTROL_SET
+            ),
+            "VerifyDrivers",
+            verify_drivers,
+        );
+    }
+
+    hive
+}
+
+fn get_entries(
+    verify_drivers: Option<&str>,
+    driver_verifier: Option<bool>,
+) -> Vec<NtLoadOrderEntry> {
+    let system_root = TestSystemRoot::new(&driver_verifier_hive(verify_drivers));
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .driver_verifier(driver_verifier)
+        .get()
+        .unwrap()
+}
+
+fn find<'a>(entries: &'a [NtLoadOrderEntry], name: &str) -> &'a NtLoadOrderEntry {
+    entries.iter().find(|entry| entry.name == name).unwrap()
+}
+
+#[test]
+fn test_driver_verifier_detected() {
+    let entries = get_entries(Some("pci.sys"), None);
+
+    // The Driver Verifier extension is loaded first as part of the "Core Driver Services".
+    assert_eq!(entries[0].name, "verifierext");
+    assert!(entries[0].moved_by_hardcoded_list.is_some());
+
+    assert!(find(&entries, "pci")
+        .reason
+        .ends_with(", verified by Driver Verifier"));
+    assert!(!find(&entries, "ACPI").reason.contains("Driver Verifier"));
+}
+
+#[test]
+fn test_driver_verifier_all_drivers() {
+    let entries = get_entries(Some("*"), None);
+
+    for name in ["ACPI", "pci", "ntfs"] {
+        assert!(
+            find(&entries, name)
+                .reason
+                .contains("verified by Driver Verifier"),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn test_driver_verifier_disabled() {
+    let entries = get_entries(None, None);
+    assert!(entries.iter().all(|entry| entry.name != "verifierext"));
+
+    // Forcing Driver Verifier off ignores the registry value.
+    let entries = get_entries(Some("pci.sys"), Some(false));
+    assert!(entries.iter().all(|entry| entry.name != "verifierext"));
+    assert!(!find(&entries, "pci").reason.contains("Driver Verifier"));
+}
+
+#[test]
+fn test_driver_verifier_forced() {
+    let entries = get_entries(None, Some(true));
+    assert_eq!(entries[0].name, "verifierext");
+    assert!(entries
+        .iter()
+        .all(|entry| !entry.reason.contains("verified by Driver Verifier")));
+}
@@ -0,0 +1,28 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_empty_image_path() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Wof", None, None);
+    hive.set_sz(&service_key("Wof"), "ImagePath", "");
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    // The image path is derived from the service name, like for a missing "ImagePath" value.
+    let wof = entries.iter().find(|entry| entry.name == "Wof").unwrap();
+    assert_eq!(wof.image_path, "System32\\Drivers\\Wof.sys");
+    assert!(wof.warnings.is_empty());
+}
@@ -0,0 +1,63 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntryKind};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_entry_kind() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .get()
+        .unwrap();
+    let kinds = entries
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.kind))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        kinds,
+        [
+            ("ntoskrnl", NtLoadOrderEntryKind::KernelBinary),
+            ("hal", NtLoadOrderEntryKind::KernelBinary),
+            ("Pci", NtLoadOrderEntryKind::Service),
+            ("pcw.sys", NtLoadOrderEntryKind::Import),
+            ("ntfs", NtLoadOrderEntryKind::BootFileSystem),
+        ]
+    );
+
+    #[allow(deprecated)]
+    let kernel_binary_count = entries
+        .iter()
+        .filter(|entry| entry.is_kernel_binary())
+        .count();
+    assert_eq!(kernel_binary_count, 2);
+}
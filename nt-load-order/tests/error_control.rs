@@ -0,0 +1,38 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_error_control() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "FltMgr", Some("Filter"), None);
+    hive.set_dword(&service_key("Pci"), "ErrorControl", 3);
+    hive.set_dword(&service_key("Ntfs"), "ErrorControl", 1);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let error_controls = entries
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.error_control, entry.critical))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        error_controls,
+        [
+            ("Pci", Some(3), true),
+            ("ntfs", Some(1), false),
+            ("FltMgr", None, false),
+        ]
+    );
+}
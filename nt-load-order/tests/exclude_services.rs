@@ -0,0 +1,83 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntryKind};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+fn exclude_services_system_root() -> TestSystemRoot {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "ACPI", Some("Core"), None);
+    add_boot_service(&mut hive, "pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "pcw", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "IntelPEP", Some("Core"), None);
+    add_boot_service(&mut hive, "AmdPEP", Some("Core"), None);
+
+    let system_root = TestSystemRoot::new(&hive);
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for name in ["acpi", "pcw", "intelpep", "amdpep", "ntfs"] {
+        system_root.write_file(
+            &format!("System32\\drivers\\{name}.sys"),
+            &PeBuilder::new().to_bytes(),
+        );
+    }
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+
+    system_root
+}
+
+#[test]
+fn test_exclude_services() {
+    let system_root = exclude_services_system_root();
+    let mut names = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .exclude_services(vec!["acpi".to_string(), "*Pep".to_string()])
+        .get()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect::<Vec<_>>();
+
+    names.sort();
+    assert_eq!(names, ["ntfs", "pci", "pcw"]);
+}
+
+#[test]
+fn test_exclude_imported_service() {
+    let system_root = exclude_services_system_root();
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .exclude_services(vec!["pcw".to_string()])
+        .get()
+        .unwrap();
+
+    // "pcw.sys" is still loaded, but only as an import of "pci.sys".
+    let pcw = entries
+        .iter()
+        .find(|entry| {
+            entry
+                .image_path
+                .eq_ignore_ascii_case("System32\\drivers\\pcw.sys")
+        })
+        .unwrap();
+    assert_eq!(pcw.kind, NtLoadOrderEntryKind::Import);
+    assert_eq!(
+        pcw.warnings,
+        ["Excluded service \"pcw\" is still loaded, because it is imported"]
+    );
+    assert!(entries.iter().all(|entry| entry.name != "pcw"));
+}
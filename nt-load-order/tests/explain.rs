@@ -0,0 +1,95 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+fn system_root() -> TestSystemRoot {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), Some(2));
+    add_boot_service(&mut hive, "Wdf01000", Some("Early-Launch"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+        "System32\\drivers\\pci.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\wdf01000.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+
+    system_root
+}
+
+#[test]
+fn test_explain() {
+    let system_root = system_root();
+
+    let explanation = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .explain("wdf01000")
+        .unwrap();
+
+    assert_eq!(explanation.len(), 6);
+    assert!(explanation[0].contains("group \"Early-Launch\" and no tag"));
+    assert!(explanation[2].contains("hardcoded \"Early-Launch\" group"));
+    assert!(explanation[3].contains("hardcoded \"Core Driver Services\" list"));
+    assert!(explanation[4].contains("position 3"));
+    assert!(explanation[5].contains("imports \"pcw.sys\""));
+}
+
+#[test]
+fn test_explain_unknown() {
+    let system_root = system_root();
+
+    let result = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .add_imports(false)
+        .explain("pcw.sys");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_explain_unresolved_import() {
+    let system_root = system_root();
+    system_root.write_file(
+        "System32\\drivers\\wdf01000.sys",
+        &PeBuilder::new()
+            .import("pcw.sys")
+            .import("missing.sys")
+            .to_bytes(),
+    );
+
+    let explanation = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .strict_imports(false)
+        .explain("wdf01000")
+        .unwrap();
+
+    assert!(
+        explanation
+            .last()
+            .unwrap()
+            .ends_with("imports \"pcw.sys\", \"missing.sys\""),
+        "{explanation:#?}"
+    );
+}
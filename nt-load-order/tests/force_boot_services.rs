@@ -0,0 +1,51 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntry};
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+fn get_entries(force_boot_services: &[&str]) -> anyhow::Result<Vec<NtLoadOrderEntry>> {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "pci", Some("Boot Bus Extender"), Some(2));
+
+    // A demand-start driver in the same group with a tag that sorts it before "pci".
+    let usbhub = service_key("usbhub");
+    hive.set_dword(&usbhub, "Start", 3);
+    hive.set_sz(&usbhub, "Group", "Boot Bus Extender");
+    hive.set_dword(&usbhub, "Tag", 1);
+    hive.set_sz(&usbhub, "ImagePath", "System32\\drivers\\usbhub.sys");
+
+    let system_root = TestSystemRoot::new(&hive);
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .force_boot_services(force_boot_services.iter().map(|s| s.to_string()).collect())
+        .get()
+}
+
+#[test]
+fn test_force_boot_services() {
+    let names = |entries: &[NtLoadOrderEntry]| {
+        entries
+            .iter()
+            .map(|entry| entry.name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(&get_entries(&[]).unwrap()), ["pci", "ntfs"]);
+
+    // The forced service is placed according to its group and tag.
+    let entries = get_entries(&["USBHUB"]).unwrap();
+    assert_eq!(names(&entries), ["usbhub", "pci", "ntfs"]);
+    assert!(entries[0].reason.contains("forced boot-start (simulation)"));
+}
+
+#[test]
+fn test_force_missing_boot_service() {
+    let error = get_entries(&["missing"]).unwrap_err();
+    assert!(format!("{error:#}").contains("\"missing\""), "{error:#}");
+}
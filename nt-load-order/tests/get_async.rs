@@ -0,0 +1,31 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[tokio::test]
+async fn test_get_async() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_imports(false);
+    let expected = nt_load_order.clone().get().unwrap();
+    let actual = nt_load_order.get_async().await.unwrap();
+
+    let image_paths = |entries: Vec<nt_load_order::NtLoadOrderEntry>| {
+        entries
+            .into_iter()
+            .map(|entry| entry.image_path)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(image_paths(actual), image_paths(expected));
+}
@@ -0,0 +1,45 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_get_many() {
+    let first_system_root = TestSystemRoot::new(&system_hive());
+
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Beep", None, None);
+    let second_system_root = TestSystemRoot::new(&hive);
+
+    let missing_system_root = format!("{}-missing", first_system_root.path());
+
+    let results = NtLoadOrder::new()
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get_many(vec![
+            first_system_root.path(),
+            missing_system_root.clone(),
+            second_system_root.path(),
+        ]);
+    assert_eq!(results.len(), 3);
+
+    // An error for one system root doesn't stop the analysis of the following ones.
+    let names = |index: usize| {
+        results[index]
+            .1
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(results[0].0, first_system_root.path());
+    assert_eq!(names(0), ["ntfs"]);
+    assert_eq!(results[1].0, missing_system_root);
+    assert!(results[1].1.is_err());
+    assert_eq!(names(2), ["ntfs", "Beep"]);
+}
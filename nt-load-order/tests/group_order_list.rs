@@ -0,0 +1,205 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{
+    add_boot_service, group_order_list_data, system_hive, TestSystemRoot, CONTROL_SET,
+};
+
+#[test]
+fn test_zero_count_group_order_list_entry() {
+    let mut hive = system_hive();
+    hive.set_multi_sz(
+        &format!("{CONTROL_SET}\\Control\\ServiceGroupOrder"),
+        "List",
+        &[
+            "Boot Bus Extender",
+            "Empty Group",
+            "System Bus Extender",
+            "Boot File System",
+        ],
+    );
+    hive.set_binary(
+        &format!("{CONTROL_SET}\\Control\\GroupOrderList"),
+        "Empty Group",
+        &group_order_list_data(&[]),
+    );
+
+    add_boot_service(&mut hive, "BusD", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "EmptyA", Some("Empty Group"), Some(2));
+    add_boot_service(&mut hive, "EmptyB", Some("Empty Group"), Some(1));
+    add_boot_service(&mut hive, "EmptyC", Some("Empty Group"), None);
+    add_boot_service(&mut hive, "SysE", Some("System Bus Extender"), Some(1));
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+
+    // The group keeps its ServiceGroupOrder position.
+    // Its tags are not in the (empty) GroupOrderList entry, so they are not used as indexes,
+    // and "EmptyA" with tag 2 is not moved behind "EmptyB" with tag 1.
+    assert_eq!(
+        names,
+        ["BusD", "EmptyA", "EmptyB", "EmptyC", "SysE", "ntfs"]
+    );
+}
+
+#[test]
+fn test_group_name_whitespace() {
+    let mut hive = system_hive();
+    hive.set_multi_sz(
+        &format!("{CONTROL_SET}\\Control\\ServiceGroupOrder"),
+        "List",
+        &[
+            "Boot Bus Extender ",
+            "System Bus Extender",
+            "Boot File System",
+        ],
+    );
+    hive.set_binary(
+        &format!("{CONTROL_SET}\\Control\\GroupOrderList"),
+        " System Bus Extender",
+        &group_order_list_data(&[2, 1]),
+    );
+
+    add_boot_service(&mut hive, "SysA", Some("System Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "SysB", Some("system bus extender "), Some(2));
+    add_boot_service(&mut hive, "BusC", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+
+    // All groups are matched despite the different whitespace and case, so the GroupOrderList entry applies
+    // and no service is left behind "ntfs".
+    assert_eq!(names, ["BusC", "SysB", "SysA", "ntfs"]);
+}
+
+#[test]
+fn test_truncated_group_order_list_entry() {
+    let mut hive = system_hive();
+
+    // The count promises 4 tags, but only 2 follow, plus a partial one.
+    let mut data = 4u32.to_le_bytes().to_vec();
+    data.extend(2u32.to_le_bytes());
+    data.extend(1u32.to_le_bytes());
+    data.extend([3, 0]);
+    hive.set_binary(
+        &format!("{CONTROL_SET}\\Control\\GroupOrderList"),
+        "Boot Bus Extender",
+        &data,
+    );
+
+    add_boot_service(&mut hive, "BusA", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "BusB", Some("Boot Bus Extender"), Some(2));
+    let system_root = TestSystemRoot::new(&hive);
+
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false);
+    let entries = nt_load_order.clone().get().unwrap();
+
+    // The available tags are still used.
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["BusB", "BusA", "ntfs"]);
+
+    let warning =
+        "\"GroupOrderList\" value \"Boot Bus Extender\" specifies 4 tags, but only contains 2";
+    assert_eq!(entries[0].warnings, [warning]);
+    assert_eq!(entries[1].warnings, [warning]);
+    assert!(entries[2].warnings.is_empty());
+
+    let error = nt_load_order.strict(true).get().unwrap_err();
+    assert_eq!(error.to_string(), warning);
+}
+
+#[test]
+fn test_misaligned_group_order_list_entries() {
+    let mut hive = system_hive();
+    let group_order_list = format!("{CONTROL_SET}\\Control\\GroupOrderList");
+
+    // A trailing partial tag after all counted tags, and a value too short to even hold the count.
+    let mut data = group_order_list_data(&[2, 1]);
+    data.extend([0xff, 0xff, 0xff]);
+    hive.set_binary(&group_order_list, "Boot Bus Extender", &data);
+    hive.set_binary(&group_order_list, "System Bus Extender", &[1, 0]);
+
+    add_boot_service(&mut hive, "BusA", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "BusB", Some("Boot Bus Extender"), Some(2));
+    add_boot_service(&mut hive, "SysC", Some("System Bus Extender"), Some(1));
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .strict(true)
+        .get()
+        .unwrap();
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+
+    // The partial tag is ignored, and the short value is treated as an empty tag list.
+    assert_eq!(names, ["BusB", "BusA", "SysC", "ntfs"]);
+    assert!(entries.iter().all(|entry| entry.warnings.is_empty()));
+}
+
+#[test]
+fn test_non_ascii_group_name() {
+    let mut hive = system_hive();
+    hive.set_multi_sz(
+        &format!("{CONTROL_SET}\\Control\\ServiceGroupOrder"),
+        "List",
+        &["Boot Bus Extender", "PÉRIPHÉRIQUES", "Boot File System"],
+    );
+    hive.set_binary(
+        &format!("{CONTROL_SET}\\Control\\GroupOrderList"),
+        "Périphériques",
+        &group_order_list_data(&[2, 1]),
+    );
+
+    add_boot_service(&mut hive, "Beep", None, None);
+    add_boot_service(&mut hive, "DevA", Some("périphériques"), Some(1));
+    add_boot_service(&mut hive, "DevB", Some("Périphériques"), Some(2));
+    add_boot_service(&mut hive, "BusA", Some("Boot Bus Extender"), Some(1));
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+
+    // The group names only differ in the case of non-ASCII characters and are still matched.
+    assert_eq!(names, ["BusA", "DevB", "DevA", "ntfs", "Beep"]);
+}
@@ -0,0 +1,66 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{GroupOrder, NtLoadOrder};
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+const OVERRIDE: &str = r"
+; Load the System Bus Extenders first.
+[ServiceGroupOrder]
+System Bus Extender
+Boot Bus Extender
+Boot File System
+
+[GroupOrderList\Boot Bus Extender]
+2
+1
+";
+
+#[test]
+fn test_group_order_override() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "A", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "B", Some("Boot Bus Extender"), Some(2));
+    add_boot_service(&mut hive, "C", Some("System Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let group_order = GroupOrder::parse(OVERRIDE).unwrap();
+    assert_eq!(group_order.group_order_lists["Boot Bus Extender"], [2, 1]);
+
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false);
+    let names = |nt_load_order: NtLoadOrder| {
+        nt_load_order
+            .get()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(nt_load_order.clone()), ["A", "B", "C", "ntfs"]);
+
+    let nt_load_order = nt_load_order.group_order_override(Some(group_order));
+    assert_eq!(names(nt_load_order.clone()), ["C", "B", "A", "ntfs"]);
+
+    // The registry info is not affected by the override.
+    let registry_info = nt_load_order.get_registry_info().unwrap();
+    assert_eq!(registry_info.service_group_order[0], "Early-Launch");
+}
+
+#[test]
+fn test_invalid_group_order() {
+    for (text, message) in [
+        ("Boot Bus Extender", "Line 1 is outside of any section"),
+        ("[Foo]", "Unknown section \"Foo\" in line 1"),
+        ("[GroupOrderList\\Core]\nx", "Invalid tag \"x\" in line 2"),
+    ] {
+        let error = GroupOrder::parse(text).err().unwrap();
+        assert_eq!(error.to_string(), message);
+    }
+}
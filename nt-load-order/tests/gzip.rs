@@ -0,0 +1,68 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#![cfg(feature = "gzip")]
+
+mod common;
+
+use std::io::Write;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_gzip_compressed_hive() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&hive.to_bytes()).unwrap();
+    let compressed_hive_data = encoder.finish().unwrap();
+
+    let system_root = TestSystemRoot::new(&hive);
+    system_root.write_file("System32\\config\\SYSTEM", &compressed_hive_data);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Pci", "ntfs"]);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_gzip_compressed_memory_mapped_hive() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&hive.to_bytes()).unwrap();
+    let compressed_hive_data = encoder.finish().unwrap();
+
+    let system_root = TestSystemRoot::new(&hive);
+    system_root.write_file("System32\\config\\SYSTEM", &compressed_hive_data);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .memory_map_hive(true)
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Pci", "ntfs"]);
+}
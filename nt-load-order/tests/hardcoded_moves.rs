@@ -0,0 +1,80 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::hardcoded::HARDCODED_GROUPS;
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_moved_by_hardcoded_group_and_list() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Acpi", Some("Core"), None);
+    add_boot_service(&mut hive, "WdBoot", Some("Early-Launch"), None);
+    add_boot_service(&mut hive, "Wdf01000", Some("Early-Launch"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let moves = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.name.as_str(),
+                entry.moved_by_hardcoded_group.as_deref(),
+                entry.moved_by_hardcoded_list.as_deref(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        moves,
+        [
+            (
+                "Wdf01000",
+                Some("Early-Launch"),
+                Some("Core Driver Services")
+            ),
+            ("Acpi", None, Some("TPM Core Driver Services")),
+            ("WdBoot", Some("Early-Launch"), None),
+            ("Pci", None, None),
+            ("ntfs", None, None),
+        ]
+    );
+}
+
+#[test]
+fn test_hardcoded_groups_order() {
+    // Add the services in reverse order of the hardcoded groups.
+    let mut hive = system_hive();
+    for (index, group_name) in HARDCODED_GROUPS.iter().enumerate().rev() {
+        add_boot_service(
+            &mut hive,
+            &format!("Service{index}"),
+            Some(group_name),
+            None,
+        );
+    }
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .sort_by_tag_and_group(false)
+        .get()
+        .unwrap();
+    let groups = entries
+        .iter()
+        .filter_map(|entry| entry.moved_by_hardcoded_group.as_deref())
+        .collect::<Vec<_>>();
+
+    assert_eq!(groups, HARDCODED_GROUPS);
+}
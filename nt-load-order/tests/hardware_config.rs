@@ -0,0 +1,51 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_hardware_configs() {
+    let mut hive = system_hive();
+    hive.set_dword(
+        "HardwareConfig\\{6a2d7b2c-1f3e-4d5a-9b8c-0e1f2a3b4c5d}",
+        "Id",
+        1,
+    );
+    hive.set_dword(
+        "HardwareConfig\\{f0e1d2c3-b4a5-4968-8776-655443322110}",
+        "Id",
+        0,
+    );
+
+    // "Pci" is only a boot driver in hardware configuration 1.
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    hive.set_dword(&service_key("Pci"), "Start", 3);
+    hive.set_dword(&format!("{}\\StartOverride", service_key("Pci")), "1", 0);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false);
+    assert_eq!(nt_load_order.list_hardware_configs().unwrap(), [0, 1]);
+
+    let names = |hardware_config_id| {
+        nt_load_order
+            .clone()
+            .hardware_config_id(hardware_config_id)
+            .get()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect::<Vec<_>>()
+    };
+
+    // "HardwareConfig\LastId" is 0.
+    assert_eq!(names(None), ["ntfs"]);
+    assert_eq!(names(Some(0)), ["ntfs"]);
+    assert_eq!(names(Some(1)), ["Pci", "ntfs"]);
+}
@@ -0,0 +1,80 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::HiveBuilder;
+use crate::common::TestSystemRoot;
+
+#[test]
+fn test_import_closure() {
+    // No SYSTEM hive is needed, because the registry is not analyzed.
+    let system_root = TestSystemRoot::new(&HiveBuilder::new());
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-wmi-l1-1-0", "wmilib.sys")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\acpi.sys",
+        &PeBuilder::new()
+            .import("api-ms-win-core-wmi-l1-1-0.dll")
+            .import("api-ms-win-core-missing-l1-1-0.dll")
+            .import("pcw.sys")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\pcw.sys",
+        &PeBuilder::new().import("msrpc.sys").to_bytes(),
+    );
+    // A circular import back to the analyzed image.
+    system_root.write_file(
+        "System32\\drivers\\msrpc.sys",
+        &PeBuilder::new().import("acpi.sys").to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\wmilib.sys",
+        &PeBuilder::new().to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .import_closure("System32\\drivers\\acpi.sys")
+        .unwrap();
+    let image_paths = entries
+        .iter()
+        .map(|entry| entry.image_path.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        image_paths,
+        [
+            "System32\\drivers\\wmilib.sys",
+            "System32\\drivers\\msrpc.sys",
+            "System32\\drivers\\pcw.sys",
+        ]
+    );
+}
+
+#[test]
+fn test_import_closure_missing_image() {
+    let system_root = TestSystemRoot::new(&HiveBuilder::new());
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-wmi-l1-1-0", "wmilib.sys")
+            .to_bytes(),
+    );
+
+    let error = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .import_closure("System32\\drivers\\missing.sys")
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(error.contains("missing.sys"), "{error}");
+}
@@ -0,0 +1,42 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_import_file_plan() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Pci2", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Beep", None, None);
+    // Both services refer to the same file, which is only opened once.
+    hive.set_sz(
+        &service_key("Pci2"),
+        "ImagePath",
+        "System32\\DRIVERS\\pci.sys",
+    );
+    hive.set_sz(&service_key("Beep"), "ImagePath", "D:\\Drivers\\beep.sys");
+
+    // No image file needs to exist for the plan.
+    let system_root = TestSystemRoot::new(&hive);
+
+    let plan = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .import_file_plan()
+        .unwrap();
+    assert_eq!(
+        plan,
+        [
+            "System32\\apisetschema.dll",
+            "System32\\ntoskrnl.exe",
+            "System32\\hal.dll",
+            "System32\\DRIVERS\\pci.sys",
+            "System32\\drivers\\ntfs.sys",
+        ]
+    );
+}
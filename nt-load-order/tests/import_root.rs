@@ -0,0 +1,51 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_import_root() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    // The image files are only present in a separate directory, which comes with its own (unused) hive.
+    let import_root = TestSystemRoot::new(&system_hive());
+    import_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        import_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    import_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None);
+    assert!(nt_load_order.clone().get().is_err());
+
+    let names = nt_load_order
+        .import_root(Some(import_root.path()))
+        .get()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["ntoskrnl", "hal", "Pci", "pcw.sys", "ntfs"]);
+}
@@ -0,0 +1,74 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_import_roots() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    // The base layer has all files of the original installation.
+    let base = TestSystemRoot::new(&system_hive());
+    base.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pci.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        base.write_file(path, &PeBuilder::new().to_bytes());
+    }
+
+    // The overlay layer only has a serviced "pci.sys" with an additional import found in the base layer.
+    let overlay = TestSystemRoot::new(&system_hive());
+    overlay.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+
+    let names = |import_roots: Vec<String>| {
+        NtLoadOrder::new()
+            .system_root(Some(system_root.path()))
+            .import_roots(import_roots)
+            .kd_driver(None)
+            .get()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        names(vec![overlay.path(), base.path()]),
+        ["ntoskrnl", "hal", "Pci", "pcw.sys", "ntfs"]
+    );
+
+    // With the layers swapped, the original "pci.sys" without imports wins.
+    assert_eq!(
+        names(vec![base.path(), overlay.path()]),
+        ["ntoskrnl", "hal", "Pci", "ntfs"]
+    );
+}
+
+#[test]
+fn test_import_roots_with_import_root() {
+    let error = NtLoadOrder::new()
+        .import_root(Some("/mnt/c/Windows".to_string()))
+        .import_roots(vec!["/mnt/overlay/Windows".to_string()])
+        .validate()
+        .unwrap_err();
+    assert!(error.to_string().contains("cannot be combined"), "{error}");
+}
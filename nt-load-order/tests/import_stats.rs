@@ -0,0 +1,55 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_import_stats() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None);
+    let (entries, import_stats) = nt_load_order.clone().get_with_import_stats().unwrap();
+    assert_eq!(entries, nt_load_order.get().unwrap());
+
+    // Every image file is opened exactly once.
+    let image_paths = import_stats
+        .files
+        .iter()
+        .map(|file| file.image_path.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    assert_eq!(import_stats.total_files(), entries.len(), "{image_paths:?}");
+    assert!(image_paths.contains(&"system32\\ntoskrnl.exe".to_string()));
+    assert!(image_paths.contains(&"system32\\drivers\\pcw.sys".to_string()));
+
+    let expected_bytes = PeBuilder::new().to_bytes().len() as u64 * 4
+        + PeBuilder::new().import("pcw.sys").to_bytes().len() as u64;
+    assert_eq!(import_stats.total_bytes(), expected_bytes);
+}
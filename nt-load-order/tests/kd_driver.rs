@@ -0,0 +1,92 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{system_hive, HiveBuilder, TestSystemRoot};
+
+#[test]
+fn test_missing_kd_driver() {
+    let system_root = TestSystemRoot::new(&system_hive());
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+
+    // "kdnet.dll" is a valid KD driver name, but doesn't exist in this system root.
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(Some("kdnet".to_string()));
+
+    let error = nt_load_order.clone().get().err().unwrap().to_string();
+    assert!(error.contains("\"kdnet\""), "{error}");
+    assert!(error.contains("kdnet.dll"), "{error}");
+
+    // The file is not needed without analyzing imports.
+    let entries = nt_load_order.add_imports(false).get().unwrap();
+    assert!(entries.iter().any(|entry| entry.name == "kdnet"));
+}
+
+fn kd_driver_names(hive: &HiveBuilder, nt_load_order: NtLoadOrder) -> Vec<String> {
+    let system_root = TestSystemRoot::new(hive);
+    nt_load_order
+        .system_root(Some(system_root.path()))
+        .add_imports(false)
+        .get()
+        .unwrap()
+        .into_iter()
+        .filter(|entry| entry.name.starts_with("kd"))
+        .map(|entry| entry.name)
+        .collect()
+}
+
+#[test]
+fn test_detect_kd_driver() {
+    let mut hive = system_hive();
+    hive.set_sz(
+        &format!("{}\\Control", common::CONTROL_SET),
+        "SystemStartOptions",
+        " NOEXECUTE=OPTIN  DEBUG  DEBUGPORT=NET  HOST_IP=192.168.0.1",
+    );
+
+    assert_eq!(kd_driver_names(&hive, NtLoadOrder::new()), ["kdnet"]);
+
+    // An explicitly set KD driver overrides the detection.
+    assert_eq!(
+        kd_driver_names(
+            &hive,
+            NtLoadOrder::new().kd_driver(Some("kdcom".to_string()))
+        ),
+        ["kdcom"]
+    );
+    assert!(kd_driver_names(&hive, NtLoadOrder::new().kd_driver(None)).is_empty());
+}
+
+#[test]
+fn test_detect_kd_driver_fallback() {
+    // Without any debug port, "kdcom" is used.
+    assert_eq!(
+        kd_driver_names(&system_hive(), NtLoadOrder::new()),
+        ["kdcom"]
+    );
+
+    let mut hive = system_hive();
+    hive.set_sz(
+        &format!("{}\\Control", common::CONTROL_SET),
+        "SystemStartOptions",
+        " DEBUG DEBUGPORT=COM1 BAUDRATE=115200",
+    );
+    assert_eq!(kd_driver_names(&hive, NtLoadOrder::new()), ["kdcom"]);
+}
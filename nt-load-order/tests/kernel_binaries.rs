@@ -0,0 +1,61 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntryKind};
+
+fn image_paths(nt_load_order: &NtLoadOrder) -> Vec<String> {
+    nt_load_order
+        .kernel_binaries()
+        .into_iter()
+        .map(|entry| entry.image_path)
+        .collect()
+}
+
+#[test]
+fn test_kernel_binaries() {
+    // No system root is needed, because the registry is not touched.
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some("/nonexistent".to_string()))
+        .kd_driver(Some("kdnet".to_string()))
+        .cpu_vendor(Some("AuthenticAMD".to_string()));
+
+    assert_eq!(
+        image_paths(&nt_load_order),
+        [
+            "System32\\ntoskrnl.exe",
+            "System32\\hal.dll",
+            "System32\\kdnet.dll",
+            "System32\\mcupdate_AuthenticAMD.dll",
+        ]
+    );
+    assert!(nt_load_order
+        .kernel_binaries()
+        .iter()
+        .all(|entry| entry.kind == NtLoadOrderEntryKind::KernelBinary));
+}
+
+#[test]
+fn test_kernel_binaries_without_kd_driver_and_cpu_vendor() {
+    assert_eq!(
+        image_paths(&NtLoadOrder::new()),
+        ["System32\\ntoskrnl.exe", "System32\\hal.dll"]
+    );
+}
+
+#[test]
+fn test_kernel_binaries_with_multiple_kd_drivers() {
+    let nt_load_order = NtLoadOrder::new()
+        .kd_drivers(vec!["kdcom".to_string(), "kdnet".to_string()])
+        .cpu_vendor(Some("GenuineIntel".to_string()));
+
+    assert_eq!(
+        image_paths(&nt_load_order),
+        [
+            "System32\\ntoskrnl.exe",
+            "System32\\hal.dll",
+            "System32\\kdcom.dll",
+            "System32\\kdnet.dll",
+            "System32\\mcupdate_GenuineIntel.dll",
+        ]
+    );
+}
@@ -0,0 +1,79 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+fn get_names(expand_kernel_binary_imports: bool, kernel_binary_imports_first: bool) -> Vec<String> {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Pcw", Some("Filter"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\hal.dll",
+        "System32\\pshed.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\ntoskrnl.exe",
+        &PeBuilder::new()
+            .import("pshed.dll")
+            .import("pcw.sys")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("pshed.dll").to_bytes(),
+    );
+
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .expand_kernel_binary_imports(expand_kernel_binary_imports)
+        .kernel_binary_imports_first(kernel_binary_imports_first)
+        .get()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect()
+}
+
+#[test]
+fn test_kernel_binary_imports_first() {
+    assert_eq!(
+        get_names(true, true),
+        ["ntoskrnl", "hal", "pshed.dll", "Pcw", "Pci", "ntfs"]
+    );
+}
+
+#[test]
+fn test_kernel_binary_imports_last() {
+    // The kernel binaries stay in front, but their imports are only added if no service has loaded them before.
+    assert_eq!(
+        get_names(true, false),
+        ["ntoskrnl", "hal", "Pci", "pshed.dll", "ntfs", "Pcw"]
+    );
+}
+
+#[test]
+fn test_kernel_binary_imports_not_expanded() {
+    // "pshed.dll" is still added as an import of "Pci", and "Pcw" stays at its own position.
+    assert_eq!(
+        get_names(false, true),
+        ["ntoskrnl", "hal", "Pci", "pshed.dll", "ntfs", "Pcw"]
+    );
+}
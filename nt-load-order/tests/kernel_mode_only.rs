@@ -0,0 +1,73 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+const IMAGE_SUBSYSTEM_WINDOWS_GUI: u16 = 2;
+
+#[test]
+fn test_kernel_mode_only() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    system_root.write_file("System32\\drivers\\ntfs.sys", &PeBuilder::new().to_bytes());
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new()
+            .import("helper.dll")
+            .import("user.dll")
+            .to_bytes(),
+    );
+    system_root.write_file("System32\\helper.dll", &PeBuilder::new().to_bytes());
+    system_root.write_file(
+        "System32\\user.dll",
+        &PeBuilder::new()
+            .subsystem(IMAGE_SUBSYSTEM_WINDOWS_GUI)
+            .to_bytes(),
+    );
+
+    let get_names = |kernel_mode_only| {
+        NtLoadOrder::new()
+            .system_root(Some(system_root.path()))
+            .add_kernel_binaries(false)
+            .kernel_mode_only(kernel_mode_only)
+            .get()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect::<Vec<_>>()
+    };
+
+    let names = get_names(false);
+    assert!(names.contains(&"user.dll".to_string()), "{names:?}");
+
+    let names = get_names(true);
+    assert!(!names.contains(&"user.dll".to_string()), "{names:?}");
+    assert!(names.contains(&"helper.dll".to_string()), "{names:?}");
+}
+
+#[test]
+fn test_kernel_mode_only_with_boot_execute() {
+    let error = NtLoadOrder::new()
+        .kernel_mode_only(true)
+        .include_boot_execute(true)
+        .validate()
+        .unwrap_err();
+
+    assert!(
+        error.to_string().contains("Boot Execute applications"),
+        "{error}"
+    );
+}
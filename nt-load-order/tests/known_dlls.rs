@@ -0,0 +1,55 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot, CONTROL_SET};
+
+/// KnownDlls only affect user-mode processes, so they must not change how imports of boot drivers are resolved.
+#[test]
+fn test_known_dlls_are_ignored() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    hive.set_sz(
+        &format!("{CONTROL_SET}\\Control\\Session Manager\\KnownDlls"),
+        "msrpc",
+        "msrpc.dll",
+    );
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\msrpc.dll",
+        "System32\\drivers\\msrpc.dll",
+        "System32\\drivers\\ntfs.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("msrpc.dll").to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .get()
+        .unwrap();
+
+    // The bootloader looks in "System32\drivers" first, even for a KnownDll.
+    let msrpc = entries
+        .iter()
+        .find(|entry| entry.name == "msrpc.dll")
+        .unwrap();
+    assert_eq!(msrpc.image_path, "System32\\drivers\\msrpc.dll");
+}
@@ -0,0 +1,179 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Compares the load order determined by nt-load-order against the expected load orders in `tests/fixtures`.
+//! See `tests/fixtures/README.md` for the fixture format.
+//!
+//! The only case so far is synthetic, so this is a regression snapshot of the current algorithm.
+//! It doesn't prove that the load order matches the one of a real Windows boot.
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, service_key, system_hive};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+}
+
+/// Returns the lines of a fixture file, skipping empty lines and comments.
+fn read_lines(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Could not read \"{}\": {e}", path.display()))
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+fn run_fixture(fixture_dir: &Path) -> Vec<String> {
+    let system_root = fixture_dir.to_str().unwrap().to_string();
+    let mut nt_load_order = NtLoadOrder::new().system_root(Some(system_root));
+
+    let options_path = fixture_dir.join("options.txt");
+    if options_path.exists() {
+        for line in read_lines(&options_path) {
+            let (key, value) = line
+                .split_once('=')
+                .unwrap_or_else(|| panic!("Invalid option line \"{line}\""));
+            let value = value.trim().to_string();
+
+            nt_load_order = match key.trim() {
+                "kd_driver" => nt_load_order.kd_driver(Some(value)),
+                "cpu_vendor" => nt_load_order.cpu_vendor(Some(value)),
+                key => panic!("Unknown option \"{key}\""),
+            };
+        }
+    }
+
+    nt_load_order
+        .get()
+        .unwrap_or_else(|e| panic!("NtLoadOrder::get failed: {e:?}"))
+        .into_iter()
+        .map(|entry| entry.image_path)
+        .collect()
+}
+
+#[test]
+fn test_load_order_fixtures() {
+    let mut fixture_dirs = fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    fixture_dirs.sort();
+    assert!(!fixture_dirs.is_empty());
+
+    for fixture_dir in fixture_dirs {
+        let name = fixture_dir.file_name().unwrap().to_string_lossy();
+        let expected = read_lines(&fixture_dir.join("expected.txt"));
+        let actual = run_fixture(&fixture_dir);
+
+        assert_eq!(
+            actual, expected,
+            "Load order of fixture \"{name}\" does not match"
+        );
+    }
+}
+
+/// Regenerates the synthetic "basic" fixture.
+/// Its "expected.txt" has been derived by hand and is not touched.
+#[test]
+#[ignore = "only run this to regenerate the committed fixture files"]
+fn generate_basic_fixture() {
+    let fixture_dir = fixtures_dir().join("basic");
+    let write_file = |relative_path: &str, data: &[u8]| {
+        let mut path = fixture_dir.clone();
+        path.extend(relative_path.split('\\'));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, data).unwrap();
+    };
+
+    let mut hive = system_hive();
+    hive.set_binary(
+        &format!("{}\\Control\\GroupOrderList", common::CONTROL_SET),
+        "Boot Bus Extender",
+        &common::group_order_list_data(&[3, 1]),
+    );
+    add_boot_service(&mut hive, "ACPI", Some("Core"), Some(2));
+    add_boot_service(&mut hive, "Beep", None, None);
+    add_boot_service(&mut hive, "isapnp", Some("Boot Bus Extender"), Some(3));
+    add_boot_service(&mut hive, "pci", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "vdrvroot", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "volmgr", Some("System Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "WdBoot", Some("Early-Launch"), None);
+    add_boot_service(&mut hive, "Wdf01000", Some("WdfLoadGroup"), None);
+
+    // A system-start driver, which must not show up.
+    add_boot_service(&mut hive, "disk", Some("SCSI miniport"), None);
+    hive.set_dword(&service_key("disk"), "Start", 1);
+
+    write_file("System32\\config\\SYSTEM", &hive.to_bytes());
+
+    write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-wmi-l1-1-0", "wmilib.sys")
+            .to_bytes(),
+    );
+    write_file(
+        "System32\\ntoskrnl.exe",
+        &PeBuilder::new()
+            .import("PSHED.dll")
+            .import("BOOTVID.dll")
+            .to_bytes(),
+    );
+    write_file(
+        "System32\\hal.dll",
+        &PeBuilder::new().import("ntoskrnl.exe").to_bytes(),
+    );
+    write_file(
+        "System32\\drivers\\acpi.sys",
+        &PeBuilder::new()
+            .import("api-ms-win-core-wmi-l1-1-0.dll")
+            .to_bytes(),
+    );
+    write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("hal.dll").to_bytes(),
+    );
+    write_file(
+        "System32\\drivers\\volmgr.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+    write_file(
+        "System32\\drivers\\ntfs.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+    write_file(
+        "System32\\drivers\\wdf01000.sys",
+        &PeBuilder::new()
+            .import("api-ms-win-core-missing-l1-1-0.dll")
+            .import("WppRecorder.sys")
+            .to_bytes(),
+    );
+
+    for path in [
+        "System32\\kdcom.dll",
+        "System32\\mcupdate_GenuineIntel.dll",
+        "System32\\PSHED.dll",
+        "System32\\BOOTVID.dll",
+        "System32\\drivers\\beep.sys",
+        "System32\\drivers\\isapnp.sys",
+        "System32\\drivers\\pcw.sys",
+        "System32\\drivers\\vdrvroot.sys",
+        "System32\\drivers\\wdboot.sys",
+        "System32\\drivers\\wmilib.sys",
+        "System32\\drivers\\WppRecorder.sys",
+    ] {
+        write_file(path, &PeBuilder::new().to_bytes());
+    }
+}
@@ -0,0 +1,31 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Checks that analyzing the local system fails with a clear message on non-Windows platforms.
+
+#![cfg(not(target_os = "windows"))]
+
+use nt_load_order::NtLoadOrder;
+
+fn assert_local_unsupported(error: anyhow::Error) {
+    let message = format!("{error:#}");
+    assert!(message.contains("only supported on Windows"), "{message}");
+}
+
+#[test]
+fn test_local_get() {
+    assert_local_unsupported(NtLoadOrder::new().get().err().unwrap());
+}
+
+#[test]
+fn test_local_imports() {
+    let nt_load_order = NtLoadOrder::new();
+
+    assert_local_unsupported(
+        nt_load_order
+            .import_closure("System32\\drivers\\acpi.sys")
+            .err()
+            .unwrap(),
+    );
+    assert_local_unsupported(nt_load_order.apiset_schema_info().err().unwrap());
+}
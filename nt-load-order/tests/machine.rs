@@ -0,0 +1,111 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_machine() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\kdcom.dll",
+        "System32\\drivers\\ntfs.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+
+    // A mismatched ARM64 driver among x64 ones.
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().machine(0xaa64).to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .get()
+        .unwrap();
+    let machines = entries
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.machine, entry.machine_name()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        machines,
+        [
+            ("ntoskrnl", Some(0x8664), Some("x64")),
+            ("hal", Some(0x8664), Some("x64")),
+            ("kdcom", Some(0x8664), Some("x64")),
+            ("Pci", Some(0xaa64), Some("ARM64")),
+            ("ntfs", Some(0x8664), Some("x64")),
+        ]
+    );
+}
+
+#[test]
+fn test_machine_arm64x() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\kdcom.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().machine(0xaa64).to_bytes());
+    }
+
+    // The imports of the native view of an ARM64X driver must be found.
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new()
+            .machine(0xaa64)
+            .chpe_metadata()
+            .import("pcw.sys")
+            .to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .get()
+        .unwrap();
+    let machines = entries
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.machine_name()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        machines,
+        [
+            ("ntoskrnl", Some("ARM64")),
+            ("hal", Some("ARM64")),
+            ("kdcom", Some("ARM64")),
+            ("Pci", Some("ARM64X")),
+            ("pcw.sys", Some("ARM64")),
+            ("ntfs", Some("ARM64")),
+        ]
+    );
+}
@@ -0,0 +1,62 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+const REG_SZ: u32 = 1;
+
+fn utf16_bytes(code_units: &[u16]) -> Vec<u8> {
+    code_units
+        .iter()
+        .flat_map(|code_unit| code_unit.to_le_bytes())
+        .collect()
+}
+
+#[test]
+fn test_malformed_sz_values() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", None, None);
+    add_boot_service(&mut hive, "Beep", None, None);
+
+    // "System32\drivers\pci.sys", followed by an embedded NUL and garbage.
+    let mut image_path = "System32\\drivers\\pci.sys"
+        .encode_utf16()
+        .collect::<Vec<_>>();
+    image_path.extend([0, u16::from(b'x'), 0]);
+    hive.set_value(
+        &service_key("Pci"),
+        "ImagePath",
+        REG_SZ,
+        utf16_bytes(&image_path),
+    );
+
+    // A group name with an unpaired surrogate.
+    let mut group = "Boot Bus Extender".encode_utf16().collect::<Vec<_>>();
+    group.push(0xd800);
+    hive.set_value(&service_key("Beep"), "Group", REG_SZ, utf16_bytes(&group));
+
+    let system_root = TestSystemRoot::new(&hive);
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    let pci = entries.iter().find(|entry| entry.name == "Pci").unwrap();
+    assert_eq!(pci.image_path, "System32\\drivers\\pci.sys");
+    assert_eq!(pci.warnings.len(), 1);
+    assert!(pci.warnings[0].contains("\"ImagePath\" contains an embedded NUL"));
+
+    let beep = entries.iter().find(|entry| entry.name == "Beep").unwrap();
+    assert_eq!(beep.warnings.len(), 1);
+    assert!(beep.warnings[0].contains("\"Group\" is not valid UTF-16"));
+
+    // A regular NUL terminator is no reason for a warning.
+    let ntfs = entries.iter().find(|entry| entry.name == "ntfs").unwrap();
+    assert!(ntfs.warnings.is_empty());
+}
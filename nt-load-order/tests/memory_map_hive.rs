@@ -0,0 +1,34 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#![cfg(feature = "mmap")]
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_memory_map_hive() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Fltmgr", Some("Filter"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let names = |memory_map_hive| {
+        NtLoadOrder::new()
+            .system_root(Some(system_root.path()))
+            .memory_map_hive(memory_map_hive)
+            .add_kernel_binaries(false)
+            .add_imports(false)
+            .get()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(true), ["Pci", "ntfs", "Fltmgr"]);
+    assert_eq!(names(true), names(false));
+}
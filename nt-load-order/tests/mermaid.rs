@@ -0,0 +1,70 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{to_mermaid, NtLoadOrder};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_to_mermaid() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), Some(1));
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\pcw.sys",
+        &PeBuilder::new().import("cng.sys").to_bytes(),
+    );
+    system_root.write_file("System32\\drivers\\cng.sys", &PeBuilder::new().to_bytes());
+    system_root.write_file("System32\\drivers\\ntfs.sys", &PeBuilder::new().to_bytes());
+
+    let tree = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .get_tree()
+        .unwrap();
+
+    assert_eq!(
+        to_mermaid(&tree),
+        "graph TD\n    \
+         n0[\"Pci\"]\n    \
+         n1[\"pcw.sys\"]\n    \
+         n2[\"cng.sys\"]\n    \
+         n3[\"ntfs\"]\n    \
+         n0 --> n1\n    \
+         n1 --> n2\n"
+    );
+}
+
+#[test]
+fn test_to_mermaid_escapes_labels() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Odd\"#<Name>", None, None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let tree = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get_tree()
+        .unwrap();
+
+    assert!(
+        to_mermaid(&tree).contains("[\"Odd#quot;#35;#lt;Name#gt;\"]"),
+        "{}",
+        to_mermaid(&tree)
+    );
+}
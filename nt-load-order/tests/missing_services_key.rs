@@ -0,0 +1,26 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{system_hive, TestSystemRoot, CONTROL_SET};
+
+#[test]
+fn test_missing_services_key() {
+    let mut hive = system_hive();
+    hive.remove_key(&format!("{CONTROL_SET}\\Services"));
+    let system_root = TestSystemRoot::new(&hive);
+
+    let error = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .get()
+        .err()
+        .unwrap();
+
+    assert!(
+        format!("{error:#}").contains("Control set \"ControlSet001\" has no \"Services\" subkey"),
+        "{error:#}"
+    );
+}
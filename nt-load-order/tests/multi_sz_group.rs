@@ -0,0 +1,71 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_multi_sz_group() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Fltmgr", Some("Filter"), None);
+    add_boot_service(&mut hive, "Pci", None, None);
+    hive.set_multi_sz(&service_key("Pci"), "Group", &["Boot Bus Extender"]);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    // "Pci" is sorted into "Boot Bus Extender" like with a REG_SZ value.
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Pci", "ntfs", "Fltmgr"]);
+
+    let pci = &entries[0];
+    assert_eq!(
+        pci.group.as_ref().unwrap().display_name,
+        "Boot Bus Extender"
+    );
+    assert_eq!(pci.warnings.len(), 1);
+    assert!(pci.warnings[0].contains("REG_MULTI_SZ"));
+    assert!(entries[1..].iter().all(|entry| entry.warnings.is_empty()));
+}
+
+#[test]
+fn test_comma_separated_group() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Fltmgr", Some("Filter"), None);
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender, Filter"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    // The whole value is a single group, which is not in the ServiceGroupOrder.
+    // So "Pci" is sorted behind all listed groups.
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["ntfs", "Fltmgr", "Pci"]);
+
+    let pci = &entries[2];
+    assert_eq!(
+        pci.group.as_ref().unwrap().display_name,
+        "Boot Bus Extender, Filter"
+    );
+    assert_eq!(pci.warnings.len(), 1);
+    assert!(pci.warnings[0].contains("contains a comma"));
+}
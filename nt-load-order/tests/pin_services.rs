@@ -0,0 +1,96 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntry, NtLoadOrderEntryKind};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, HiveBuilder, TestSystemRoot};
+
+fn pin_hive() -> HiveBuilder {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "Beep", None, None);
+    hive
+}
+
+fn names(entries: &[NtLoadOrderEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.name.as_str()).collect()
+}
+
+#[test]
+fn test_pin_services_after_kernel_binaries() {
+    let system_root = TestSystemRoot::new(&pin_hive());
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_imports(false)
+        .kd_drivers(Vec::new())
+        .pin_services(vec!["beep".to_string(), "NTFS".to_string()])
+        .get()
+        .unwrap();
+
+    let first_service = entries
+        .iter()
+        .position(|entry| entry.kind != NtLoadOrderEntryKind::KernelBinary)
+        .unwrap();
+    assert_eq!(
+        names(&entries[first_service..]),
+        ["Beep", "ntfs", "Pci"],
+        "{entries:#?}"
+    );
+
+    assert!(entries[first_service].pinned);
+    assert!(entries[first_service]
+        .reason
+        .ends_with(", pinned after the kernel binaries"));
+    assert!(!entries.last().unwrap().pinned);
+}
+
+#[test]
+fn test_pin_services_imports() {
+    let system_root = TestSystemRoot::new(&pin_hive());
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    system_root.write_file("System32\\drivers\\ntfs.sys", &PeBuilder::new().to_bytes());
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("helper.dll").to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\beep.sys",
+        &PeBuilder::new().import("helper.dll").to_bytes(),
+    );
+    system_root.write_file("System32\\helper.dll", &PeBuilder::new().to_bytes());
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .pin_services(vec!["Beep".to_string()])
+        .get()
+        .unwrap();
+
+    // The imports of the pinned service follow it, before all unpinned services.
+    assert_eq!(names(&entries), ["Beep", "helper.dll", "Pci", "ntfs"]);
+}
+
+#[test]
+fn test_pin_unknown_service() {
+    let system_root = TestSystemRoot::new(&pin_hive());
+    let error = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_imports(false)
+        .pin_services(vec!["Missing".to_string()])
+        .get()
+        .err()
+        .unwrap();
+
+    assert!(
+        format!("{error:#}").contains("Cannot pin service \"Missing\""),
+        "{error:#}"
+    );
+}
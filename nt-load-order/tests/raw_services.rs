@@ -0,0 +1,69 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, RegistryValueData};
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_raw_services() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), Some(2));
+    let pci = service_key("Pci");
+    hive.set_dword(&pci, "Type", 1);
+    hive.set_dword(&pci, "ErrorControl", 3);
+    hive.set_multi_sz(&pci, "DependOnService", &["Acpi", "Wdf01000"]);
+    hive.set_qword(&pci, "InstallTime", 0x1234_5678_9abc_def0);
+    hive.set_binary(&pci, "Blob", &[1, 2, 3]);
+    hive.set_binary(&pci, "BigBlob", &[0; 20000]);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let services = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .get_raw_services()
+        .unwrap();
+
+    // Services that are not loaded at boot are returned as well.
+    let ntfs = services
+        .iter()
+        .find(|service| service.name == "Ntfs")
+        .unwrap();
+    assert_eq!(ntfs.start, Some(3));
+    assert_eq!(ntfs.group.as_deref(), Some("Boot File System"));
+    assert_eq!(ntfs.tag, None);
+
+    let pci = services
+        .iter()
+        .find(|service| service.name == "Pci")
+        .unwrap();
+    assert_eq!(pci.start, Some(0));
+    assert_eq!(pci.group.as_deref(), Some("Boot Bus Extender"));
+    assert_eq!(pci.tag, Some(2));
+    assert_eq!(
+        pci.image_path.as_deref(),
+        Some("System32\\drivers\\pci.sys")
+    );
+    assert_eq!(pci.service_type, Some(1));
+    assert_eq!(pci.error_control, Some(3));
+    assert_eq!(pci.depend_on_service, ["Acpi", "Wdf01000"]);
+    assert!(pci.depend_on_group.is_empty());
+    assert_eq!(
+        pci.value("installtime"),
+        Some(&RegistryValueData::Qword(0x1234_5678_9abc_def0))
+    );
+    assert_eq!(
+        pci.value("Blob"),
+        Some(&RegistryValueData::Binary(vec![1, 2, 3]))
+    );
+
+    // A value that cannot be read doesn't prevent reading the remaining ones.
+    assert!(
+        matches!(
+            pci.value("BigBlob"),
+            Some(RegistryValueData::Unreadable(error)) if error.contains("big data")
+        ),
+        "{pci:#?}"
+    );
+}
@@ -0,0 +1,108 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{
+    add_boot_service, group_order_list_data, system_hive, TestSystemRoot, CONTROL_SET,
+};
+
+#[test]
+fn test_get_registry_info() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Zeta", Some("Boot Bus Extender"), Some(2));
+    add_boot_service(&mut hive, "Alpha", Some("Filter"), None);
+    add_boot_service(&mut hive, "Beta", Some("Boot Bus Extender"), Some(1));
+    let system_root = TestSystemRoot::new(&hive);
+
+    let registry_info = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .get_registry_info()
+        .unwrap();
+
+    // The entries are not sorted by group or tag yet.
+    let names = registry_info
+        .entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Alpha", "Beta", "Zeta", "ntfs"]);
+
+    assert_eq!(
+        registry_info.service_group_order,
+        [
+            "Early-Launch",
+            "Core Platform Extensions",
+            "Boot Bus Extender",
+            "System Bus Extender",
+            "Boot File System",
+            "Filter",
+        ]
+    );
+    let boot_bus_extender_tags = registry_info.groups["boot bus extender"]
+        .iter()
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(boot_bus_extender_tags, [1, 2, 3]);
+}
+
+#[test]
+fn test_group_order_mismatches() {
+    let mut hive = system_hive();
+    hive.set_binary(
+        &format!("{CONTROL_SET}\\Control\\GroupOrderList"),
+        "Orphan Group",
+        &group_order_list_data(&[1]),
+    );
+    let system_root = TestSystemRoot::new(&hive);
+
+    let registry_info = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .get_registry_info()
+        .unwrap();
+    let mismatches = registry_info.group_order_mismatches();
+
+    assert_eq!(
+        mismatches.only_in_service_group_order,
+        [
+            "Early-Launch",
+            "Core Platform Extensions",
+            "System Bus Extender",
+            "Boot File System",
+            "Filter",
+        ]
+    );
+    assert_eq!(mismatches.only_in_group_order_list, ["orphan group"]);
+}
+
+#[test]
+fn test_orphan_group_services() {
+    let mut hive = system_hive();
+    hive.set_binary(
+        &format!("{CONTROL_SET}\\Control\\GroupOrderList"),
+        "Orphan Group",
+        &group_order_list_data(&[1]),
+    );
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "Typo", Some("Boot Bus Extendr"), Some(1));
+    add_boot_service(&mut hive, "Listed", Some("Orphan Group"), Some(1));
+    add_boot_service(&mut hive, "Spaced", Some(" FILTER "), None);
+    add_boot_service(&mut hive, "Old", Some("Base"), None);
+    add_boot_service(&mut hive, "Ungrouped", None, None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let registry_info = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .get_registry_info()
+        .unwrap();
+
+    assert_eq!(
+        registry_info.orphan_group_services(),
+        [
+            ("Old".to_string(), "Base".to_string()),
+            ("Typo".to_string(), "Boot Bus Extendr".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,47 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_registry_only_without_binaries() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Acpi", Some("Core Platform Extensions"), None);
+    add_boot_service(&mut hive, "Wdf01000", Some("Early-Launch"), None);
+    add_boot_service(&mut hive, "Fltmgr", Some("Filter"), None);
+
+    // The system root only contains the SYSTEM hive, but none of the referenced binaries.
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(Some("kdcom".to_string()))
+        .cpu_vendor(Some("GenuineIntel".to_string()))
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    let image_paths = entries
+        .iter()
+        .map(|entry| entry.image_path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        image_paths,
+        [
+            "System32\\ntoskrnl.exe",
+            "System32\\hal.dll",
+            "System32\\kdcom.dll",
+            "System32\\mcupdate_GenuineIntel.dll",
+            "System32\\drivers\\wdf01000.sys",
+            "System32\\drivers\\acpi.sys",
+            "System32\\drivers\\pci.sys",
+            "System32\\drivers\\ntfs.sys",
+            "System32\\drivers\\fltmgr.sys",
+        ]
+    );
+}
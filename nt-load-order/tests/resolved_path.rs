@@ -0,0 +1,61 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_resolved_path() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Acpi", Some("Core"), None);
+    add_boot_service(&mut hive, "Beep", None, None);
+    hive.set_sz(
+        &service_key("Acpi"),
+        "ImagePath",
+        "\\SystemRoot\\System32\\drivers\\acpi.sys",
+    );
+    hive.set_sz(&service_key("Beep"), "ImagePath", "D:\\Drivers\\beep.sys");
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let entry = |name: &str| entries.iter().find(|entry| entry.name == name).unwrap();
+    let drive_mappings = HashMap::from([('D', "/mnt/d".to_string())]);
+
+    let drivers = Path::new(&system_root.path())
+        .join("System32")
+        .join("drivers");
+    assert_eq!(
+        entry("Pci").resolved_path(&system_root.path(), Some(&HashMap::new())),
+        Some(drivers.join("pci.sys"))
+    );
+    assert_eq!(
+        entry("Acpi").resolved_path(&system_root.path(), Some(&HashMap::new())),
+        Some(drivers.join("acpi.sys"))
+    );
+
+    // Absolute paths are resolved via the drive mappings on a target system and kept as they are on the local system.
+    let beep = entry("Beep");
+    assert_eq!(
+        beep.resolved_path(&system_root.path(), Some(&HashMap::new())),
+        None
+    );
+    assert_eq!(
+        beep.resolved_path(&system_root.path(), Some(&drive_mappings)),
+        Some(Path::new("/mnt/d").join("Drivers").join("beep.sys"))
+    );
+    assert_eq!(
+        beep.resolved_path(&system_root.path(), None),
+        Some(Path::new("D:\\Drivers\\beep.sys").to_path_buf())
+    );
+}
@@ -0,0 +1,123 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntry, SafeBootMode};
+
+use crate::common::{add_boot_service, system_hive, HiveBuilder, TestSystemRoot};
+
+fn safe_boot_hive() -> HiveBuilder {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "ACPI", Some("Core"), None);
+    add_boot_service(&mut hive, "pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "NDIS", Some("NDIS Wrapper"), None);
+    add_boot_service(&mut hive, "Beep", None, None);
+
+    let safe_boot = format!("{}\\Control\\SafeBoot", common::CONTROL_SET);
+    for mode in ["Minimal", "Network"] {
+        hive.set_sz(&format!("{safe_boot}\\{mode}\\ACPI"), "", "Driver");
+        hive.set_sz(
+            &format!("{safe_boot}\\{mode}\\Boot Bus Extender"),
+            "",
+            "Driver Group",
+        );
+        hive.set_sz(
+            &format!("{safe_boot}\\{mode}\\Boot File System"),
+            "",
+            "Driver Group",
+        );
+    }
+    hive.set_sz(&format!("{safe_boot}\\Network\\NDIS"), "", "Driver");
+
+    hive
+}
+
+fn get_entries(safe_boot: Option<SafeBootMode>) -> Vec<NtLoadOrderEntry> {
+    let system_root = TestSystemRoot::new(&safe_boot_hive());
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .safe_boot(safe_boot)
+        .get()
+        .unwrap()
+}
+
+fn names(entries: &[NtLoadOrderEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.name.as_str()).collect()
+}
+
+#[test]
+fn test_safe_boot_disabled() {
+    let entries = get_entries(None);
+    let names = names(&entries);
+
+    for name in ["ACPI", "pci", "NDIS", "Beep", "ntfs"] {
+        assert!(names.contains(&name), "{name} is missing in {names:?}");
+    }
+}
+
+#[test]
+fn test_safe_boot_minimal() {
+    let entries = get_entries(Some(SafeBootMode::Minimal));
+    assert_eq!(names(&entries), ["ACPI", "pci", "ntfs"]);
+
+    // Services are kept via their own name or via their group.
+    assert!(entries[0]
+        .reason
+        .contains("kept in Safe Mode via \"SafeBoot\\Minimal\\ACPI\""));
+    assert!(entries[1]
+        .reason
+        .contains("kept in Safe Mode via \"SafeBoot\\Minimal\\Boot Bus Extender\""));
+}
+
+#[test]
+fn test_safe_boot_network() {
+    let entries = get_entries(Some(SafeBootMode::Network));
+    let names = names(&entries);
+
+    assert!(names.contains(&"NDIS"), "{names:?}");
+    assert!(!names.contains(&"Beep"), "{names:?}");
+}
+
+#[test]
+fn test_missing_safe_boot_key() {
+    let system_root = TestSystemRoot::new(&system_hive());
+    let error = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .safe_boot(Some(SafeBootMode::Minimal))
+        .get()
+        .err()
+        .unwrap();
+
+    assert!(
+        format!("{error:#}").contains("SafeBoot\\Minimal"),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn test_safe_boot_group_name_spelling() {
+    let mut hive = safe_boot_hive();
+    add_boot_service(&mut hive, "Spaced", Some(" boot bus extender "), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .safe_boot(Some(SafeBootMode::Minimal))
+        .get()
+        .unwrap();
+
+    // The group is matched like in the other ordering lists (trimmed and case-insensitive).
+    let spaced = entries.iter().find(|entry| entry.name == "Spaced").unwrap();
+    assert!(
+        spaced
+            .reason
+            .contains("kept in Safe Mode via \"SafeBoot\\Minimal\\boot bus extender\""),
+        "{}",
+        spaced.reason
+    );
+}
@@ -0,0 +1,46 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_service_dll() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "SvcHosted", None, None);
+    hive.set_sz(
+        &service_key("SvcHosted"),
+        "ImagePath",
+        "%SystemRoot%\\System32\\svchost.exe -k netsvcs",
+    );
+    hive.set_sz(
+        &format!("{}\\Parameters", service_key("SvcHosted")),
+        "ServiceDll",
+        "%SystemRoot%\\System32\\svchosted.dll",
+    );
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let entry = entries
+        .iter()
+        .find(|entry| entry.name == "SvcHosted")
+        .unwrap();
+
+    // The DLL is the relevant image, made relative to the system root.
+    assert_eq!(entry.image_path, "System32\\svchosted.dll");
+    assert!(
+        entry.reason.ends_with(
+            ", hosted by \"%SystemRoot%\\System32\\svchost.exe -k netsvcs\" via \"ServiceDll\""
+        ),
+        "{}",
+        entry.reason
+    );
+}
@@ -0,0 +1,59 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntryKind};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_service_also_imported() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Pcw", Some("Filter"), Some(5));
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .get()
+        .unwrap();
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+
+    // "Pcw" is loaded as an import of "Pci", long before its "Filter" group would be loaded.
+    assert_eq!(names, ["ntoskrnl", "hal", "Pci", "Pcw", "ntfs"]);
+
+    let pcw = &entries[3];
+    assert_eq!(pcw.kind, NtLoadOrderEntryKind::Service);
+    assert_eq!(pcw.group.as_ref().unwrap().display_name, "Filter");
+    assert_eq!(pcw.tag, Some(5));
+    assert_eq!(
+        pcw.reason,
+        "Boot Driver via its \"Start\" value; also imported by \"System32\\drivers\\pci.sys\""
+    );
+    assert!(pcw.signed_embedded.is_some());
+}
@@ -0,0 +1,46 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_service_type() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "FltMgr", Some("Filter"), None);
+    hive.set_dword(&service_key("Pci"), "Type", 1);
+    hive.set_dword(&service_key("Ntfs"), "Type", 2);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let service_types = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.name.as_str(),
+                entry.service_type,
+                entry.service_type_name(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        service_types,
+        [
+            ("ntoskrnl", None, None),
+            ("hal", None, None),
+            ("kdcom", None, None),
+            ("Pci", Some(1), Some("Kernel Driver")),
+            ("ntfs", Some(2), Some("File System Driver")),
+            ("FltMgr", None, None),
+        ]
+    );
+}
@@ -0,0 +1,75 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_signed_embedded() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\ntoskrnl.exe",
+        &PeBuilder::new().embedded_signature().to_bytes(),
+    );
+    system_root.write_file("System32\\hal.dll", &PeBuilder::new().to_bytes());
+    system_root.write_file("System32\\kdcom.dll", &PeBuilder::new().to_bytes());
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new()
+            .import("pcw.sys")
+            .embedded_signature()
+            .to_bytes(),
+    );
+    system_root.write_file("System32\\drivers\\pcw.sys", &PeBuilder::new().to_bytes());
+    system_root.write_file(
+        "System32\\drivers\\ntfs.sys",
+        &PeBuilder::new().embedded_signature().to_bytes(),
+    );
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .get()
+        .unwrap();
+    let signatures = entries
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.signed_embedded))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        signatures,
+        [
+            ("ntoskrnl", Some(true)),
+            ("hal", Some(false)),
+            ("kdcom", Some(false)),
+            ("Pci", Some(true)),
+            ("pcw.sys", Some(false)),
+            ("ntfs", Some(true)),
+        ]
+    );
+}
+
+#[test]
+fn test_signed_embedded_without_imports() {
+    let system_root = TestSystemRoot::new(&system_hive());
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    assert!(entries.iter().all(|entry| entry.signed_embedded.is_none()));
+}
@@ -0,0 +1,67 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use indexmap::IndexSet;
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntry};
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+fn names(entries: &[NtLoadOrderEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.name.as_str()).collect()
+}
+
+#[test]
+fn test_sort() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Beep", None, None);
+    add_boot_service(&mut hive, "BusB", Some("Boot Bus Extender"), Some(2));
+    add_boot_service(&mut hive, "BusA", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "Elam", Some("Early-Launch"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false);
+    let mut registry_info = nt_load_order.clone().get_registry_info().unwrap();
+
+    // Sorting the registry info gives the same result as the full analysis without kernel binaries and imports.
+    let expected = nt_load_order.clone().get().unwrap();
+    assert_eq!(nt_load_order.sort(registry_info.clone()), expected);
+    assert_eq!(names(&expected), ["Elam", "BusA", "BusB", "ntfs", "Beep"]);
+
+    // The input can be modified freely without touching the registry.
+    registry_info
+        .groups
+        .insert("boot bus extender".to_string(), IndexSet::from([2, 1]));
+    let entries = nt_load_order.sort(registry_info);
+    assert_eq!(names(&entries), ["Elam", "BusB", "BusA", "ntfs", "Beep"]);
+}
+
+#[test]
+fn test_reverse_registry_order() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Beep", None, None);
+    add_boot_service(&mut hive, "Null", None, None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let get_names = |reverse_registry_order| {
+        let entries = NtLoadOrder::new()
+            .system_root(Some(system_root.path()))
+            .add_kernel_binaries(false)
+            .add_imports(false)
+            .reverse_registry_order(reverse_registry_order)
+            .get()
+            .unwrap();
+        entries
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect::<Vec<_>>()
+    };
+
+    // Untagged services without a group keep the reversed order of their service keys.
+    assert_eq!(get_names(true), ["ntfs", "Null", "Beep"]);
+    assert_eq!(get_names(false), ["ntfs", "Beep", "Null"]);
+}
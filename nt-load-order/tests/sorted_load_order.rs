@@ -0,0 +1,51 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_get_sorted() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in [
+        "System32\\ntoskrnl.exe",
+        "System32\\hal.dll",
+        "System32\\drivers\\ntfs.sys",
+        "System32\\drivers\\pcw.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("pcw.sys").to_bytes(),
+    );
+
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None);
+    let sorted = nt_load_order.clone().get_sorted().unwrap();
+
+    assert_eq!(
+        sorted.entries(),
+        nt_load_order.clone().add_imports(false).get().unwrap()
+    );
+
+    // Adding the imports repeatedly gives the same result as a full run.
+    let full = nt_load_order.get().unwrap();
+    assert_eq!(sorted.with_imports().unwrap(), full);
+    assert_eq!(sorted.with_imports().unwrap(), full);
+    assert_eq!(full.len(), sorted.entries().len() + 1);
+}
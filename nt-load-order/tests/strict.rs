@@ -0,0 +1,77 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, service_key, system_hive, HiveBuilder, TestSystemRoot};
+
+fn get(hive: &HiveBuilder, strict: bool) -> anyhow::Result<Vec<String>> {
+    let system_root = TestSystemRoot::new(hive);
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .strict(strict)
+        .get()?;
+
+    Ok(entries.into_iter().map(|entry| entry.name).collect())
+}
+
+#[test]
+fn test_strict_well_formed() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), Some(1));
+
+    assert_eq!(get(&hive, true).unwrap(), ["Pci", "ntfs"]);
+}
+
+#[test]
+fn test_strict_invalid_start() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    add_boot_service(&mut hive, "Beep", None, None);
+    hive.set_sz(&service_key("Beep"), "Start", "0");
+
+    // The service is skipped without strict mode.
+    assert_eq!(get(&hive, false).unwrap(), ["Pci", "ntfs"]);
+
+    let error = get(&hive, true).unwrap_err();
+    assert!(
+        format!("{error:#}").contains("\"Start\" of \"Beep\" is not a valid REG_DWORD value"),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn test_strict_invalid_tag() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    hive.set_binary(&service_key("Pci"), "Tag", &[1]);
+
+    assert!(get(&hive, false).is_ok());
+
+    let error = get(&hive, true).unwrap_err();
+    assert!(
+        format!("{error:#}").contains("\"Tag\" of \"Pci\" is not a valid REG_DWORD value"),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn test_strict_warning() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", None, None);
+    hive.set_multi_sz(&service_key("Pci"), "Group", &["Boot Bus Extender"]);
+
+    assert!(get(&hive, false).is_ok());
+
+    let error = get(&hive, true).unwrap_err();
+    assert!(
+        format!("{error:#}").contains(
+            "Service \"Pci\" is malformed: \"Group\" is a REG_MULTI_SZ value, using its first string"
+        ),
+        "{error:#}"
+    );
+}
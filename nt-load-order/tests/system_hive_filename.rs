@@ -0,0 +1,46 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_system_hive_filename() {
+    let system_root = TestSystemRoot::new(&system_hive());
+
+    let mut backup_hive = system_hive();
+    add_boot_service(&mut backup_hive, "Pci", Some("Boot Bus Extender"), None);
+    system_root.write_file("System32\\config\\SYSTEM.SAV", &backup_hive.to_bytes());
+
+    let names = |system_hive_filename: Option<&str>| {
+        NtLoadOrder::new()
+            .system_root(Some(system_root.path()))
+            .system_hive_filename(system_hive_filename.map(str::to_string))
+            .add_kernel_binaries(false)
+            .add_imports(false)
+            .get()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(None), ["ntfs"]);
+    assert_eq!(names(Some("SYSTEM.SAV")), ["Pci", "ntfs"]);
+}
+
+#[test]
+fn test_missing_system_hive_filename() {
+    let system_root = TestSystemRoot::new(&system_hive());
+    let error = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .system_hive_filename(Some("SYSTEM.ALT".to_string()))
+        .get()
+        .err()
+        .unwrap();
+
+    assert!(format!("{error:#}").contains("SYSTEM.ALT"), "{error:#}");
+}
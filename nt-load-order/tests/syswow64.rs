@@ -0,0 +1,61 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_syswow64() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    system_root.write_file("System32\\drivers\\ntfs.sys", &PeBuilder::new().to_bytes());
+
+    // The 64-bit driver imports a 32-bit legacy.dll and a 64-bit helper.dll.
+    // The 32-bit legacy.dll imports helper.dll as well, which must come from "SysWOW64".
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new()
+            .import("legacy.dll")
+            .import("helper.dll")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\legacy.dll",
+        &PeBuilder::new().pe32().import("helper.dll").to_bytes(),
+    );
+    system_root.write_file("System32\\helper.dll", &PeBuilder::new().to_bytes());
+    system_root.write_file("SysWOW64\\helper.dll", &PeBuilder::new().pe32().to_bytes());
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .get()
+        .unwrap();
+    let image_paths = entries
+        .iter()
+        .map(|entry| (entry.image_path.as_str(), entry.machine_name()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        image_paths,
+        [
+            ("System32\\drivers\\pci.sys", Some("x64")),
+            ("SysWOW64\\helper.dll", Some("x86")),
+            ("System32\\legacy.dll", Some("x86")),
+            ("System32\\helper.dll", Some("x64")),
+            ("System32\\drivers\\ntfs.sys", Some("x64")),
+        ]
+    );
+}
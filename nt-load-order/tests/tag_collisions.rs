@@ -0,0 +1,45 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_tag_collisions() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "isapnp", Some("Boot Bus Extender"), Some(2));
+    add_boot_service(&mut hive, "pci", Some("Boot Bus Extender"), Some(2));
+    add_boot_service(&mut hive, "vdrvroot", Some("Boot Bus Extender"), Some(3));
+    // The same tag in another group is no collision.
+    add_boot_service(&mut hive, "volmgr", Some("System Bus Extender"), Some(2));
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let warnings = |name: &str| {
+        entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .unwrap()
+            .warnings
+            .clone()
+    };
+
+    assert_eq!(
+        warnings("isapnp"),
+        ["Tag 2 in group \"Boot Bus Extender\" is shared with \"pci\""]
+    );
+    assert_eq!(
+        warnings("pci"),
+        ["Tag 2 in group \"Boot Bus Extender\" is shared with \"isapnp\""]
+    );
+    assert!(warnings("vdrvroot").is_empty());
+    assert!(warnings("volmgr").is_empty());
+}
@@ -0,0 +1,67 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::path::Path;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::transaction_log::dirty_hive_with_log;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+fn service_names(system_root: &TestSystemRoot) -> Vec<String> {
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect()
+}
+
+/// Creates a system root with a dirty SYSTEM hive, whose "Pci" service is only in the transaction log.
+fn dirty_system_root() -> TestSystemRoot {
+    let flushed = system_hive();
+    let mut current = system_hive();
+    add_boot_service(&mut current, "Pci", Some("Boot Bus Extender"), None);
+
+    let (dirty_hive, log) = dirty_hive_with_log(&flushed.to_bytes(), &current.to_bytes());
+    let system_root = TestSystemRoot::new(&flushed);
+    system_root.write_file("System32\\config\\SYSTEM", &dirty_hive);
+    system_root.write_file("System32\\config\\SYSTEM.LOG1", &log);
+    system_root
+}
+
+#[cfg(feature = "transaction-logs")]
+#[test]
+fn test_replay_transaction_log() {
+    let system_root = dirty_system_root();
+    assert_eq!(service_names(&system_root), ["Pci", "ntfs"]);
+}
+
+#[cfg(not(feature = "transaction-logs"))]
+#[test]
+fn test_last_flushed_state() {
+    let system_root = dirty_system_root();
+    assert_eq!(service_names(&system_root), ["ntfs"]);
+}
+
+#[test]
+fn test_corrupt_transaction_log() {
+    let system_root = dirty_system_root();
+
+    // Damage the log entry, so that its hash no longer matches and it is ignored.
+    let log_path = Path::new(&system_root.path())
+        .join("System32")
+        .join("config")
+        .join("SYSTEM.LOG1");
+    let mut log = std::fs::read(log_path).unwrap();
+    let last = log.len() - 1;
+    log[last] ^= 0xff;
+    system_root.write_file("System32\\config\\SYSTEM.LOG1", &log);
+
+    assert_eq!(service_names(&system_root), ["ntfs"]);
+}
@@ -0,0 +1,119 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{LoadOrderNode, NtLoadOrder};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+/// Formats the tree as indented names, one line per node.
+fn indented_names(nodes: &[LoadOrderNode], depth: usize, lines: &mut Vec<String>) {
+    for node in nodes {
+        lines.push(format!("{}{}", "  ".repeat(depth), node.entry.name));
+        indented_names(&node.children, depth + 1, lines);
+    }
+}
+
+#[test]
+fn test_get_tree() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\ntoskrnl.exe",
+        &PeBuilder::new().import("PSHED.dll").to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new()
+            .import("pcw.sys")
+            .import("msrpc.sys")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\pcw.sys",
+        &PeBuilder::new().import("cng.sys").to_bytes(),
+    );
+    for path in [
+        "System32\\hal.dll",
+        "System32\\PSHED.dll",
+        "System32\\drivers\\cng.sys",
+        "System32\\drivers\\msrpc.sys",
+        "System32\\drivers\\ntfs.sys",
+    ] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+
+    let nt_load_order = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None);
+
+    let flat_names = nt_load_order
+        .clone()
+        .get()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        flat_names,
+        [
+            "ntoskrnl",
+            "hal",
+            "PSHED.dll",
+            "Pci",
+            "cng.sys",
+            "pcw.sys",
+            "msrpc.sys",
+            "ntfs"
+        ]
+    );
+
+    let tree = nt_load_order.clone().get_tree().unwrap();
+    let mut lines = Vec::new();
+    indented_names(&tree, 0, &mut lines);
+    assert_eq!(
+        lines,
+        [
+            "ntoskrnl",
+            "  PSHED.dll",
+            "hal",
+            "Pci",
+            "  pcw.sys",
+            "    cng.sys",
+            "  msrpc.sys",
+            "ntfs",
+        ]
+    );
+
+    let iterated_names = tree
+        .iter()
+        .flat_map(LoadOrderNode::iter)
+        .map(|node| node.entry.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        iterated_names,
+        [
+            "ntoskrnl",
+            "PSHED.dll",
+            "hal",
+            "Pci",
+            "pcw.sys",
+            "cng.sys",
+            "msrpc.sys",
+            "ntfs"
+        ]
+    );
+
+    // The result types can be compared, so repeated runs can be checked for equality.
+    assert_eq!(nt_load_order.get_tree().unwrap(), tree);
+}
@@ -0,0 +1,36 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{unload_order, NtLoadOrder};
+
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+#[test]
+fn test_unload_order() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "pci", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "isapnp", Some("Boot Bus Extender"), Some(2));
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_imports(false)
+        .get()
+        .unwrap();
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["ntoskrnl", "hal", "kdcom", "pci", "isapnp", "ntfs"]);
+
+    let unload_names = unload_order(&entries)
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        unload_names,
+        ["ntfs", "isapnp", "pci", "ntoskrnl", "hal", "kdcom"]
+    );
+}
@@ -0,0 +1,94 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{NtLoadOrder, NtLoadOrderEntryKind};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, system_hive, TestSystemRoot};
+
+fn system_root_with_missing_imports() -> TestSystemRoot {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    for path in ["System32\\ntoskrnl.exe", "System32\\hal.dll"] {
+        system_root.write_file(path, &PeBuilder::new().to_bytes());
+    }
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new()
+            .import("missing.sys")
+            .import("api-ms-win-core-foo-l1-1-0.dll")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\ntfs.sys",
+        &PeBuilder::new().import("missing.sys").to_bytes(),
+    );
+
+    system_root
+}
+
+#[test]
+fn test_strict_imports() {
+    let system_root = system_root_with_missing_imports();
+    let error = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .get()
+        .err()
+        .unwrap();
+
+    assert!(format!("{error:#}").contains("missing.sys"), "{error:#}");
+}
+
+#[test]
+fn test_unresolved_imports() {
+    let system_root = system_root_with_missing_imports();
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .strict_imports(false)
+        .get()
+        .unwrap();
+
+    let unresolved = entries
+        .iter()
+        .filter(|entry| entry.kind == NtLoadOrderEntryKind::UnresolvedImport)
+        .map(|entry| {
+            (
+                entry.name.as_str(),
+                entry.image_path.as_str(),
+                entry.reason.as_str(),
+                entry.via_api_set.as_deref(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // "missing.sys" is only added once, even though "ntfs.sys" imports it as well.
+    assert_eq!(
+        unresolved,
+        [
+            (
+                "missing.sys",
+                "",
+                "Unresolved import of \"missing.sys\" from \"System32\\drivers\\pci.sys\"",
+                None
+            ),
+            (
+                "foo.sys",
+                "",
+                "Unresolved import of \"foo.sys\" from \"System32\\drivers\\pci.sys\"",
+                Some("api-ms-win-core-foo-l1-1-0")
+            ),
+        ]
+    );
+}
@@ -0,0 +1,73 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::collections::HashMap;
+
+use nt_load_order::NtLoadOrder;
+
+fn validation_error(nt_load_order: NtLoadOrder) -> String {
+    nt_load_order.validate().unwrap_err().to_string()
+}
+
+#[test]
+fn test_validate_defaults() {
+    NtLoadOrder::new().validate().unwrap();
+}
+
+#[test]
+fn test_validate_drive_mappings() {
+    let drive_mappings = HashMap::from([('D', "/mnt/d".to_string())]);
+
+    let error = validation_error(NtLoadOrder::new().drive_mappings(drive_mappings.clone()));
+    assert!(error.contains("target SystemRoot"), "{error}");
+
+    NtLoadOrder::new()
+        .system_root(Some("/mnt/c/Windows".to_string()))
+        .drive_mappings(drive_mappings)
+        .validate()
+        .unwrap();
+
+    let error = validation_error(
+        NtLoadOrder::new()
+            .system_root(Some("/mnt/c/Windows".to_string()))
+            .drive_mappings(HashMap::from([('1', "/mnt/1".to_string())])),
+    );
+    assert!(error.contains("'1'"), "{error}");
+}
+
+#[test]
+fn test_validate_kd_drivers() {
+    for kd_driver in ["", "kdcom.dll", "System32\\kdcom"] {
+        let error = validation_error(NtLoadOrder::new().kd_driver(Some(kd_driver.to_string())));
+        assert!(error.contains("Invalid KD driver name"), "{error}");
+    }
+}
+
+#[test]
+fn test_validate_system_hive_filename() {
+    let error =
+        validation_error(NtLoadOrder::new().system_hive_filename(Some("SYSTEM.SAV".to_string())));
+    assert!(error.contains("target SystemRoot"), "{error}");
+
+    for system_hive_filename in ["", "config\\SYSTEM", "../SYSTEM"] {
+        let error = validation_error(
+            NtLoadOrder::new()
+                .system_root(Some("/mnt/c/Windows".to_string()))
+                .system_hive_filename(Some(system_hive_filename.to_string())),
+        );
+        assert!(error.contains("Invalid SYSTEM hive file name"), "{error}");
+    }
+}
+
+#[test]
+fn test_validate_before_get() {
+    // The error must be reported before the (non-existing) registry is opened.
+    let error = NtLoadOrder::new()
+        .system_root(Some("/nonexistent".to_string()))
+        .cpu_vendor(Some(String::new()))
+        .get()
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(error.contains("CPU vendor"), "{error}");
+}
@@ -0,0 +1,87 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{system_hive, HiveBuilder, TestSystemRoot, CONTROL_SET};
+
+#[test]
+fn test_validate_system_hive() {
+    let system_root = TestSystemRoot::new(&system_hive());
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .validate_system_hive()
+        .unwrap();
+}
+
+#[test]
+fn test_software_hive() {
+    let mut software_hive = HiveBuilder::new();
+    software_hive.set_sz(
+        "Microsoft\\Windows NT\\CurrentVersion",
+        "ProductName",
+        "Windows 11 Pro",
+    );
+    let system_root = TestSystemRoot::new(&software_hive);
+
+    let nt_load_order = NtLoadOrder::new().system_root(Some(system_root.path()));
+    let error = nt_load_order.validate_system_hive().unwrap_err();
+    assert!(
+        format!("{error:#}").contains("doesn't look like a SYSTEM hive"),
+        "{error:#}"
+    );
+
+    // The same error is reported early when getting the load order.
+    let error = nt_load_order.get().unwrap_err();
+    assert!(
+        format!("{error:#}").contains("doesn't look like a SYSTEM hive"),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn test_missing_select_key() {
+    let mut hive = system_hive();
+    hive.remove_key("Select");
+    let system_root = TestSystemRoot::new(&hive);
+
+    let error = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .validate_system_hive()
+        .unwrap_err();
+    assert!(
+        format!("{error:#}").contains("doesn't look like a SYSTEM hive"),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn test_current_control_set_other_than_001() {
+    let mut hive = HiveBuilder::new();
+    hive.set_dword("Select", "Current", 2);
+    hive.create_key("ControlSet002\\Services");
+    let system_root = TestSystemRoot::new(&hive);
+
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .validate_system_hive()
+        .unwrap();
+
+    // Without a "Current" value, only "ControlSet001" is accepted.
+    hive.remove_key("Select");
+    hive.create_key("Select");
+    let system_root = TestSystemRoot::new(&hive);
+    assert!(NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .validate_system_hive()
+        .is_err());
+
+    hive.create_key(&format!("{CONTROL_SET}\\Services"));
+    let system_root = TestSystemRoot::new(&hive);
+    NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .validate_system_hive()
+        .unwrap();
+}
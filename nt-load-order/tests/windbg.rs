@@ -0,0 +1,107 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::{to_windbg_modules, NtLoadOrder};
+
+use crate::common::pe::PeBuilder;
+use crate::common::{add_boot_service, service_key, system_hive, TestSystemRoot};
+
+#[test]
+fn test_to_windbg_modules() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), Some(1));
+    add_boot_service(&mut hive, "storahci", Some("Boot Bus Extender"), Some(2));
+    hive.set_sz(
+        &service_key("storahci"),
+        "ImagePath",
+        "\\SystemRoot\\System32\\drivers\\storahci.sys",
+    );
+    add_boot_service(&mut hive, "Wdf01000", Some("Boot Bus Extender"), Some(3));
+    hive.set_sz(
+        &service_key("Wdf01000"),
+        "ImagePath",
+        "System32\\drivers\\Wdf01000.sys",
+    );
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .kd_driver(None)
+        .sort_by_hardcoded_service_lists(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    assert_eq!(
+        to_windbg_modules(&entries),
+        "module name\n\
+         nt       \\SystemRoot\\System32\\ntoskrnl.exe\n\
+         hal      \\SystemRoot\\System32\\hal.dll\n\
+         pci      \\SystemRoot\\System32\\drivers\\pci.sys\n\
+         storahci \\SystemRoot\\System32\\drivers\\storahci.sys\n\
+         Wdf01000 \\SystemRoot\\System32\\drivers\\Wdf01000.sys\n\
+         ntfs     \\SystemRoot\\System32\\drivers\\ntfs.sys\n"
+    );
+}
+
+#[test]
+fn test_to_windbg_modules_drive_letter_path() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Vendor", None, None);
+    hive.set_sz(
+        &service_key("Vendor"),
+        "ImagePath",
+        "D:\\drivers\\vendor.sys",
+    );
+    let system_root = TestSystemRoot::new(&hive);
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()
+        .unwrap();
+
+    assert_eq!(
+        to_windbg_modules(&entries),
+        "module name\n\
+         ntfs     \\SystemRoot\\System32\\drivers\\ntfs.sys\n\
+         vendor   \\??\\D:\\drivers\\vendor.sys\n"
+    );
+}
+
+#[test]
+fn test_to_windbg_modules_skips_unresolved_imports() {
+    let mut hive = system_hive();
+    add_boot_service(&mut hive, "Pci", Some("Boot Bus Extender"), None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    system_root.write_file(
+        "System32\\apisetschema.dll",
+        &PeBuilder::new()
+            .apiset_entry("api-ms-win-core-foo-l1-1-0", "foo.sys")
+            .to_bytes(),
+    );
+    system_root.write_file(
+        "System32\\drivers\\pci.sys",
+        &PeBuilder::new().import("missing.sys").to_bytes(),
+    );
+    system_root.write_file("System32\\drivers\\ntfs.sys", &PeBuilder::new().to_bytes());
+
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .add_kernel_binaries(false)
+        .strict_imports(false)
+        .get()
+        .unwrap();
+    assert!(entries.iter().any(|entry| entry.name == "missing.sys"));
+
+    assert_eq!(
+        to_windbg_modules(&entries),
+        "module name\n\
+         pci      \\SystemRoot\\System32\\drivers\\pci.sys\n\
+         ntfs     \\SystemRoot\\System32\\drivers\\ntfs.sys\n"
+    );
+}
@@ -0,0 +1,60 @@
+// Copyright 2025 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use nt_load_order::NtLoadOrder;
+
+use crate::common::{add_boot_service, HiveBuilder, TestSystemRoot};
+
+/// Creates a reduced SYSTEM hive like found in WinPE images, with nothing but a few services
+/// in "ControlSet002".
+fn winpe_hive() -> HiveBuilder {
+    let mut hive = HiveBuilder::new();
+    hive.set_dword("Select", "Current", 2);
+
+    for name in ["Pci", "Acpi"] {
+        let key = format!("ControlSet002\\Services\\{name}");
+        let image_path = format!("System32\\drivers\\{}.sys", name.to_ascii_lowercase());
+        hive.set_dword(&key, "Start", 0);
+        hive.set_sz(&key, "ImagePath", &image_path);
+    }
+
+    hive
+}
+
+fn get_image_paths(system_root: &TestSystemRoot, winpe: bool) -> anyhow::Result<Vec<String>> {
+    let entries = NtLoadOrder::new()
+        .system_root(Some(system_root.path()))
+        .winpe(winpe)
+        .add_kernel_binaries(false)
+        .add_imports(false)
+        .get()?;
+
+    Ok(entries.into_iter().map(|entry| entry.image_path).collect())
+}
+
+#[test]
+fn test_winpe_mode() {
+    let system_root = TestSystemRoot::new(&winpe_hive());
+
+    // A regular analysis fails due to the missing keys.
+    assert!(get_image_paths(&system_root, false).is_err());
+
+    let image_paths = get_image_paths(&system_root, true).unwrap();
+    assert_eq!(
+        image_paths,
+        ["System32\\drivers\\acpi.sys", "System32\\drivers\\pci.sys"]
+    );
+}
+
+#[test]
+fn test_winpe_mode_detected_via_mini_nt() {
+    let mut hive = winpe_hive();
+    hive.create_key("ControlSet001\\Control\\MiniNT");
+    add_boot_service(&mut hive, "Pci", None, None);
+    let system_root = TestSystemRoot::new(&hive);
+
+    let image_paths = get_image_paths(&system_root, false).unwrap();
+    assert_eq!(image_paths, ["System32\\drivers\\pci.sys"]);
+}